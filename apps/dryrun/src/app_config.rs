@@ -1,3 +1,5 @@
+use std::{collections::BTreeMap, path::PathBuf};
+
 use config::{Config, Environment, File};
 use serde::Deserialize;
 
@@ -7,24 +9,191 @@ pub struct AppConfig {
     pub ethereum: EthereumConfig,
     pub conflux: ConfluxConfig,
     pub simulation: SimulationConfig,
+    #[serde(default)]
+    pub risk: RiskConfig,
     pub tracing: TracingConfig,
     pub metrics: MetricsConfig,
 }
 
 #[derive(Debug, Deserialize)]
 pub struct EthereumConfig {
+    #[serde(default)]
+    pub chains: BTreeMap<String, EthereumChainConfig>,
+    /// Legacy single-chain form, served as chain `mainnet` with chain id 1.
+    #[serde(default)]
+    pub rpc_url: Option<String>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct EthereumChainConfig {
+    pub chain_id: u64,
     pub rpc_url: String,
+    #[serde(default)]
+    pub hardforks: BTreeMap<String, HardforkActivationConfig>,
+    #[serde(default)]
+    pub native_currency: Option<NativeCurrencyConfig>,
+    #[serde(default)]
+    pub prefetch: PrefetchConfig,
+    /// Seconds between blocks. Unset keeps Ethereum's 12 second slots.
+    #[serde(default)]
+    pub block_interval_secs: Option<u64>,
+    /// EIP-1559 base fee parameters. Unset keeps Ethereum's.
+    #[serde(default)]
+    pub base_fee: Option<BaseFeeConfig>,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize)]
+pub struct BaseFeeConfig {
+    pub max_change_denominator: u128,
+    pub elasticity_multiplier: u128,
+}
+
+/// How state is loaded ahead of execution. `prestate_trace` needs an upstream
+/// that serves `debug_traceCall` with the prestate tracer.
+#[derive(Clone, Copy, Debug, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PrefetchConfig {
+    Disabled,
+    #[default]
+    AccessList,
+    PrestateTrace,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HardforkActivationConfig {
+    Block(u64),
+    Timestamp(u64),
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct NativeCurrencyConfig {
+    pub name: String,
+    pub symbol: String,
+    pub decimals: u8,
 }
 
 #[derive(Debug, Deserialize)]
 pub struct ConfluxConfig {
     pub espace_rpc_url: String,
     pub core_space_rpc_url: String,
+    #[serde(default)]
+    pub network: ConfluxNetworkConfig,
+    /// Chain identity of a `custom` network.
+    #[serde(default)]
+    pub chain: Option<ConfluxCustomChainConfig>,
+    /// Hardfork activation points. Required for `custom`; on `mainnet` and
+    /// `testnet` they replace the built-in heights.
+    #[serde(default)]
+    pub transitions: Option<ConfluxTransitionsConfig>,
+}
+
+#[derive(Clone, Copy, Debug, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ConfluxNetworkConfig {
+    #[default]
+    Mainnet,
+    Testnet,
+    Custom,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ConfluxCustomChainConfig {
+    pub core_space_chain_id: u32,
+    pub evm_chain_id: u32,
+    pub network_id: u64,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize)]
+pub struct ConfluxTransitionsConfig {
+    pub tanzanite_transition_height: u64,
+    /// Unset keeps the network's built-in value, or genesis on `custom`.
+    #[serde(default)]
+    pub bn128_enable_number: Option<u64>,
+    pub hydra_transition_number: u64,
+    pub hydra_transition_height: u64,
+    pub cip43_init_end_number: u64,
+    pub dao_vote_transition_number: u64,
+    pub dao_vote_transition_height: u64,
+    pub sigma_fix_transition_number: u64,
+    pub burn_collateral_transition_number: u64,
+    pub cip112_transition_height: u64,
+    pub base_fee_burn_transition_number: u64,
+    pub base_fee_burn_transition_height: u64,
+    pub c2_fix_transition_height: u64,
+    pub eoa_code_transition_height: u64,
 }
 
 #[derive(Debug, Deserialize)]
 pub struct SimulationConfig {
     pub max_concurrent: usize,
+    /// Upstream state reads kept per kind of read and chain. Unset uses the
+    /// built-in default.
+    #[serde(default)]
+    pub state_cache_entries: Option<usize>,
+    /// Total size of the cached values, mostly contract code, kept per kind
+    /// of read and chain. Unset uses the built-in default.
+    #[serde(default)]
+    pub state_cache_bytes: Option<usize>,
+    /// Directory of state snapshots. Unset disables recording and replay.
+    #[serde(default)]
+    pub snapshot_directory: Option<PathBuf>,
+    #[serde(default)]
+    pub snapshot_mode: SnapshotModeConfig,
+}
+
+/// `record` saves the state reads of successful simulations. `replay` runs
+/// EVM simulations against those reads without calling the upstream RPC;
+/// Conflux simulations are neither recorded nor replayed then.
+#[derive(Clone, Copy, Debug, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SnapshotModeConfig {
+    #[default]
+    Record,
+    Replay,
+}
+
+/// Overrides for the EVM risk rules. Unset fields keep the rule defaults.
+#[derive(Debug, Default, Deserialize)]
+pub struct RiskConfig {
+    #[serde(default)]
+    pub unlimited_allowance_to_eoa: RiskRuleConfig,
+    #[serde(default)]
+    pub approval_for_all_to_fresh_contract: FreshContractRuleConfig,
+    #[serde(default)]
+    pub native_value_to_codeless_target: RiskRuleConfig,
+    #[serde(default)]
+    pub ownership_transfer: RiskRuleConfig,
+    #[serde(default)]
+    pub wallet_drained: WalletDrainedRuleConfig,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct RiskRuleConfig {
+    pub enabled: Option<bool>,
+    pub severity: Option<RiskSeverityConfig>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct FreshContractRuleConfig {
+    pub enabled: Option<bool>,
+    pub severity: Option<RiskSeverityConfig>,
+    pub max_age_blocks: Option<u64>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct WalletDrainedRuleConfig {
+    pub enabled: Option<bool>,
+    pub severity: Option<RiskSeverityConfig>,
+    pub min_assets: Option<usize>,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RiskSeverityConfig {
+    Low,
+    Medium,
+    High,
 }
 
 #[derive(Debug, Deserialize)]
@@ -1,19 +1,33 @@
-use std::{io, sync::Arc};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    io,
+    num::NonZeroUsize,
+    path::Path,
+    sync::Arc,
+};
 
-use alloy::providers::RootProvider;
+use alloy::{
+    eips::eip1559::BaseFeeParams,
+    providers::{Provider, RootProvider},
+};
 use alloy_rpc_client::RpcClient;
 use conflux_provider::ConfluxProvider;
 use conflux_rpc::build_rpc_module as build_conflux_rpc_module;
 use conflux_service::ConfluxService;
 use conflux_simulation::{
-    ConfluxSimulationProvider,
-    config::ConfluxChainConfig,
+    ConfluxSimulationProvider, ConfluxSnapshotStore, ConfluxStateCache,
+    config::{ConfluxChainConfig, ConfluxTransitions},
     core_space::{CoreSpaceSimulationPreparer, CoreSpaceSimulator},
     espace::{EspaceSimulationPreparer, EspaceSimulator},
 };
 use evm_rpc::{DryrunRpcServer, RpcHandler};
-use evm_service::SimulationService;
-use evm_simulation::{EvmSimulationPreparer, EvmSimulator};
+use evm_service::{EvmChainBackend, SimulationService};
+use evm_simulation::{
+    EvmPrefetch, EvmSimulationPreparer, EvmSimulator, EvmSnapshotStore, EvmStateCache,
+    NativeMetadata, RiskConfig as EvmRiskConfig, RiskRule, RiskSeverity, SnapshotMode,
+    StateCacheLimits,
+    config::{EthereumHardfork, EvmChainConfig, ForkCondition},
+};
 use jsonrpsee::{
     RpcModule,
     server::{BatchRequestConfig, Server, ServerConfig as JsonRpcServerConfig, ServerHandle},
@@ -22,16 +36,27 @@ use jsonrpsee::{
 use simulation_tasks::SimulationTaskSet;
 use tracing::info;
 
-use crate::app_config::{AppConfig, ConfluxConfig, EthereumConfig};
+use crate::app_config::{
+    AppConfig, ConfluxConfig, ConfluxNetworkConfig, ConfluxTransitionsConfig, EthereumChainConfig,
+    EthereumConfig, HardforkActivationConfig, PrefetchConfig, RiskConfig, RiskRuleConfig,
+    RiskSeverityConfig, SimulationConfig, SnapshotModeConfig,
+};
 
 const MAX_RPC_CONNECTIONS: u32 = 100;
 const MAX_RPC_BODY_SIZE_BYTES: u32 = 10 * 1024 * 1024;
+const LEGACY_ETHEREUM_CHAIN_NAME: &str = "mainnet";
+const LEGACY_ETHEREUM_CHAIN_ID: u64 = 1;
 
 pub async fn start(
     config: &AppConfig,
     simulation_tasks: SimulationTaskSet,
 ) -> io::Result<ServerHandle> {
-    let rpc_module = build_host_rpc_module(config, simulation_tasks)?;
+    let ethereum_chains = ethereum_chains(&config.ethereum)?;
+    // Replayed simulations never reach the upstream, which may be offline.
+    if !replays_snapshots(&config.simulation) {
+        verify_ethereum_chain_ids(&ethereum_chains).await?;
+    }
+    let rpc_module = build_host_rpc_module(config, &ethereum_chains, simulation_tasks)?;
     let server_config = JsonRpcServerConfig::builder()
         .max_connections(MAX_RPC_CONNECTIONS)
         .max_request_body_size(MAX_RPC_BODY_SIZE_BYTES)
@@ -52,12 +77,38 @@ pub async fn start(
 
 fn build_host_rpc_module(
     config: &AppConfig,
+    ethereum_chains: &BTreeMap<String, EthereumChainConfig>,
     simulation_tasks: SimulationTaskSet,
 ) -> io::Result<RpcModule<()>> {
     let mut rpc_module = RpcModule::new(());
+    let state_cache_limits = state_cache_limits(&config.simulation)?;
+    let evm_snapshot_store = config
+        .simulation
+        .snapshot_directory
+        .as_deref()
+        .map(|directory| EvmSnapshotStore::new(directory, snapshot_mode(&config.simulation)));
+    // Conflux simulations only record snapshots.
+    let conflux_snapshot_directory = config
+        .simulation
+        .snapshot_directory
+        .as_deref()
+        .filter(|_| !replays_snapshots(&config.simulation));
 
-    add_evm_rpc_module(&mut rpc_module, &config.ethereum, simulation_tasks.clone())?;
-    add_conflux_rpc_module(&mut rpc_module, &config.conflux, simulation_tasks)?;
+    add_evm_rpc_module(
+        &mut rpc_module,
+        ethereum_chains,
+        &config.risk,
+        state_cache_limits,
+        evm_snapshot_store.as_ref(),
+        simulation_tasks.clone(),
+    )?;
+    add_conflux_rpc_module(
+        &mut rpc_module,
+        &config.conflux,
+        state_cache_limits,
+        conflux_snapshot_directory,
+        simulation_tasks,
+    )?;
     rpc_module
         .register_method("dryrun_health", |_, _, _| Ok::<_, ErrorObjectOwned>("ok"))
         .map_err(|error| startup_error(format!("failed to register health RPC method: {error}")))?;
@@ -65,22 +116,110 @@ fn build_host_rpc_module(
     Ok(rpc_module)
 }
 
+/// The configured EVM chains, with a legacy `ethereum.rpc_url` served as the
+/// `mainnet` chain.
+fn ethereum_chains(config: &EthereumConfig) -> io::Result<BTreeMap<String, EthereumChainConfig>> {
+    let mut chains = config.chains.clone();
+
+    if let Some(rpc_url) = &config.rpc_url {
+        if chains.contains_key(LEGACY_ETHEREUM_CHAIN_NAME) {
+            return Err(configuration_error(
+                "ethereum.rpc_url conflicts with ethereum.chains.mainnet",
+            ));
+        }
+
+        chains.insert(
+            LEGACY_ETHEREUM_CHAIN_NAME.to_owned(),
+            EthereumChainConfig {
+                chain_id: LEGACY_ETHEREUM_CHAIN_ID,
+                rpc_url: rpc_url.clone(),
+                hardforks: BTreeMap::new(),
+                native_currency: None,
+                prefetch: PrefetchConfig::default(),
+                block_interval_secs: None,
+                base_fee: None,
+            },
+        );
+    }
+
+    if chains.is_empty() {
+        return Err(configuration_error(
+            "ethereum.chains must configure at least one chain",
+        ));
+    }
+
+    Ok(chains)
+}
+
+/// Refuses to start when an upstream serves a different chain than the one
+/// configured for it.
+async fn verify_ethereum_chain_ids(
+    chains: &BTreeMap<String, EthereumChainConfig>,
+) -> io::Result<()> {
+    for (name, chain_config) in chains {
+        let chain_id = create_ethereum_provider(name, chain_config)?
+            .get_chain_id()
+            .await
+            .map_err(|error| {
+                startup_error(format!(
+                    "failed to fetch eth_chainId for ethereum.chains.{name}: {error}"
+                ))
+            })?;
+
+        if chain_id != chain_config.chain_id {
+            return Err(configuration_error(format!(
+                "ethereum.chains.{name} is configured with chain_id {} but its RPC reports {chain_id}",
+                chain_config.chain_id
+            )));
+        }
+    }
+
+    Ok(())
+}
+
 fn add_evm_rpc_module(
     rpc_module: &mut RpcModule<()>,
-    config: &EthereumConfig,
+    chains: &BTreeMap<String, EthereumChainConfig>,
+    risk_config: &RiskConfig,
+    state_cache_limits: StateCacheLimits,
+    snapshot_store: Option<&EvmSnapshotStore>,
     simulation_tasks: SimulationTaskSet,
 ) -> io::Result<()> {
-    let ethereum_provider = create_ethereum_provider(config)?;
-    let evm_simulator = Arc::new(EvmSimulator::new(
-        ethereum_provider.clone(),
-        tokio::runtime::Handle::current(),
-    ));
-    let evm_preparer = Arc::new(EvmSimulationPreparer::new(ethereum_provider));
-    let simulation_service = Arc::new(SimulationService::new(
-        evm_preparer,
-        evm_simulator,
-        simulation_tasks,
-    ));
+    let runtime_handle = tokio::runtime::Handle::current();
+    let risk = create_risk_config(risk_config);
+    let mut chain_ids = BTreeSet::new();
+    let mut backends = Vec::with_capacity(chains.len());
+
+    for (name, chain_config) in chains {
+        if !chain_ids.insert(chain_config.chain_id) {
+            return Err(configuration_error(format!(
+                "ethereum.chains.{name} reuses chain_id {}",
+                chain_config.chain_id
+            )));
+        }
+
+        let chain = create_evm_chain_config(name, chain_config)?;
+        let provider = create_ethereum_provider(name, chain_config)?;
+        let state_cache = EvmStateCache::new(state_cache_limits);
+        let mut evm_preparer = EvmSimulationPreparer::new(provider.clone(), chain.clone())
+            .with_state_cache(state_cache.clone())
+            .with_prefetch(create_prefetch(chain_config.prefetch));
+        let mut evm_simulator = EvmSimulator::new(provider, runtime_handle.clone(), chain)
+            .with_risk_config(risk.clone())
+            .with_state_cache(state_cache);
+        if let Some(snapshot_store) = snapshot_store {
+            if snapshot_store.mode() == SnapshotMode::Replay {
+                evm_preparer = evm_preparer.with_prefetch(EvmPrefetch::Disabled);
+            }
+            evm_preparer = evm_preparer.with_snapshot_store(snapshot_store.clone());
+            evm_simulator = evm_simulator.with_snapshot_store(snapshot_store.clone());
+        }
+        let evm_preparer = Arc::new(evm_preparer);
+        let evm_simulator = Arc::new(evm_simulator);
+        backends.push(EvmChainBackend::new(evm_preparer, evm_simulator));
+    }
+
+    let simulation_service = Arc::new(SimulationService::new(backends, simulation_tasks));
 
     rpc_module
         .merge(RpcHandler::new(simulation_service).into_rpc())
@@ -90,22 +229,34 @@ fn add_evm_rpc_module(
 fn add_conflux_rpc_module(
     rpc_module: &mut RpcModule<()>,
     config: &ConfluxConfig,
+    state_cache_limits: StateCacheLimits,
+    snapshot_directory: Option<&Path>,
     simulation_tasks: SimulationTaskSet,
 ) -> io::Result<()> {
-    let conflux_chain = ConfluxChainConfig::mainnet();
+    let conflux_chain = create_conflux_chain_config(config)?;
     let core_space_address_network = conflux_chain.core_space_address_network;
     let conflux_provider = Arc::new(create_conflux_provider(config, &conflux_chain)?);
     let runtime_handle = tokio::runtime::Handle::current();
-    let espace_preparer = Arc::new(EspaceSimulationPreparer::new(
+    // eSpace and Core Space share one cache since their reads are keyed apart.
+    let state_cache = ConfluxStateCache::new(state_cache_limits);
+    let mut espace_preparer =
+        EspaceSimulationPreparer::new(conflux_chain.clone(), Arc::clone(&conflux_provider))
+            .with_state_cache(state_cache.clone());
+    let espace_simulator = Arc::new(EspaceSimulator::new(
         conflux_chain.clone(),
-        Arc::clone(&conflux_provider),
-    ));
-    let espace_simulator = Arc::new(EspaceSimulator::new(runtime_handle.clone()));
-    let core_space_preparer = Arc::new(CoreSpaceSimulationPreparer::new(
-        conflux_chain,
-        Arc::clone(&conflux_provider),
+        runtime_handle.clone(),
     ));
-    let core_space_simulator = Arc::new(CoreSpaceSimulator::new(runtime_handle));
+    let mut core_space_preparer =
+        CoreSpaceSimulationPreparer::new(conflux_chain.clone(), Arc::clone(&conflux_provider))
+            .with_state_cache(state_cache);
+    if let Some(directory) = snapshot_directory {
+        let snapshot_store = ConfluxSnapshotStore::new(directory);
+        espace_preparer = espace_preparer.with_snapshot_store(snapshot_store.clone());
+        core_space_preparer = core_space_preparer.with_snapshot_store(snapshot_store);
+    }
+    let espace_preparer = Arc::new(espace_preparer);
+    let core_space_preparer = Arc::new(core_space_preparer);
+    let core_space_simulator = Arc::new(CoreSpaceSimulator::new(conflux_chain, runtime_handle));
     let conflux_service = Arc::new(ConfluxService::new(
         espace_preparer,
         espace_simulator,
@@ -122,11 +273,208 @@ fn add_conflux_rpc_module(
         .map_err(|error| startup_error(format!("failed to merge Conflux RPC module: {error}")))
 }
 
-fn create_ethereum_provider(config: &EthereumConfig) -> io::Result<RootProvider> {
-    let rpc_url = config
-        .rpc_url
-        .parse()
-        .map_err(|error| configuration_error(format!("invalid Ethereum RPC URL: {error}")))?;
+fn snapshot_mode(config: &SimulationConfig) -> SnapshotMode {
+    match config.snapshot_mode {
+        SnapshotModeConfig::Record => SnapshotMode::Record,
+        SnapshotModeConfig::Replay => SnapshotMode::Replay,
+    }
+}
+
+fn replays_snapshots(config: &SimulationConfig) -> bool {
+    config.snapshot_directory.is_some() && snapshot_mode(config) == SnapshotMode::Replay
+}
+
+fn state_cache_limits(config: &SimulationConfig) -> io::Result<StateCacheLimits> {
+    let mut limits = StateCacheLimits::default();
+    if let Some(entries) = config.state_cache_entries {
+        limits.entries = NonZeroUsize::new(entries).ok_or_else(|| {
+            configuration_error("simulation.state_cache_entries must be greater than zero")
+        })?;
+    }
+    if let Some(bytes) = config.state_cache_bytes {
+        limits.bytes = NonZeroUsize::new(bytes).ok_or_else(|| {
+            configuration_error("simulation.state_cache_bytes must be greater than zero")
+        })?;
+    }
+    Ok(limits)
+}
+
+fn create_prefetch(config: PrefetchConfig) -> EvmPrefetch {
+    match config {
+        PrefetchConfig::Disabled => EvmPrefetch::Disabled,
+        PrefetchConfig::AccessList => EvmPrefetch::AccessList,
+        PrefetchConfig::PrestateTrace => EvmPrefetch::PrestateTrace,
+    }
+}
+
+fn create_evm_chain_config(name: &str, config: &EthereumChainConfig) -> io::Result<EvmChainConfig> {
+    let mut chain = if config.hardforks.is_empty() {
+        EvmChainConfig::known(config.chain_id).ok_or_else(|| {
+            configuration_error(format!(
+                "ethereum.chains.{name}.hardforks is required for chain_id {}",
+                config.chain_id
+            ))
+        })?
+    } else {
+        let hardforks = config
+            .hardforks
+            .iter()
+            .map(|(hardfork, activation)| {
+                let hardfork = hardfork.parse::<EthereumHardfork>().map_err(|_| {
+                    configuration_error(format!(
+                        "ethereum.chains.{name}.hardforks has unknown hardfork {hardfork}"
+                    ))
+                })?;
+                let condition = match *activation {
+                    HardforkActivationConfig::Block(number) => ForkCondition::Block(number),
+                    HardforkActivationConfig::Timestamp(timestamp) => {
+                        ForkCondition::Timestamp(timestamp)
+                    }
+                };
+
+                Ok((hardfork, condition))
+            })
+            .collect::<io::Result<Vec<_>>>()?;
+        let native_currency = EvmChainConfig::known(config.chain_id)
+            .map(|chain| chain.native_currency)
+            .unwrap_or_default();
+
+        EvmChainConfig::custom(config.chain_id, hardforks, native_currency)
+    };
+
+    if let Some(native_currency) = &config.native_currency {
+        chain.native_currency = NativeMetadata {
+            name: Some(native_currency.name.clone()),
+            symbol: Some(native_currency.symbol.clone()),
+            decimals: Some(native_currency.decimals),
+        };
+    }
+    if let Some(block_interval_secs) = config.block_interval_secs {
+        chain.block_interval_secs = block_interval_secs;
+    }
+    if let Some(base_fee) = config.base_fee {
+        chain.base_fee_params = BaseFeeParams::new(
+            base_fee.max_change_denominator,
+            base_fee.elasticity_multiplier,
+        );
+    }
+
+    Ok(chain)
+}
+
+fn create_conflux_chain_config(config: &ConfluxConfig) -> io::Result<ConfluxChainConfig> {
+    let mut chain = match config.network {
+        ConfluxNetworkConfig::Mainnet => ConfluxChainConfig::mainnet(),
+        ConfluxNetworkConfig::Testnet => ConfluxChainConfig::testnet(),
+        ConfluxNetworkConfig::Custom => {
+            let chain = config.chain.as_ref().ok_or_else(|| {
+                configuration_error("conflux.chain is required for network custom")
+            })?;
+            let transitions = config.transitions.ok_or_else(|| {
+                configuration_error("conflux.transitions is required for network custom")
+            })?;
+
+            // Private networks enable the BN128 precompiles from genesis
+            // unless configured otherwise, as upstream dev networks do.
+            return Ok(ConfluxChainConfig::custom(
+                chain.core_space_chain_id,
+                chain.evm_chain_id,
+                chain.network_id,
+                create_conflux_transitions(transitions, 0),
+            ));
+        }
+    };
+    if let Some(transitions) = config.transitions {
+        chain.transitions =
+            create_conflux_transitions(transitions, chain.transitions.bn128_enable_number);
+    }
+
+    Ok(chain)
+}
+
+fn create_conflux_transitions(
+    config: ConfluxTransitionsConfig,
+    default_bn128_enable_number: u64,
+) -> ConfluxTransitions {
+    ConfluxTransitions {
+        tanzanite_transition_height: config.tanzanite_transition_height,
+        bn128_enable_number: config
+            .bn128_enable_number
+            .unwrap_or(default_bn128_enable_number),
+        hydra_transition_number: config.hydra_transition_number,
+        hydra_transition_height: config.hydra_transition_height,
+        cip43_init_end_number: config.cip43_init_end_number,
+        dao_vote_transition_number: config.dao_vote_transition_number,
+        dao_vote_transition_height: config.dao_vote_transition_height,
+        sigma_fix_transition_number: config.sigma_fix_transition_number,
+        burn_collateral_transition_number: config.burn_collateral_transition_number,
+        cip112_transition_height: config.cip112_transition_height,
+        base_fee_burn_transition_number: config.base_fee_burn_transition_number,
+        base_fee_burn_transition_height: config.base_fee_burn_transition_height,
+        c2_fix_transition_height: config.c2_fix_transition_height,
+        eoa_code_transition_height: config.eoa_code_transition_height,
+    }
+}
+
+fn create_risk_config(config: &RiskConfig) -> EvmRiskConfig {
+    let defaults = EvmRiskConfig::default();
+    let approval_for_all_to_fresh_contract = RiskRuleConfig {
+        enabled: config.approval_for_all_to_fresh_contract.enabled,
+        severity: config.approval_for_all_to_fresh_contract.severity,
+    };
+    let wallet_drained = RiskRuleConfig {
+        enabled: config.wallet_drained.enabled,
+        severity: config.wallet_drained.severity,
+    };
+
+    EvmRiskConfig {
+        unlimited_allowance_to_eoa: create_risk_rule(
+            &config.unlimited_allowance_to_eoa,
+            defaults.unlimited_allowance_to_eoa,
+        ),
+        approval_for_all_to_fresh_contract: create_risk_rule(
+            &approval_for_all_to_fresh_contract,
+            defaults.approval_for_all_to_fresh_contract,
+        ),
+        native_value_to_codeless_target: create_risk_rule(
+            &config.native_value_to_codeless_target,
+            defaults.native_value_to_codeless_target,
+        ),
+        ownership_transfer: create_risk_rule(
+            &config.ownership_transfer,
+            defaults.ownership_transfer,
+        ),
+        wallet_drained: create_risk_rule(&wallet_drained, defaults.wallet_drained),
+        fresh_contract_max_age_blocks: config
+            .approval_for_all_to_fresh_contract
+            .max_age_blocks
+            .unwrap_or(defaults.fresh_contract_max_age_blocks),
+        wallet_drained_min_assets: config
+            .wallet_drained
+            .min_assets
+            .unwrap_or(defaults.wallet_drained_min_assets),
+    }
+}
+
+fn create_risk_rule(config: &RiskRuleConfig, default: RiskRule) -> RiskRule {
+    RiskRule {
+        enabled: config.enabled.unwrap_or(default.enabled),
+        severity: config
+            .severity
+            .map_or(default.severity, |severity| match severity {
+                RiskSeverityConfig::Low => RiskSeverity::Low,
+                RiskSeverityConfig::Medium => RiskSeverity::Medium,
+                RiskSeverityConfig::High => RiskSeverity::High,
+            }),
+    }
+}
+
+fn create_ethereum_provider(name: &str, config: &EthereumChainConfig) -> io::Result<RootProvider> {
+    let rpc_url = config.rpc_url.parse().map_err(|error| {
+        configuration_error(format!(
+            "invalid RPC URL for ethereum.chains.{name}: {error}"
+        ))
+    })?;
 
     Ok(RootProvider::new_http(rpc_url))
 }
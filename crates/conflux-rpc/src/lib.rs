@@ -13,12 +13,20 @@ use self::{
         core_space_response_mapping_error, invalid_params, map_core_space_service_error,
         map_espace_service_error,
     },
-    request::{SimulateCoreSpaceTransactionRequest, SimulateEspaceTransactionRequest},
-    response::{SimulateCoreSpaceTransactionResponse, SimulateEspaceTransactionResponse},
+    request::{
+        ReplayCoreSpaceTransactionRequest, SimulateBundleRequest,
+        SimulateCoreSpaceTransactionRequest, SimulateEspaceTransactionRequest,
+    },
+    response::{
+        SimulateBundleResponse, SimulateCoreSpaceTransactionResponse,
+        SimulateEspaceTransactionResponse,
+    },
 };
 
 const METHOD_SIMULATE_ESPACE_TRANSACTION: &str = "dryrun_conflux_espace_simulateTransaction";
 const METHOD_SIMULATE_CORE_SPACE_TRANSACTION: &str = "dryrun_conflux_coreSpace_simulateTransaction";
+const METHOD_SIMULATE_BUNDLE: &str = "dryrun_conflux_simulateBundle";
+const METHOD_REPLAY_CORE_SPACE_TRANSACTION: &str = "dryrun_conflux_coreSpace_replayTransaction";
 
 pub fn build_rpc_module(
     service: Arc<ConfluxService>,
@@ -34,12 +42,17 @@ pub fn build_rpc_module(
                     .parse::<SimulateEspaceTransactionRequest>()
                     .map_err(|error| invalid_params(error.to_string()))?;
 
+                let format_amounts = request.formats_amounts();
                 let output = service
                     .simulate_espace_transaction(request.try_into()?)
                     .await
                     .map_err(map_espace_service_error)?;
 
-                Ok::<_, ErrorObjectOwned>(SimulateEspaceTransactionResponse::from(output))
+                let mut response = SimulateEspaceTransactionResponse::from(output);
+                if format_amounts {
+                    response.format_amounts();
+                }
+                Ok::<_, ErrorObjectOwned>(response)
             },
         )
         .expect("RPC method names must be unique");
@@ -52,6 +65,7 @@ pub fn build_rpc_module(
                     .parse::<SimulateCoreSpaceTransactionRequest>()
                     .map_err(|error| invalid_params(error.to_string()))?;
 
+                let format_amounts = request.formats_amounts();
                 let input = request.try_into_service_input(core_space_address_network)?;
 
                 let output = service
@@ -59,11 +73,69 @@ pub fn build_rpc_module(
                     .await
                     .map_err(map_core_space_service_error)?;
 
-                SimulateCoreSpaceTransactionResponse::try_from_output(
+                let mut response = SimulateCoreSpaceTransactionResponse::try_from_output(
+                    output,
+                    core_space_address_network,
+                )
+                .map_err(|error| core_space_response_mapping_error(error.to_string()))?;
+                if format_amounts {
+                    response.format_amounts();
+                }
+                Ok::<_, ErrorObjectOwned>(response)
+            },
+        )
+        .expect("RPC method names must be unique");
+
+    module
+        .register_async_method(
+            METHOD_SIMULATE_BUNDLE,
+            move |params, service, _| async move {
+                let request = params
+                    .parse::<SimulateBundleRequest>()
+                    .map_err(|error| invalid_params(error.to_string()))?;
+
+                let format_amounts = request.formats_amounts();
+                let input = request.try_into_service_input(core_space_address_network)?;
+
+                let output = service
+                    .simulate_core_space_bundle(input)
+                    .await
+                    .map_err(map_core_space_service_error)?;
+
+                let mut response =
+                    SimulateBundleResponse::try_from_output(output, core_space_address_network)
+                        .map_err(|error| core_space_response_mapping_error(error.to_string()))?;
+                if format_amounts {
+                    response.format_amounts();
+                }
+                Ok::<_, ErrorObjectOwned>(response)
+            },
+        )
+        .expect("RPC method names must be unique");
+
+    module
+        .register_async_method(
+            METHOD_REPLAY_CORE_SPACE_TRANSACTION,
+            move |params, service, _| async move {
+                let request = params
+                    .parse::<ReplayCoreSpaceTransactionRequest>()
+                    .map_err(|error| invalid_params(error.to_string()))?;
+
+                let format_amounts = request.formats_amounts();
+                let output = service
+                    .replay_core_space_transaction(request.into())
+                    .await
+                    .map_err(map_core_space_service_error)?;
+
+                let mut response = SimulateCoreSpaceTransactionResponse::try_from_output(
                     output,
                     core_space_address_network,
                 )
-                .map_err(|error| core_space_response_mapping_error(error.to_string()))
+                .map_err(|error| core_space_response_mapping_error(error.to_string()))?;
+                if format_amounts {
+                    response.format_amounts();
+                }
+                Ok::<_, ErrorObjectOwned>(response)
             },
         )
         .expect("RPC method names must be unique");
@@ -1,21 +1,152 @@
-use alloy_primitives::Bytes;
+use std::collections::BTreeMap;
+
+use alloy_primitives::{B256, Bytes};
 use cfx_addr::Network;
 use cfx_rpc_cfx_types::{EpochNumber, RpcAddress};
+use cfx_rpc_eth_types::TransactionRequest;
 use cfx_rpc_primitives::Bytes as CoreSpaceRpcBytes;
 use cfx_types::{H256, U64, U256};
 use conflux_service::core_space as service_core_space;
 use serde::Deserialize;
 use simulation_transaction::TransactionType;
 
-use super::{cfx_h256_to_alloy, cfx_u256_to_alloy, u64_param, u128_param};
+use super::{
+    cfx_h256_to_alloy, cfx_u256_to_alloy,
+    espace::{IncludeField, map_transaction, validate_transaction},
+    u64_param, u128_param,
+};
 use crate::error::ValidationError;
 
+const MAX_BUNDLE_TRANSACTIONS: usize = 16;
+
 #[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "camelCase", deny_unknown_fields)]
 pub(crate) struct SimulateCoreSpaceTransactionRequest {
     transaction: CoreSpaceTransactionRequest,
     #[serde(default)]
-    epoch: Option<EpochNumber>,
+    epoch: Option<EpochRef>,
+    #[serde(default)]
+    options: Option<SimulateCoreSpaceTransactionOptions>,
+}
+
+#[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub(crate) struct SimulateBundleRequest {
+    transactions: Vec<BundleTransaction>,
+    #[serde(default)]
+    epoch: Option<EpochRef>,
+    #[serde(default)]
+    options: Option<SimulateCoreSpaceTransactionOptions>,
+}
+
+#[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub(crate) struct ReplayCoreSpaceTransactionRequest {
+    transaction_hash: H256,
+    #[serde(default)]
+    options: Option<ReplayCoreSpaceTransactionOptions>,
+}
+
+#[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+enum BundleTransaction {
+    CoreSpace(CoreSpaceTransactionRequest),
+    Espace(TransactionRequest),
+}
+
+#[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
+#[serde(untagged)]
+enum EpochRef {
+    Number(EpochNumber),
+    Hash(EpochHashRef),
+}
+
+#[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+struct EpochHashRef {
+    epoch_hash: H256,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+struct SimulateCoreSpaceTransactionOptions {
+    /// Keyed by base32 Core Space address. Transactions must then set `gas`
+    /// and `storageLimit`, as upstream estimation cannot see the overrides.
+    #[serde(default)]
+    state_overrides: Option<BTreeMap<String, CoreSpaceAccountOverride>>,
+    #[serde(default)]
+    epoch_overrides: Option<CoreSpaceEpochOverrides>,
+    #[serde(default)]
+    include: Option<Vec<IncludeField>>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+struct ReplayCoreSpaceTransactionOptions {
+    #[serde(default)]
+    include: Option<Vec<IncludeField>>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+struct CoreSpaceAccountOverride {
+    #[serde(default)]
+    balance: Option<U256>,
+    #[serde(default)]
+    nonce: Option<U256>,
+    #[serde(default)]
+    code: Option<CoreSpaceRpcBytes>,
+    #[serde(default)]
+    state: Option<BTreeMap<H256, H256>>,
+    #[serde(default)]
+    state_diff: Option<BTreeMap<H256, H256>>,
+    #[serde(default)]
+    staking_balance: Option<U256>,
+    #[serde(default)]
+    sponsor_info: Option<CoreSpaceSponsorInfoOverride>,
+    #[serde(default)]
+    deposit_list: Option<Vec<CoreSpaceDepositOverride>>,
+    #[serde(default)]
+    vote_list: Option<Vec<CoreSpaceVoteStakeOverride>>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+struct CoreSpaceSponsorInfoOverride {
+    #[serde(default)]
+    sponsor_for_gas: Option<RpcAddress>,
+    #[serde(default)]
+    sponsor_for_collateral: Option<RpcAddress>,
+    #[serde(default)]
+    sponsor_gas_bound: Option<U256>,
+    #[serde(default)]
+    sponsor_balance_for_gas: Option<U256>,
+    #[serde(default)]
+    sponsor_balance_for_collateral: Option<U256>,
+}
+
+#[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+struct CoreSpaceDepositOverride {
+    amount: U256,
+    deposit_time: U256,
+    accumulated_interest_rate: U256,
+}
+
+#[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+struct CoreSpaceVoteStakeOverride {
+    amount: U256,
+    unlock_block_number: U256,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+struct CoreSpaceEpochOverrides {
+    #[serde(default)]
+    epoch_height: Option<U256>,
+    #[serde(default)]
+    timestamp: Option<U256>,
 }
 
 #[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
@@ -46,28 +177,347 @@ struct CoreSpaceAccessListItem {
 }
 
 impl SimulateCoreSpaceTransactionRequest {
+    pub(crate) fn formats_amounts(&self) -> bool {
+        self.options
+            .as_ref()
+            .is_some_and(|options| includes_formatted_amounts(options.include.as_deref()))
+    }
+
     pub(crate) fn try_into_service_input(
         self,
         expected_network: Network,
     ) -> Result<service_core_space::SimulateCoreSpaceTransactionInput, ValidationError> {
+        self.validate()?;
+        let options = self.options.unwrap_or_default();
+
         Ok(service_core_space::SimulateCoreSpaceTransactionInput {
             epoch: map_core_space_epoch(self.epoch)?,
             transaction: map_core_space_transaction(self.transaction, expected_network)?,
+            state_overrides: options
+                .state_overrides
+                .map(map_core_space_state_overrides)
+                .transpose()?
+                .unwrap_or_default(),
+            epoch_overrides: options
+                .epoch_overrides
+                .map(map_core_space_epoch_overrides)
+                .transpose()?
+                .unwrap_or_default(),
+        })
+    }
+
+    fn validate(&self) -> Result<(), ValidationError> {
+        // Upstream estimation cannot see overridden state.
+        if has_state_overrides(self.options.as_ref())
+            && (self.transaction.gas.is_none() || self.transaction.storage_limit.is_none())
+        {
+            return Err(ValidationError::invalid_params(
+                "`transaction.gas` and `storageLimit` are required with `options.stateOverrides`",
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+impl ReplayCoreSpaceTransactionRequest {
+    pub(crate) fn formats_amounts(&self) -> bool {
+        self.options
+            .as_ref()
+            .is_some_and(|options| includes_formatted_amounts(options.include.as_deref()))
+    }
+}
+
+impl From<ReplayCoreSpaceTransactionRequest>
+    for service_core_space::ReplayCoreSpaceTransactionInput
+{
+    fn from(request: ReplayCoreSpaceTransactionRequest) -> Self {
+        Self {
+            transaction_hash: cfx_h256_to_alloy(request.transaction_hash),
+        }
+    }
+}
+
+impl SimulateBundleRequest {
+    pub(crate) fn formats_amounts(&self) -> bool {
+        self.options
+            .as_ref()
+            .is_some_and(|options| includes_formatted_amounts(options.include.as_deref()))
+    }
+
+    pub(crate) fn try_into_service_input(
+        self,
+        expected_network: Network,
+    ) -> Result<service_core_space::SimulateCoreSpaceBundleInput, ValidationError> {
+        self.validate()?;
+        let options = self.options.unwrap_or_default();
+
+        Ok(service_core_space::SimulateCoreSpaceBundleInput {
+            epoch: map_core_space_epoch(self.epoch)?,
+            transactions: self
+                .transactions
+                .into_iter()
+                .map(|transaction| map_bundle_transaction(transaction, expected_network))
+                .collect::<Result<_, _>>()?,
+            state_overrides: options
+                .state_overrides
+                .map(map_core_space_state_overrides)
+                .transpose()?
+                .unwrap_or_default(),
+            epoch_overrides: options
+                .epoch_overrides
+                .map(map_core_space_epoch_overrides)
+                .transpose()?
+                .unwrap_or_default(),
         })
     }
+
+    fn validate(&self) -> Result<(), ValidationError> {
+        if self.transactions.is_empty() {
+            return Err(ValidationError::invalid_params(
+                "`transactions` must contain at least one transaction",
+            ));
+        }
+
+        if self.transactions.len() > MAX_BUNDLE_TRANSACTIONS {
+            return Err(ValidationError::invalid_params(format!(
+                "`transactions` must not contain more than {MAX_BUNDLE_TRANSACTIONS} transactions"
+            )));
+        }
+
+        let has_state_overrides = has_state_overrides(self.options.as_ref());
+        for (index, transaction) in self.transactions.iter().enumerate() {
+            if let BundleTransaction::Espace(transaction) = transaction {
+                validate_transaction(transaction)?;
+            }
+            // Upstream estimation cannot see overridden state or the effects
+            // of earlier bundle transactions.
+            let reason = if has_state_overrides {
+                "with `options.stateOverrides`"
+            } else if index > 0 {
+                "after the first bundle transaction"
+            } else {
+                continue;
+            };
+            match transaction {
+                BundleTransaction::CoreSpace(transaction)
+                    if transaction.gas.is_none() || transaction.storage_limit.is_none() =>
+                {
+                    return Err(ValidationError::invalid_params(format!(
+                        "`transactions[{index}].coreSpace.gas` and `storageLimit` are required {reason}"
+                    )));
+                }
+                BundleTransaction::Espace(transaction) if transaction.gas.is_none() => {
+                    return Err(ValidationError::invalid_params(format!(
+                        "`transactions[{index}].espace.gas` is required {reason}"
+                    )));
+                }
+                _ => {}
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn has_state_overrides(options: Option<&SimulateCoreSpaceTransactionOptions>) -> bool {
+    options
+        .and_then(|options| options.state_overrides.as_ref())
+        .is_some_and(|state_overrides| !state_overrides.is_empty())
+}
+
+fn includes_formatted_amounts(include: Option<&[IncludeField]>) -> bool {
+    include.is_some_and(|include| include.contains(&IncludeField::FormattedAmounts))
+}
+
+fn map_bundle_transaction(
+    transaction: BundleTransaction,
+    expected_network: Network,
+) -> Result<service_core_space::CoreSpaceBundleTransaction, ValidationError> {
+    match transaction {
+        BundleTransaction::CoreSpace(transaction) => {
+            let service_core_space::CoreSpaceTransactionRequest {
+                transaction,
+                storage_limit,
+                epoch_height,
+            } = map_core_space_transaction(transaction, expected_network)?;
+
+            Ok(service_core_space::CoreSpaceBundleTransaction::CoreSpace {
+                transaction,
+                storage_limit,
+                epoch_height,
+            })
+        }
+        BundleTransaction::Espace(transaction) => Ok(
+            service_core_space::CoreSpaceBundleTransaction::Espace(map_transaction(transaction)?),
+        ),
+    }
+}
+
+fn map_core_space_state_overrides(
+    overrides: BTreeMap<String, CoreSpaceAccountOverride>,
+) -> Result<service_core_space::CoreSpaceStateOverrides, ValidationError> {
+    overrides
+        .into_iter()
+        .map(|(address, account_override)| {
+            let field = format!("options.stateOverrides.{address}");
+            let address = service_core_space::CoreAddress::parse(&address).map_err(|error| {
+                ValidationError::invalid_params(format!(
+                    "`{field}` is not a Core Space address: {error}"
+                ))
+            })?;
+
+            Ok((
+                address,
+                map_core_space_account_override(account_override, &field)?,
+            ))
+        })
+        .collect()
+}
+
+fn map_core_space_account_override(
+    account_override: CoreSpaceAccountOverride,
+    field: &str,
+) -> Result<service_core_space::CoreSpaceAccountOverride, ValidationError> {
+    let CoreSpaceAccountOverride {
+        balance,
+        nonce,
+        code,
+        state,
+        state_diff,
+        staking_balance,
+        sponsor_info,
+        deposit_list,
+        vote_list,
+    } = account_override;
+
+    let storage = match (state, state_diff) {
+        (Some(_), Some(_)) => {
+            return Err(ValidationError::invalid_params(format!(
+                "`{field}` cannot set both `state` and `stateDiff`"
+            )));
+        }
+        (Some(slots), None) => Some(service_core_space::CoreSpaceStorageOverride::Replace(
+            map_storage_slots(slots),
+        )),
+        (None, Some(slots)) => Some(service_core_space::CoreSpaceStorageOverride::Patch(
+            map_storage_slots(slots),
+        )),
+        (None, None) => None,
+    };
+
+    Ok(service_core_space::CoreSpaceAccountOverride {
+        balance: balance.map(cfx_u256_to_alloy),
+        nonce: nonce
+            .map(|value| u64_param(value, &format!("{field}.nonce")))
+            .transpose()?,
+        code: code.map(|code| Bytes::from(code.into_vec())),
+        storage,
+        staking_balance: staking_balance.map(cfx_u256_to_alloy),
+        sponsor: sponsor_info
+            .map(|sponsor| {
+                Ok::<_, ValidationError>(service_core_space::CoreSpaceSponsorOverride {
+                    sponsor_for_gas: sponsor
+                        .sponsor_for_gas
+                        .map(map_core_space_address)
+                        .transpose()?,
+                    sponsor_for_collateral: sponsor
+                        .sponsor_for_collateral
+                        .map(map_core_space_address)
+                        .transpose()?,
+                    sponsor_gas_bound: sponsor.sponsor_gas_bound.map(cfx_u256_to_alloy),
+                    sponsor_balance_for_gas: sponsor.sponsor_balance_for_gas.map(cfx_u256_to_alloy),
+                    sponsor_balance_for_collateral: sponsor
+                        .sponsor_balance_for_collateral
+                        .map(cfx_u256_to_alloy),
+                })
+            })
+            .transpose()?,
+        deposit_list: deposit_list
+            .map(|deposits| {
+                deposits
+                    .into_iter()
+                    .map(|deposit| {
+                        Ok(service_core_space::CoreSpaceDeposit {
+                            amount: cfx_u256_to_alloy(deposit.amount),
+                            deposit_time: u64_param(
+                                deposit.deposit_time,
+                                &format!("{field}.depositList.depositTime"),
+                            )?,
+                            accumulated_interest_rate: cfx_u256_to_alloy(
+                                deposit.accumulated_interest_rate,
+                            ),
+                        })
+                    })
+                    .collect::<Result<Vec<_>, ValidationError>>()
+            })
+            .transpose()?,
+        vote_list: vote_list
+            .map(|votes| {
+                votes
+                    .into_iter()
+                    .map(|vote| {
+                        Ok(service_core_space::CoreSpaceVoteStake {
+                            amount: cfx_u256_to_alloy(vote.amount),
+                            unlock_block_number: u64_param(
+                                vote.unlock_block_number,
+                                &format!("{field}.voteList.unlockBlockNumber"),
+                            )?,
+                        })
+                    })
+                    .collect::<Result<Vec<_>, ValidationError>>()
+            })
+            .transpose()?,
+    })
+}
+
+fn map_storage_slots(slots: BTreeMap<H256, H256>) -> BTreeMap<B256, B256> {
+    slots
+        .into_iter()
+        .map(|(slot, value)| (cfx_h256_to_alloy(slot), cfx_h256_to_alloy(value)))
+        .collect()
+}
+
+fn map_core_space_epoch_overrides(
+    overrides: CoreSpaceEpochOverrides,
+) -> Result<service_core_space::CoreSpaceEpochOverrides, ValidationError> {
+    Ok(service_core_space::CoreSpaceEpochOverrides {
+        epoch_height: overrides
+            .epoch_height
+            .map(|value| u64_param(value, "options.epochOverrides.epochHeight"))
+            .transpose()?,
+        timestamp: overrides
+            .timestamp
+            .map(|value| u64_param(value, "options.epochOverrides.timestamp"))
+            .transpose()?,
+    })
 }
 
 fn map_core_space_epoch(
-    epoch: Option<EpochNumber>,
+    epoch: Option<EpochRef>,
 ) -> Result<service_core_space::CoreSpaceEpochRef, ValidationError> {
-    match epoch.unwrap_or(EpochNumber::LatestState) {
+    let epoch = match epoch.unwrap_or(EpochRef::Number(EpochNumber::LatestState)) {
+        EpochRef::Number(epoch) => epoch,
+        EpochRef::Hash(epoch) => {
+            return Ok(service_core_space::CoreSpaceEpochRef::Hash(
+                cfx_h256_to_alloy(epoch.epoch_hash),
+            ));
+        }
+    };
+
+    match epoch {
         EpochNumber::LatestState => Ok(service_core_space::CoreSpaceEpochRef::LatestState),
+        EpochNumber::LatestMined => Ok(service_core_space::CoreSpaceEpochRef::LatestMined),
+        EpochNumber::LatestConfirmed => Ok(service_core_space::CoreSpaceEpochRef::LatestConfirmed),
+        EpochNumber::LatestFinalized => Ok(service_core_space::CoreSpaceEpochRef::LatestFinalized),
         EpochNumber::Num(number) => Ok(service_core_space::CoreSpaceEpochRef::Number(
             number.as_u64(),
         )),
-        _ => Err(ValidationError::not_supported(
-            "`epoch` only supports `latest_state` or a hex epoch number",
-        )),
+        EpochNumber::Earliest | EpochNumber::LatestCheckpoint => {
+            Err(ValidationError::not_supported(
+                "`epoch` does not support `earliest` or `latest_checkpoint`",
+            ))
+        }
     }
 }
 
@@ -245,3 +695,300 @@ fn map_core_space_address(
     service_core_space::CoreAddress::from_bytes(bytes, network)
         .map_err(|error| ValidationError::invalid_params(error.to_string()))
 }
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    const FIELD: &str = "options.stateOverrides.account";
+
+    fn account_override(value: serde_json::Value) -> CoreSpaceAccountOverride {
+        serde_json::from_value(value).expect("valid account override")
+    }
+
+    fn bundle(
+        transactions: serde_json::Value,
+        options: serde_json::Value,
+    ) -> SimulateBundleRequest {
+        serde_json::from_value(json!({ "transactions": transactions, "options": options }))
+            .expect("valid bundle request")
+    }
+
+    fn core_space_limits() -> serde_json::Value {
+        json!({ "coreSpace": { "gas": "0x5208", "storageLimit": "0x0" } })
+    }
+
+    #[test]
+    fn bundle_requires_transactions_within_the_limit() {
+        let error = bundle(json!([]), json!({})).validate().unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "`transactions` must contain at least one transaction"
+        );
+
+        let transactions = vec![core_space_limits(); MAX_BUNDLE_TRANSACTIONS + 1];
+        let error = bundle(json!(transactions), json!({}))
+            .validate()
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            format!(
+                "`transactions` must not contain more than {MAX_BUNDLE_TRANSACTIONS} transactions"
+            )
+        );
+
+        let transactions = vec![core_space_limits(); MAX_BUNDLE_TRANSACTIONS];
+        bundle(json!(transactions), json!({})).validate().unwrap();
+    }
+
+    #[test]
+    fn bundle_lets_only_the_first_transaction_rely_on_estimation() {
+        bundle(json!([{ "coreSpace": {} }, core_space_limits()]), json!({}))
+            .validate()
+            .unwrap();
+        bundle(
+            json!([{ "espace": {} }, { "espace": { "gas": "0x5208" } }]),
+            json!({}),
+        )
+        .validate()
+        .unwrap();
+
+        let error = bundle(
+            json!([core_space_limits(), { "coreSpace": { "gas": "0x5208" } }]),
+            json!({}),
+        )
+        .validate()
+        .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "`transactions[1].coreSpace.gas` and `storageLimit` are required after the first bundle transaction"
+        );
+
+        let error = bundle(json!([core_space_limits(), { "espace": {} }]), json!({}))
+            .validate()
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "`transactions[1].espace.gas` is required after the first bundle transaction"
+        );
+    }
+
+    #[test]
+    fn bundle_with_state_overrides_requires_limits_on_every_transaction() {
+        let options = json!({ "stateOverrides": { "account": { "balance": "0x1" } } });
+
+        let error = bundle(
+            json!([{ "coreSpace": { "gas": "0x5208" } }]),
+            options.clone(),
+        )
+        .validate()
+        .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "`transactions[0].coreSpace.gas` and `storageLimit` are required with `options.stateOverrides`"
+        );
+
+        let error = bundle(json!([{ "espace": {} }]), options.clone())
+            .validate()
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "`transactions[0].espace.gas` is required with `options.stateOverrides`"
+        );
+
+        bundle(json!([core_space_limits()]), options)
+            .validate()
+            .unwrap();
+    }
+
+    #[test]
+    fn account_override_maps_state_to_a_storage_replacement() {
+        let mapped = map_core_space_account_override(
+            account_override(json!({
+                "state": { format!("{:#066x}", 1): format!("{:#066x}", 2) },
+            })),
+            FIELD,
+        )
+        .unwrap();
+
+        assert_eq!(
+            mapped.storage,
+            Some(service_core_space::CoreSpaceStorageOverride::Replace(
+                BTreeMap::from([(B256::with_last_byte(1), B256::with_last_byte(2))])
+            ))
+        );
+    }
+
+    #[test]
+    fn account_override_maps_state_diff_to_a_storage_patch() {
+        let mapped = map_core_space_account_override(
+            account_override(json!({
+                "stateDiff": { format!("{:#066x}", 1): format!("{:#066x}", 2) },
+            })),
+            FIELD,
+        )
+        .unwrap();
+
+        assert_eq!(
+            mapped.storage,
+            Some(service_core_space::CoreSpaceStorageOverride::Patch(
+                BTreeMap::from([(B256::with_last_byte(1), B256::with_last_byte(2))])
+            ))
+        );
+    }
+
+    #[test]
+    fn account_override_rejects_state_with_state_diff() {
+        let error = map_core_space_account_override(
+            account_override(json!({ "state": {}, "stateDiff": {} })),
+            FIELD,
+        )
+        .unwrap_err();
+
+        assert!(matches!(error, ValidationError::InvalidParams(_)));
+        assert_eq!(
+            error.to_string(),
+            format!("`{FIELD}` cannot set both `state` and `stateDiff`")
+        );
+    }
+
+    #[test]
+    fn account_override_maps_amounts_and_staking_lists() {
+        let mapped = map_core_space_account_override(
+            account_override(json!({
+                "balance": "0x10",
+                "nonce": "0x2",
+                "code": "0x6000",
+                "stakingBalance": "0x20",
+                "depositList": [{
+                    "amount": "0x20",
+                    "depositTime": "0x3",
+                    "accumulatedInterestRate": "0x4",
+                }],
+                "voteList": [{ "amount": "0x8", "unlockBlockNumber": "0x5" }],
+            })),
+            FIELD,
+        )
+        .unwrap();
+
+        assert_eq!(
+            mapped,
+            service_core_space::CoreSpaceAccountOverride {
+                balance: Some(alloy_primitives::U256::from(0x10)),
+                nonce: Some(2),
+                code: Some(Bytes::from_static(&[0x60, 0x00])),
+                storage: None,
+                staking_balance: Some(alloy_primitives::U256::from(0x20)),
+                sponsor: None,
+                deposit_list: Some(vec![service_core_space::CoreSpaceDeposit {
+                    amount: alloy_primitives::U256::from(0x20),
+                    deposit_time: 3,
+                    accumulated_interest_rate: alloy_primitives::U256::from(4),
+                }]),
+                vote_list: Some(vec![service_core_space::CoreSpaceVoteStake {
+                    amount: alloy_primitives::U256::from(8),
+                    unlock_block_number: 5,
+                }]),
+            }
+        );
+    }
+
+    fn epoch(
+        value: serde_json::Value,
+    ) -> Result<service_core_space::CoreSpaceEpochRef, ValidationError> {
+        map_core_space_epoch(Some(serde_json::from_value(value).expect("valid epoch")))
+    }
+
+    #[test]
+    fn epoch_defaults_to_latest_state() {
+        assert_eq!(
+            map_core_space_epoch(None).unwrap(),
+            service_core_space::CoreSpaceEpochRef::LatestState
+        );
+    }
+
+    #[test]
+    fn epoch_maps_tags_and_numbers() {
+        for (tag, expected) in [
+            (
+                "latest_state",
+                service_core_space::CoreSpaceEpochRef::LatestState,
+            ),
+            (
+                "latest_mined",
+                service_core_space::CoreSpaceEpochRef::LatestMined,
+            ),
+            (
+                "latest_confirmed",
+                service_core_space::CoreSpaceEpochRef::LatestConfirmed,
+            ),
+            (
+                "latest_finalized",
+                service_core_space::CoreSpaceEpochRef::LatestFinalized,
+            ),
+            ("0x10", service_core_space::CoreSpaceEpochRef::Number(16)),
+        ] {
+            assert_eq!(epoch(json!(tag)).unwrap(), expected, "{tag}");
+        }
+    }
+
+    #[test]
+    fn epoch_maps_an_epoch_hash() {
+        assert_eq!(
+            epoch(json!({ "epochHash": format!("{:#066x}", 7) })).unwrap(),
+            service_core_space::CoreSpaceEpochRef::Hash(B256::with_last_byte(7))
+        );
+    }
+
+    #[test]
+    fn epoch_rejects_earliest_and_latest_checkpoint() {
+        for tag in ["earliest", "latest_checkpoint"] {
+            assert!(
+                matches!(epoch(json!(tag)), Err(ValidationError::NotSupported(_))),
+                "{tag}"
+            );
+        }
+    }
+
+    #[test]
+    fn state_overrides_require_explicit_transaction_limits() {
+        let request = |transaction: serde_json::Value| {
+            serde_json::from_value::<SimulateCoreSpaceTransactionRequest>(json!({
+                "transaction": transaction,
+                "options": { "stateOverrides": { "account": { "balance": "0x1" } } },
+            }))
+            .expect("valid request")
+        };
+
+        let error = request(json!({ "gas": "0x5208" })).validate().unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "`transaction.gas` and `storageLimit` are required with `options.stateOverrides`"
+        );
+        request(json!({ "gas": "0x5208", "storageLimit": "0x0" }))
+            .validate()
+            .unwrap();
+    }
+
+    #[test]
+    fn account_override_names_the_field_of_an_oversized_value() {
+        let error = map_core_space_account_override(
+            account_override(json!({
+                "voteList": [{
+                    "amount": "0x1",
+                    "unlockBlockNumber": "0x10000000000000000",
+                }],
+            })),
+            FIELD,
+        )
+        .unwrap_err();
+
+        assert!(
+            error
+                .to_string()
+                .starts_with(&format!("`{FIELD}.voteList.unlockBlockNumber` value"))
+        );
+    }
+}
@@ -6,7 +6,9 @@ use cfx_types::{Address as CfxAddress, H256, U64, U256};
 use conflux_service::espace as service_espace;
 use serde::Deserialize;
 use serde_json::Value;
-use simulation_transaction::{TransactionType, TransactionVariantRequest};
+use simulation_transaction::{
+    TransactionType, TransactionVariantFields, TransactionVariantRequest,
+};
 
 use super::{cfx_address_to_alloy, cfx_h256_to_alloy, cfx_u256_to_alloy, u64_param, u128_param};
 use crate::error::ValidationError;
@@ -40,9 +42,32 @@ struct SimulateTransactionOptions {
     #[serde(default)]
     state_overrides: Option<Value>,
     #[serde(default)]
-    block_overrides: Option<Value>,
+    block_overrides: Option<BlockOverrides>,
     #[serde(default)]
-    include: Option<Value>,
+    include: Option<Vec<IncludeField>>,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub(super) enum IncludeField {
+    FormattedAmounts,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+struct BlockOverrides {
+    #[serde(default)]
+    number: Option<U256>,
+    #[serde(default)]
+    timestamp: Option<U256>,
+    #[serde(default)]
+    base_fee_per_gas: Option<U256>,
+    #[serde(default)]
+    coinbase: Option<CfxAddress>,
+    #[serde(default)]
+    gas_limit: Option<U256>,
+    #[serde(default)]
+    prev_randao: Option<H256>,
 }
 
 impl TryFrom<SimulateEspaceTransactionRequest> for service_espace::SimulateEspaceTransactionInput {
@@ -51,18 +76,36 @@ impl TryFrom<SimulateEspaceTransactionRequest> for service_espace::SimulateEspac
     fn try_from(request: SimulateEspaceTransactionRequest) -> Result<Self, Self::Error> {
         request.validate()?;
 
+        let SimulateEspaceTransactionRequest {
+            transaction,
+            block,
+            options,
+        } = request;
+        let options = options.unwrap_or_default();
+
         Ok(Self {
-            block: request
-                .block
+            block: block
                 .map(map_block_ref)
                 .transpose()?
                 .unwrap_or(service_espace::EspaceBlockRef::Latest),
-            transaction: map_transaction(request.transaction)?,
+            transaction: map_transaction(transaction)?,
+            block_overrides: options
+                .block_overrides
+                .map(map_block_overrides)
+                .transpose()?
+                .unwrap_or_default(),
         })
     }
 }
 
 impl SimulateEspaceTransactionRequest {
+    pub(crate) fn formats_amounts(&self) -> bool {
+        self.options
+            .as_ref()
+            .and_then(|options| options.include.as_ref())
+            .is_some_and(|include| include.contains(&IncludeField::FormattedAmounts))
+    }
+
     fn validate(&self) -> Result<(), ValidationError> {
         validate_transaction(&self.transaction)?;
 
@@ -84,16 +127,12 @@ impl BlockRef {
             Self::Tag(value) => match value.as_str() {
                 "latest" => Ok(()),
                 "pending" | "safe" | "finalized" => Err(ValidationError::not_supported(
-                    "`block` only supports `latest` or a hex block number",
-                )),
-                value if H256::from_str(value).is_ok() => Err(ValidationError::not_supported(
-                    "`block` does not support block hash selectors yet",
+                    "`block` only supports `latest`, a hex block number, or a block hash",
                 )),
+                value if H256::from_str(value).is_ok() => Ok(()),
                 value => parse_u64_param(value, "block").map(|_| ()),
             },
-            Self::Hash(_) => Err(ValidationError::not_supported(
-                "`block.blockHash` is not supported yet",
-            )),
+            Self::Hash(_) => Ok(()),
         }
     }
 }
@@ -101,14 +140,14 @@ impl BlockRef {
 impl SimulateTransactionOptions {
     fn validate(&self) -> Result<(), ValidationError> {
         validate_reserved_option("stateOverrides", self.state_overrides.as_ref())?;
-        validate_reserved_option("blockOverrides", self.block_overrides.as_ref())?;
-        validate_reserved_option("include", self.include.as_ref())?;
 
         Ok(())
     }
 }
 
-fn validate_transaction(transaction: &TransactionRequest) -> Result<(), ValidationError> {
+pub(super) fn validate_transaction(
+    transaction: &TransactionRequest,
+) -> Result<(), ValidationError> {
     if transaction.authorization_list.is_some() {
         return Err(ValidationError::not_supported(
             "`transaction.authorizationList` is not supported yet",
@@ -140,17 +179,44 @@ fn map_block_ref(block: BlockRef) -> Result<service_espace::EspaceBlockRef, Vali
     match block {
         BlockRef::Tag(value) => match value.as_str() {
             "latest" => Ok(service_espace::EspaceBlockRef::Latest),
-            value => Ok(service_espace::EspaceBlockRef::Number(parse_u64_param(
-                value, "block",
-            )?)),
+            value => match H256::from_str(value) {
+                Ok(hash) => Ok(service_espace::EspaceBlockRef::Hash(cfx_h256_to_alloy(
+                    hash,
+                ))),
+                Err(_) => Ok(service_espace::EspaceBlockRef::Number(parse_u64_param(
+                    value, "block",
+                )?)),
+            },
         },
-        BlockRef::Hash(_) => Err(ValidationError::not_supported(
-            "eSpace block hash selectors are not supported yet",
-        )),
+        BlockRef::Hash(block) => Ok(service_espace::EspaceBlockRef::Hash(cfx_h256_to_alloy(
+            block.block_hash,
+        ))),
     }
 }
 
-fn map_transaction(
+fn map_block_overrides(
+    overrides: BlockOverrides,
+) -> Result<service_espace::EspaceBlockOverrides, ValidationError> {
+    Ok(service_espace::EspaceBlockOverrides {
+        number: overrides
+            .number
+            .map(|value| u64_param(value, "options.blockOverrides.number"))
+            .transpose()?,
+        timestamp: overrides
+            .timestamp
+            .map(|value| u64_param(value, "options.blockOverrides.timestamp"))
+            .transpose()?,
+        base_fee_per_gas: overrides.base_fee_per_gas.map(cfx_u256_to_alloy),
+        coinbase: overrides.coinbase.map(cfx_address_to_alloy),
+        gas_limit: overrides
+            .gas_limit
+            .map(|value| u64_param(value, "options.blockOverrides.gasLimit"))
+            .transpose()?,
+        prevrandao: overrides.prev_randao.map(cfx_h256_to_alloy),
+    })
+}
+
+pub(super) fn map_transaction(
     transaction: TransactionRequest,
 ) -> Result<service_espace::ConfluxTransactionRequest, ValidationError> {
     let transaction_type = map_transaction_type(transaction.transaction_type)?;
@@ -186,28 +252,31 @@ fn map_transaction(
         .map(|input| Bytes::from(input.to_vec()));
     let variant = TransactionVariantRequest::try_new(
         transaction_type,
-        access_list.map(|items| {
-            items
-                .into_iter()
-                .map(|item| simulation_transaction::AccessListItem {
-                    address: cfx_address_to_alloy(item.address),
-                    storage_keys: item
-                        .storage_keys
-                        .into_iter()
-                        .map(cfx_h256_to_alloy)
-                        .collect(),
-                })
-                .collect()
-        }),
-        gas_price
-            .map(|value| u128_param(value, "transaction.gasPrice"))
-            .transpose()?,
-        max_fee_per_gas
-            .map(|value| u128_param(value, "transaction.maxFeePerGas"))
-            .transpose()?,
-        max_priority_fee_per_gas
-            .map(|value| u128_param(value, "transaction.maxPriorityFeePerGas"))
-            .transpose()?,
+        TransactionVariantFields {
+            access_list: access_list.map(|items| {
+                items
+                    .into_iter()
+                    .map(|item| simulation_transaction::AccessListItem {
+                        address: cfx_address_to_alloy(item.address),
+                        storage_keys: item
+                            .storage_keys
+                            .into_iter()
+                            .map(cfx_h256_to_alloy)
+                            .collect(),
+                    })
+                    .collect()
+            }),
+            gas_price: gas_price
+                .map(|value| u128_param(value, "transaction.gasPrice"))
+                .transpose()?,
+            max_fee_per_gas: max_fee_per_gas
+                .map(|value| u128_param(value, "transaction.maxFeePerGas"))
+                .transpose()?,
+            max_priority_fee_per_gas: max_priority_fee_per_gas
+                .map(|value| u128_param(value, "transaction.maxPriorityFeePerGas"))
+                .transpose()?,
+            ..Default::default()
+        },
     )
     .map_err(|error| ValidationError::invalid_params(error.to_string()))?;
 
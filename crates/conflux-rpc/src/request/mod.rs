@@ -6,7 +6,9 @@ use cfx_types::{Address as CfxAddress, H256, U256};
 
 use crate::error::ValidationError;
 
-pub(crate) use core_space::SimulateCoreSpaceTransactionRequest;
+pub(crate) use core_space::{
+    ReplayCoreSpaceTransactionRequest, SimulateBundleRequest, SimulateCoreSpaceTransactionRequest,
+};
 pub(crate) use espace::SimulateEspaceTransactionRequest;
 
 fn u64_param(value: U256, field: &str) -> Result<u64, ValidationError> {
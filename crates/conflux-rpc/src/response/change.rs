@@ -1,6 +1,9 @@
-use alloy_primitives::{Address, U256};
+use alloy_primitives::{Address, U64, U256};
 use conflux_service::espace as service_espace;
 use serde::Serialize;
+use simulation_changes::PERMIT2_UNLIMITED_AMOUNT;
+
+use super::format_units;
 
 #[derive(Debug, Clone, Serialize, PartialEq, Eq)]
 #[serde(
@@ -31,6 +34,25 @@ pub(super) enum Change {
         owner: Address,
         spender: Address,
     },
+    Permit {
+        #[serde(flatten)]
+        asset: AllowanceAsset,
+        owner: Address,
+        spender: Address,
+        nonce_before: U256,
+        nonce_after: U256,
+    },
+    Permit2Allowance {
+        #[serde(flatten)]
+        asset: AllowanceAsset,
+        permit2_address: Address,
+        owner: Address,
+        spender: Address,
+        expiration_before: U64,
+        expiration_after: U64,
+        nonce_before: U64,
+        nonce_after: U64,
+    },
     TokenApproval {
         #[serde(flatten)]
         asset: TokenApprovalAsset,
@@ -43,6 +65,25 @@ pub(super) enum Change {
         approved_before: bool,
         approved_after: bool,
     },
+    Delegation {
+        authority: Address,
+        delegate_before: Option<Address>,
+        delegate_after: Option<Address>,
+    },
+    ProxyUpgrade {
+        proxy: Address,
+        slot: ProxySlot,
+        address_before: Option<Address>,
+        address_after: Option<Address>,
+    },
+}
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub(super) enum ProxySlot {
+    Implementation,
+    Admin,
+    Beacon,
 }
 
 #[derive(Debug, Clone, Serialize, PartialEq, Eq)]
@@ -54,12 +95,16 @@ pub(super) enum Change {
 pub(super) enum TransferAsset {
     Native {
         raw_amount: U256,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        formatted_amount: Option<String>,
         #[serde(flatten)]
         metadata: NativeMetadata,
     },
     Erc20 {
         contract_address: Address,
         raw_amount: U256,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        formatted_amount: Option<String>,
         #[serde(flatten)]
         metadata: Erc20Metadata,
     },
@@ -86,6 +131,8 @@ pub(super) enum TokenMovementAsset {
     Erc20 {
         contract_address: Address,
         raw_amount: U256,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        formatted_amount: Option<String>,
         #[serde(flatten)]
         metadata: Erc20Metadata,
     },
@@ -113,6 +160,15 @@ pub(super) enum AllowanceAsset {
         contract_address: Address,
         raw_amount_before: U256,
         raw_amount_after: U256,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        formatted_amount_before: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        formatted_amount_after: Option<String>,
+        /// Set with formatted amounts; `true` for an unlimited allowance.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        unlimited_before: Option<bool>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        unlimited_after: Option<bool>,
         #[serde(flatten)]
         metadata: Erc20Metadata,
     },
@@ -160,7 +216,7 @@ pub(super) struct NativeMetadata {
     #[serde(skip_serializing_if = "Option::is_none")]
     symbol: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    decimals: Option<u8>,
+    pub(super) decimals: Option<u8>,
 }
 
 #[derive(Debug, Clone, Serialize, PartialEq, Eq)]
@@ -171,7 +227,7 @@ pub(super) struct Erc20Metadata {
     #[serde(skip_serializing_if = "Option::is_none")]
     symbol: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    decimals: Option<u8>,
+    pub(super) decimals: Option<u8>,
 }
 
 #[derive(Debug, Clone, Serialize, PartialEq, Eq)]
@@ -223,6 +279,7 @@ impl From<service_espace::Change> for Change {
             } => Self::Transfer {
                 asset: TransferAsset::Native {
                     raw_amount,
+                    formatted_amount: None,
                     metadata: metadata.into(),
                 },
                 from,
@@ -238,6 +295,7 @@ impl From<service_espace::Change> for Change {
                 asset: TransferAsset::Erc20 {
                     contract_address,
                     raw_amount,
+                    formatted_amount: None,
                     metadata: metadata.into(),
                 },
                 from,
@@ -252,6 +310,7 @@ impl From<service_espace::Change> for Change {
                 asset: TokenMovementAsset::Erc20 {
                     contract_address,
                     raw_amount,
+                    formatted_amount: None,
                     metadata: metadata.into(),
                 },
                 to,
@@ -265,6 +324,7 @@ impl From<service_espace::Change> for Change {
                 asset: TokenMovementAsset::Erc20 {
                     contract_address,
                     raw_amount,
+                    formatted_amount: None,
                     metadata: metadata.into(),
                 },
                 from,
@@ -359,15 +419,58 @@ impl From<service_espace::Change> for Change {
                 raw_amount_after,
                 metadata,
             } => Self::Allowance {
-                asset: AllowanceAsset::Erc20 {
+                asset: AllowanceAsset::erc20(
                     contract_address,
                     raw_amount_before,
                     raw_amount_after,
-                    metadata: metadata.into(),
-                },
+                    metadata,
+                ),
                 owner,
                 spender,
             },
+            service_espace::Change::Erc20Permit {
+                contract_address,
+                owner,
+                spender,
+                raw_amount_before,
+                raw_amount_after,
+                nonce_before,
+                nonce_after,
+                metadata,
+            } => Self::Permit {
+                asset: AllowanceAsset::erc20(
+                    contract_address,
+                    raw_amount_before,
+                    raw_amount_after,
+                    metadata,
+                ),
+                owner,
+                spender,
+                nonce_before,
+                nonce_after,
+            },
+            service_espace::Change::Permit2Allowance {
+                contract_address,
+                token,
+                owner,
+                spender,
+                raw_amount_before,
+                raw_amount_after,
+                expiration_before,
+                expiration_after,
+                nonce_before,
+                nonce_after,
+                metadata,
+            } => Self::Permit2Allowance {
+                asset: AllowanceAsset::erc20(token, raw_amount_before, raw_amount_after, metadata),
+                permit2_address: contract_address,
+                owner,
+                spender,
+                expiration_before: U64::from(expiration_before),
+                expiration_after: U64::from(expiration_after),
+                nonce_before: U64::from(nonce_before),
+                nonce_after: U64::from(nonce_after),
+            },
             service_espace::Change::Erc721TokenApproval {
                 contract_address,
                 token_id,
@@ -413,6 +516,135 @@ impl From<service_espace::Change> for Change {
                 approved_before,
                 approved_after,
             },
+            service_espace::Change::Delegation {
+                authority,
+                delegate_before,
+                delegate_after,
+            } => Self::Delegation {
+                authority,
+                delegate_before,
+                delegate_after,
+            },
+            service_espace::Change::ProxyUpgrade {
+                proxy,
+                slot,
+                address_before,
+                address_after,
+            } => Self::ProxyUpgrade {
+                proxy,
+                slot: slot.into(),
+                address_before,
+                address_after,
+            },
+        }
+    }
+}
+
+impl From<service_espace::ProxySlot> for ProxySlot {
+    fn from(slot: service_espace::ProxySlot) -> Self {
+        match slot {
+            service_espace::ProxySlot::Implementation => Self::Implementation,
+            service_espace::ProxySlot::Admin => Self::Admin,
+            service_espace::ProxySlot::Beacon => Self::Beacon,
+        }
+    }
+}
+
+impl Change {
+    /// Adds exact decimal strings next to the raw amounts whose decimals are
+    /// known.
+    pub(super) fn format_amounts(&mut self) {
+        match self {
+            Self::Transfer {
+                asset:
+                    TransferAsset::Native {
+                        raw_amount,
+                        formatted_amount,
+                        metadata: NativeMetadata { decimals, .. },
+                    }
+                    | TransferAsset::Erc20 {
+                        raw_amount,
+                        formatted_amount,
+                        metadata: Erc20Metadata { decimals, .. },
+                        ..
+                    },
+                ..
+            }
+            | Self::Mint {
+                asset:
+                    TokenMovementAsset::Erc20 {
+                        raw_amount,
+                        formatted_amount,
+                        metadata: Erc20Metadata { decimals, .. },
+                        ..
+                    },
+                ..
+            }
+            | Self::Burn {
+                asset:
+                    TokenMovementAsset::Erc20 {
+                        raw_amount,
+                        formatted_amount,
+                        metadata: Erc20Metadata { decimals, .. },
+                        ..
+                    },
+                ..
+            } => {
+                *formatted_amount = decimals.map(|decimals| format_units(*raw_amount, decimals));
+            }
+            Self::Allowance { asset, .. } | Self::Permit { asset, .. } => {
+                asset.format_amounts(U256::MAX);
+            }
+            Self::Permit2Allowance { asset, .. } => {
+                asset.format_amounts(PERMIT2_UNLIMITED_AMOUNT);
+            }
+            Self::Transfer { .. }
+            | Self::Mint { .. }
+            | Self::Burn { .. }
+            | Self::TokenApproval { .. }
+            | Self::OperatorApproval { .. }
+            | Self::Delegation { .. }
+            | Self::ProxyUpgrade { .. } => {}
+        }
+    }
+}
+
+impl AllowanceAsset {
+    fn erc20(
+        contract_address: Address,
+        raw_amount_before: U256,
+        raw_amount_after: U256,
+        metadata: service_espace::Erc20Metadata,
+    ) -> Self {
+        Self::Erc20 {
+            contract_address,
+            raw_amount_before,
+            raw_amount_after,
+            formatted_amount_before: None,
+            formatted_amount_after: None,
+            unlimited_before: None,
+            unlimited_after: None,
+            metadata: metadata.into(),
+        }
+    }
+
+    fn format_amounts(&mut self, unlimited_amount: U256) {
+        let Self::Erc20 {
+            raw_amount_before,
+            raw_amount_after,
+            formatted_amount_before,
+            formatted_amount_after,
+            unlimited_before,
+            unlimited_after,
+            metadata,
+            ..
+        } = self;
+
+        *unlimited_before = Some(*raw_amount_before == unlimited_amount);
+        *unlimited_after = Some(*raw_amount_after == unlimited_amount);
+        if let Some(decimals) = metadata.decimals {
+            *formatted_amount_before = Some(format_units(*raw_amount_before, decimals));
+            *formatted_amount_after = Some(format_units(*raw_amount_after, decimals));
         }
     }
 }
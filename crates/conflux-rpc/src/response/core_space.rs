@@ -5,13 +5,18 @@ use cfx_types::{Address, H256, U64, U256};
 use conflux_service::core_space as service_core_space;
 use serde::Serialize;
 
-use super::{core_space_change, u256_to_wire};
+use super::{
+    CFX_DECIMALS, change::Change, core_space_change, espace::SimulateEspaceTransactionResponse,
+    format_units, u256_to_wire,
+};
 
 #[derive(Debug, thiserror::Error)]
-#[error("failed to encode `{field}` as a Core Space address: {message}")]
-pub(crate) struct ResponseMappingError {
-    field: String,
-    message: String,
+pub(crate) enum ResponseMappingError {
+    #[error("failed to encode `{field}` as a Core Space address: {message}")]
+    Address { field: String, message: String },
+
+    #[error("`{field}` holds a {kind} change, which Core Space simulations do not report")]
+    UnsupportedChange { field: String, kind: &'static str },
 }
 
 #[derive(Debug, Clone, Serialize, PartialEq, Eq)]
@@ -21,6 +26,21 @@ pub(crate) struct SimulateCoreSpaceTransactionResponse {
     changes: Vec<core_space_change::Change>,
 }
 
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct SimulateBundleResponse {
+    steps: Vec<BundleStep>,
+    core_space_changes: Vec<core_space_change::Change>,
+    espace_changes: Vec<Change>,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+enum BundleStep {
+    CoreSpace(SimulateCoreSpaceTransactionResponse),
+    Espace(SimulateEspaceTransactionResponse),
+}
+
 #[derive(Debug, Clone, Serialize, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
 struct CoreSpaceExecution {
@@ -32,6 +52,10 @@ struct CoreSpaceExecution {
     gas_charged: U256,
     fee: U256,
     burnt_fee: Option<U256>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    formatted_fee: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    formatted_burnt_fee: Option<String>,
     gas_covered_by_sponsor: bool,
     storage_covered_by_sponsor: bool,
     output: CoreSpaceRpcBytes,
@@ -96,6 +120,61 @@ impl SimulateCoreSpaceTransactionResponse {
             changes: core_space_change::try_map_changes(changes, network)?,
         })
     }
+
+    /// Adds exact decimal strings next to fees and the raw amounts whose
+    /// decimals are known.
+    pub(crate) fn format_amounts(&mut self) {
+        self.execution.formatted_fee = Some(format_units(self.execution.fee, CFX_DECIMALS));
+        self.execution.formatted_burnt_fee = self
+            .execution
+            .burnt_fee
+            .map(|burnt_fee| format_units(burnt_fee, CFX_DECIMALS));
+
+        for change in &mut self.changes {
+            change.format_amounts();
+        }
+    }
+}
+
+impl SimulateBundleResponse {
+    pub(crate) fn try_from_output(
+        simulation: service_core_space::SimulateCoreSpaceBundleOutput,
+        network: Network,
+    ) -> Result<Self, ResponseMappingError> {
+        let (steps, core_space_changes, espace_changes) = simulation.into_parts();
+        Ok(Self {
+            steps: steps
+                .into_iter()
+                .map(|step| match step {
+                    service_core_space::CoreSpaceBundleStep::CoreSpace(simulation) => {
+                        SimulateCoreSpaceTransactionResponse::try_from_output(simulation, network)
+                            .map(BundleStep::CoreSpace)
+                    }
+                    service_core_space::CoreSpaceBundleStep::Espace(simulation) => Ok(
+                        BundleStep::Espace(SimulateEspaceTransactionResponse::from(simulation)),
+                    ),
+                })
+                .collect::<Result<_, _>>()?,
+            core_space_changes: core_space_change::try_map_changes(core_space_changes, network)?,
+            espace_changes: espace_changes.into_iter().map(Change::from).collect(),
+        })
+    }
+
+    /// Adds exact decimal strings to every step and to the net changes.
+    pub(crate) fn format_amounts(&mut self) {
+        for step in &mut self.steps {
+            match step {
+                BundleStep::CoreSpace(simulation) => simulation.format_amounts(),
+                BundleStep::Espace(simulation) => simulation.format_amounts(),
+            }
+        }
+        for change in &mut self.core_space_changes {
+            change.format_amounts();
+        }
+        for change in &mut self.espace_changes {
+            change.format_amounts();
+        }
+    }
 }
 
 impl CoreSpaceExecution {
@@ -161,6 +240,8 @@ impl CoreSpaceExecution {
             gas_charged,
             fee,
             burnt_fee,
+            formatted_fee: None,
+            formatted_burnt_fee: None,
             gas_covered_by_sponsor,
             storage_covered_by_sponsor,
             output,
@@ -244,5 +325,5 @@ pub(super) fn map_core_space_address(
     field: String,
 ) -> Result<RpcAddress, ResponseMappingError> {
     RpcAddress::try_from_h160(address, network)
-        .map_err(|message| ResponseMappingError { field, message })
+        .map_err(|message| ResponseMappingError::Address { field, message })
 }
@@ -4,12 +4,13 @@ use cfx_rpc_cfx_types::RpcAddress;
 use cfx_types::{H256, U64, U256};
 use conflux_service::core_space as service_core_space;
 use serde::Serialize;
+use simulation_changes::PERMIT2_UNLIMITED_AMOUNT;
 
 use super::{
-    b256_to_wire,
+    CFX_DECIMALS, b256_to_wire,
     change::{Erc20Metadata, Erc721CollectionMetadata, NativeMetadata},
     core_space::{ResponseMappingError, map_core_space_address},
-    u256_to_wire,
+    format_units, u256_to_wire,
 };
 
 #[derive(Debug, Clone, Serialize, PartialEq, Eq)]
@@ -41,6 +42,25 @@ pub(super) enum Change {
         owner: RpcAddress,
         spender: RpcAddress,
     },
+    Permit {
+        #[serde(flatten)]
+        asset: AllowanceAsset,
+        owner: RpcAddress,
+        spender: RpcAddress,
+        nonce_before: U256,
+        nonce_after: U256,
+    },
+    Permit2Allowance {
+        #[serde(flatten)]
+        asset: AllowanceAsset,
+        permit2_address: RpcAddress,
+        owner: RpcAddress,
+        spender: RpcAddress,
+        expiration_before: U64,
+        expiration_after: U64,
+        nonce_before: U64,
+        nonce_after: U64,
+    },
     TokenApproval {
         #[serde(flatten)]
         asset: TokenApprovalAsset,
@@ -56,33 +76,49 @@ pub(super) enum Change {
     StakingDeposit {
         account: RpcAddress,
         raw_amount: U256,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        formatted_amount: Option<String>,
     },
     StakingWithdrawal {
         account: RpcAddress,
         raw_amount: U256,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        formatted_amount: Option<String>,
         reward_raw_amount: U256,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        reward_formatted_amount: Option<String>,
     },
     StakingBurn {
         account: RpcAddress,
         raw_amount: U256,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        formatted_amount: Option<String>,
     },
     StakingVoteLock {
         account: RpcAddress,
         unlock_block_number: U64,
         required_locked_raw_amount_before: U256,
         required_locked_raw_amount_after: U256,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        required_locked_formatted_amount_before: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        required_locked_formatted_amount_after: Option<String>,
     },
     PosRegistration {
         account: RpcAddress,
         pos_identifier: H256,
         newly_locked_vote_count: U64,
         newly_locked_raw_amount: U256,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        newly_locked_formatted_amount: Option<String>,
     },
     PosStakeIncrease {
         account: RpcAddress,
         pos_identifier: H256,
         newly_locked_vote_count: U64,
         newly_locked_raw_amount: U256,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        newly_locked_formatted_amount: Option<String>,
     },
     PosRetirementRequest {
         account: RpcAddress,
@@ -94,12 +130,16 @@ pub(super) enum Change {
         sponsor: RpcAddress,
         contract_address: RpcAddress,
         raw_amount: U256,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        formatted_amount: Option<String>,
     },
     SponsorshipRefund {
         sponsored_resource: SponsoredResource,
         sponsor: RpcAddress,
         contract_address: RpcAddress,
         raw_amount: U256,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        formatted_amount: Option<String>,
     },
     SponsorshipConfiguration {
         contract_address: RpcAddress,
@@ -115,11 +155,15 @@ pub(super) enum Change {
     StoragePointConversion {
         contract_address: RpcAddress,
         converted_cfx_raw_amount: U256,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        converted_cfx_formatted_amount: Option<String>,
     },
     CrossSpaceTransfer {
         from: CrossSpaceAddress,
         to: CrossSpaceAddress,
         raw_amount: U256,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        formatted_amount: Option<String>,
     },
 }
 
@@ -132,12 +176,16 @@ pub(super) enum Change {
 pub(super) enum TransferAsset {
     Native {
         raw_amount: U256,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        formatted_amount: Option<String>,
         #[serde(flatten)]
         metadata: NativeMetadata,
     },
     Erc20 {
         contract_address: RpcAddress,
         raw_amount: U256,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        formatted_amount: Option<String>,
         #[serde(flatten)]
         metadata: Erc20Metadata,
     },
@@ -164,6 +212,8 @@ pub(super) enum MintAsset {
     Erc20 {
         contract_address: RpcAddress,
         raw_amount: U256,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        formatted_amount: Option<String>,
         #[serde(flatten)]
         metadata: Erc20Metadata,
     },
@@ -189,12 +239,16 @@ pub(super) enum MintAsset {
 pub(super) enum BurnAsset {
     Native {
         raw_amount: U256,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        formatted_amount: Option<String>,
         #[serde(flatten)]
         metadata: NativeMetadata,
     },
     Erc20 {
         contract_address: RpcAddress,
         raw_amount: U256,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        formatted_amount: Option<String>,
         #[serde(flatten)]
         metadata: Erc20Metadata,
     },
@@ -222,6 +276,15 @@ pub(super) enum AllowanceAsset {
         contract_address: RpcAddress,
         raw_amount_before: U256,
         raw_amount_after: U256,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        formatted_amount_before: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        formatted_amount_after: Option<String>,
+        /// Set with formatted amounts; `true` for an unlimited allowance.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        unlimited_before: Option<bool>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        unlimited_after: Option<bool>,
         #[serde(flatten)]
         metadata: Erc20Metadata,
     },
@@ -280,6 +343,10 @@ pub(super) enum SponsorshipConfiguration {
         sponsor_after: Option<RpcAddress>,
         max_sponsored_gas_fee_raw_amount_before: U256,
         max_sponsored_gas_fee_raw_amount_after: U256,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        max_sponsored_gas_fee_formatted_amount_before: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        max_sponsored_gas_fee_formatted_amount_after: Option<String>,
     },
     StorageCollateral {
         sponsor_before: Option<RpcAddress>,
@@ -309,6 +376,181 @@ pub(super) enum CrossSpaceAddress {
     Espace { address: Address },
 }
 
+impl Change {
+    /// Adds exact decimal strings next to CFX amounts and the token amounts
+    /// whose decimals are known.
+    pub(super) fn format_amounts(&mut self) {
+        match self {
+            Self::Transfer {
+                asset:
+                    TransferAsset::Native {
+                        raw_amount,
+                        formatted_amount,
+                        metadata: NativeMetadata { decimals, .. },
+                    }
+                    | TransferAsset::Erc20 {
+                        raw_amount,
+                        formatted_amount,
+                        metadata: Erc20Metadata { decimals, .. },
+                        ..
+                    },
+                ..
+            }
+            | Self::Mint {
+                asset:
+                    MintAsset::Erc20 {
+                        raw_amount,
+                        formatted_amount,
+                        metadata: Erc20Metadata { decimals, .. },
+                        ..
+                    },
+                ..
+            }
+            | Self::Burn {
+                asset:
+                    BurnAsset::Native {
+                        raw_amount,
+                        formatted_amount,
+                        metadata: NativeMetadata { decimals, .. },
+                    }
+                    | BurnAsset::Erc20 {
+                        raw_amount,
+                        formatted_amount,
+                        metadata: Erc20Metadata { decimals, .. },
+                        ..
+                    },
+                ..
+            } => {
+                *formatted_amount = decimals.map(|decimals| format_units(*raw_amount, decimals));
+            }
+            Self::Allowance { asset, .. } | Self::Permit { asset, .. } => {
+                asset.format_amounts(U256::MAX);
+            }
+            Self::Permit2Allowance { asset, .. } => {
+                asset.format_amounts(u256_to_wire(PERMIT2_UNLIMITED_AMOUNT));
+            }
+            Self::StakingDeposit {
+                raw_amount,
+                formatted_amount,
+                ..
+            }
+            | Self::StakingBurn {
+                raw_amount,
+                formatted_amount,
+                ..
+            }
+            | Self::SponsorshipDeposit {
+                raw_amount,
+                formatted_amount,
+                ..
+            }
+            | Self::SponsorshipRefund {
+                raw_amount,
+                formatted_amount,
+                ..
+            }
+            | Self::CrossSpaceTransfer {
+                raw_amount,
+                formatted_amount,
+                ..
+            }
+            | Self::StoragePointConversion {
+                converted_cfx_raw_amount: raw_amount,
+                converted_cfx_formatted_amount: formatted_amount,
+                ..
+            }
+            | Self::PosRegistration {
+                newly_locked_raw_amount: raw_amount,
+                newly_locked_formatted_amount: formatted_amount,
+                ..
+            }
+            | Self::PosStakeIncrease {
+                newly_locked_raw_amount: raw_amount,
+                newly_locked_formatted_amount: formatted_amount,
+                ..
+            } => {
+                *formatted_amount = Some(format_units(*raw_amount, CFX_DECIMALS));
+            }
+            Self::StakingWithdrawal {
+                raw_amount,
+                formatted_amount,
+                reward_raw_amount,
+                reward_formatted_amount,
+                ..
+            } => {
+                *formatted_amount = Some(format_units(*raw_amount, CFX_DECIMALS));
+                *reward_formatted_amount = Some(format_units(*reward_raw_amount, CFX_DECIMALS));
+            }
+            Self::StakingVoteLock {
+                required_locked_raw_amount_before,
+                required_locked_raw_amount_after,
+                required_locked_formatted_amount_before,
+                required_locked_formatted_amount_after,
+                ..
+            } => {
+                *required_locked_formatted_amount_before = Some(format_units(
+                    *required_locked_raw_amount_before,
+                    CFX_DECIMALS,
+                ));
+                *required_locked_formatted_amount_after = Some(format_units(
+                    *required_locked_raw_amount_after,
+                    CFX_DECIMALS,
+                ));
+            }
+            Self::SponsorshipConfiguration {
+                configuration:
+                    SponsorshipConfiguration::Gas {
+                        max_sponsored_gas_fee_raw_amount_before,
+                        max_sponsored_gas_fee_raw_amount_after,
+                        max_sponsored_gas_fee_formatted_amount_before,
+                        max_sponsored_gas_fee_formatted_amount_after,
+                        ..
+                    },
+                ..
+            } => {
+                *max_sponsored_gas_fee_formatted_amount_before = Some(format_units(
+                    *max_sponsored_gas_fee_raw_amount_before,
+                    CFX_DECIMALS,
+                ));
+                *max_sponsored_gas_fee_formatted_amount_after = Some(format_units(
+                    *max_sponsored_gas_fee_raw_amount_after,
+                    CFX_DECIMALS,
+                ));
+            }
+            Self::Transfer { .. }
+            | Self::Mint { .. }
+            | Self::Burn { .. }
+            | Self::TokenApproval { .. }
+            | Self::OperatorApproval { .. }
+            | Self::PosRetirementRequest { .. }
+            | Self::SponsorshipConfiguration { .. }
+            | Self::SponsorshipEligibilityRule { .. } => {}
+        }
+    }
+}
+
+impl AllowanceAsset {
+    fn format_amounts(&mut self, unlimited_amount: U256) {
+        let Self::Erc20 {
+            raw_amount_before,
+            raw_amount_after,
+            formatted_amount_before,
+            formatted_amount_after,
+            unlimited_before,
+            unlimited_after,
+            metadata,
+            ..
+        } = self;
+
+        *unlimited_before = Some(*raw_amount_before == unlimited_amount);
+        *unlimited_after = Some(*raw_amount_after == unlimited_amount);
+        if let Some(decimals) = metadata.decimals {
+            *formatted_amount_before = Some(format_units(*raw_amount_before, decimals));
+            *formatted_amount_after = Some(format_units(*raw_amount_after, decimals));
+        }
+    }
+}
+
 pub(super) fn try_map_changes(
     changes: Vec<service_core_space::CoreSpaceChange>,
     network: Network,
@@ -335,6 +577,7 @@ fn try_map_change(
         } => Change::StakingDeposit {
             account: map_address(account, network, field, "account")?,
             raw_amount: u256_to_wire(raw_amount),
+            formatted_amount: None,
         },
         Source::StakingWithdrawal {
             account,
@@ -343,7 +586,9 @@ fn try_map_change(
         } => Change::StakingWithdrawal {
             account: map_address(account, network, field, "account")?,
             raw_amount: u256_to_wire(raw_amount),
+            formatted_amount: None,
             reward_raw_amount: u256_to_wire(reward_raw_amount),
+            reward_formatted_amount: None,
         },
         Source::NativeBurn {
             from,
@@ -352,6 +597,7 @@ fn try_map_change(
         } => Change::Burn {
             asset: BurnAsset::Native {
                 raw_amount: u256_to_wire(raw_amount),
+                formatted_amount: None,
                 metadata: metadata.into(),
             },
             from: map_address(from, network, field, "from")?,
@@ -362,6 +608,7 @@ fn try_map_change(
         } => Change::StakingBurn {
             account: map_address(account, network, field, "account")?,
             raw_amount: u256_to_wire(raw_amount),
+            formatted_amount: None,
         },
         Source::StakingVoteLock {
             account,
@@ -373,6 +620,8 @@ fn try_map_change(
             unlock_block_number: unlock_block_number.into(),
             required_locked_raw_amount_before: u256_to_wire(required_locked_raw_amount_before),
             required_locked_raw_amount_after: u256_to_wire(required_locked_raw_amount_after),
+            required_locked_formatted_amount_before: None,
+            required_locked_formatted_amount_after: None,
         },
         Source::PoSRegistration {
             account,
@@ -384,6 +633,7 @@ fn try_map_change(
             pos_identifier: b256_to_wire(pos_identifier),
             newly_locked_vote_count: newly_locked_vote_count.into(),
             newly_locked_raw_amount: u256_to_wire(newly_locked_raw_amount),
+            newly_locked_formatted_amount: None,
         },
         Source::PoSStakeIncrease {
             account,
@@ -395,6 +645,7 @@ fn try_map_change(
             pos_identifier: b256_to_wire(pos_identifier),
             newly_locked_vote_count: newly_locked_vote_count.into(),
             newly_locked_raw_amount: u256_to_wire(newly_locked_raw_amount),
+            newly_locked_formatted_amount: None,
         },
         Source::PoSRetirementRequest {
             account,
@@ -415,6 +666,7 @@ fn try_map_change(
             sponsor: map_address(sponsor, network, field, "sponsor")?,
             contract_address: map_address(contract_address, network, field, "contractAddress")?,
             raw_amount: u256_to_wire(raw_amount),
+            formatted_amount: None,
         },
         Source::SponsorshipRefund {
             sponsored_resource,
@@ -426,6 +678,7 @@ fn try_map_change(
             sponsor: map_address(sponsor, network, field, "sponsor")?,
             contract_address: map_address(contract_address, network, field, "contractAddress")?,
             raw_amount: u256_to_wire(raw_amount),
+            formatted_amount: None,
         },
         Source::SponsorshipConfiguration {
             contract_address,
@@ -451,6 +704,7 @@ fn try_map_change(
         } => Change::StoragePointConversion {
             contract_address: map_address(contract_address, network, field, "contractAddress")?,
             converted_cfx_raw_amount: u256_to_wire(converted_cfx_raw_amount),
+            converted_cfx_formatted_amount: None,
         },
         Source::CrossSpaceTransfer {
             from,
@@ -460,6 +714,7 @@ fn try_map_change(
             from: try_map_cross_space_address(from, network, field, "from")?,
             to: try_map_cross_space_address(to, network, field, "to")?,
             raw_amount: u256_to_wire(raw_amount),
+            formatted_amount: None,
         },
     })
 }
@@ -480,6 +735,7 @@ fn try_map_asset_change(
         } => Change::Transfer {
             asset: TransferAsset::Native {
                 raw_amount: u256_to_wire(raw_amount),
+                formatted_amount: None,
                 metadata: metadata.into(),
             },
             from: map_address(from, network, field, "from")?,
@@ -495,6 +751,7 @@ fn try_map_asset_change(
             asset: TransferAsset::Erc20 {
                 contract_address: map_address(contract_address, network, field, "contractAddress")?,
                 raw_amount: u256_to_wire(raw_amount),
+                formatted_amount: None,
                 metadata: metadata.into(),
             },
             from: map_address(from, network, field, "from")?,
@@ -509,6 +766,7 @@ fn try_map_asset_change(
             asset: MintAsset::Erc20 {
                 contract_address: map_address(contract_address, network, field, "contractAddress")?,
                 raw_amount: u256_to_wire(raw_amount),
+                formatted_amount: None,
                 metadata: metadata.into(),
             },
             to: map_address(to, network, field, "to")?,
@@ -522,6 +780,7 @@ fn try_map_asset_change(
             asset: BurnAsset::Erc20 {
                 contract_address: map_address(contract_address, network, field, "contractAddress")?,
                 raw_amount: u256_to_wire(raw_amount),
+                formatted_amount: None,
                 metadata: metadata.into(),
             },
             from: map_address(from, network, field, "from")?,
@@ -620,10 +879,70 @@ fn try_map_asset_change(
                 contract_address: map_address(contract_address, network, field, "contractAddress")?,
                 raw_amount_before: u256_to_wire(raw_amount_before),
                 raw_amount_after: u256_to_wire(raw_amount_after),
+                formatted_amount_before: None,
+                formatted_amount_after: None,
+                unlimited_before: None,
+                unlimited_after: None,
+                metadata: metadata.into(),
+            },
+            owner: map_address(owner, network, field, "owner")?,
+            spender: map_address(spender, network, field, "spender")?,
+        },
+        Source::Erc20Permit {
+            contract_address,
+            owner,
+            spender,
+            raw_amount_before,
+            raw_amount_after,
+            nonce_before,
+            nonce_after,
+            metadata,
+        } => Change::Permit {
+            asset: AllowanceAsset::Erc20 {
+                contract_address: map_address(contract_address, network, field, "contractAddress")?,
+                raw_amount_before: u256_to_wire(raw_amount_before),
+                raw_amount_after: u256_to_wire(raw_amount_after),
+                formatted_amount_before: None,
+                formatted_amount_after: None,
+                unlimited_before: None,
+                unlimited_after: None,
                 metadata: metadata.into(),
             },
             owner: map_address(owner, network, field, "owner")?,
             spender: map_address(spender, network, field, "spender")?,
+            nonce_before: u256_to_wire(nonce_before),
+            nonce_after: u256_to_wire(nonce_after),
+        },
+        Source::Permit2Allowance {
+            contract_address,
+            token,
+            owner,
+            spender,
+            raw_amount_before,
+            raw_amount_after,
+            expiration_before,
+            expiration_after,
+            nonce_before,
+            nonce_after,
+            metadata,
+        } => Change::Permit2Allowance {
+            asset: AllowanceAsset::Erc20 {
+                contract_address: map_address(token, network, field, "contractAddress")?,
+                raw_amount_before: u256_to_wire(raw_amount_before),
+                raw_amount_after: u256_to_wire(raw_amount_after),
+                formatted_amount_before: None,
+                formatted_amount_after: None,
+                unlimited_before: None,
+                unlimited_after: None,
+                metadata: metadata.into(),
+            },
+            permit2_address: map_address(contract_address, network, field, "permit2Address")?,
+            owner: map_address(owner, network, field, "owner")?,
+            spender: map_address(spender, network, field, "spender")?,
+            expiration_before: expiration_before.into(),
+            expiration_after: expiration_after.into(),
+            nonce_before: nonce_before.into(),
+            nonce_after: nonce_after.into(),
         },
         Source::Erc721TokenApproval {
             contract_address,
@@ -682,6 +1001,20 @@ fn try_map_asset_change(
             approved_before,
             approved_after,
         },
+        // Core Space transactions carry no EIP-7702 authorizations.
+        Source::Delegation { .. } => {
+            return Err(ResponseMappingError::UnsupportedChange {
+                field: field.to_owned(),
+                kind: "delegation",
+            });
+        }
+        // Core Space analysis does not inspect EIP-1967 proxy slots.
+        Source::ProxyUpgrade { .. } => {
+            return Err(ResponseMappingError::UnsupportedChange {
+                field: field.to_owned(),
+                kind: "proxy upgrade",
+            });
+        }
     })
 }
 
@@ -710,6 +1043,8 @@ fn try_map_sponsorship_configuration(
             max_sponsored_gas_fee_raw_amount_after: u256_to_wire(
                 max_sponsored_gas_fee_raw_amount_after,
             ),
+            max_sponsored_gas_fee_formatted_amount_before: None,
+            max_sponsored_gas_fee_formatted_amount_after: None,
         },
         service_core_space::SponsorshipConfiguration::StorageCollateral {
             sponsor_before,
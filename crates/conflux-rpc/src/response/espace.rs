@@ -1,9 +1,10 @@
+use alloy_primitives::Address;
 use cfx_rpc_eth_types::Bytes as RpcBytes;
 use cfx_types::{H256, U64, U256};
 use conflux_service::espace as service_espace;
 use serde::Serialize;
 
-use super::{b256_to_wire, change::Change, u256_to_wire};
+use super::{CFX_DECIMALS, b256_to_wire, change::Change, format_units, u256_to_wire};
 
 #[derive(Debug, Clone, Serialize, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
@@ -23,6 +24,10 @@ struct Execution {
     gas_charged: U256,
     fee: U256,
     burnt_fee: Option<U256>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    formatted_fee: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    formatted_burnt_fee: Option<String>,
     output: RpcBytes,
     failure: Option<ExecutionFailure>,
 }
@@ -32,6 +37,25 @@ struct Execution {
 struct SimulatedBlock {
     number: U64,
     hash: H256,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    overrides: Option<BlockOverrides>,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+struct BlockOverrides {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    number: Option<U64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    timestamp: Option<U64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    base_fee_per_gas: Option<U256>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    coinbase: Option<Address>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    gas_limit: Option<U64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    prev_randao: Option<H256>,
 }
 
 #[derive(Debug, Clone, Serialize, PartialEq, Eq)]
@@ -79,6 +103,22 @@ impl From<service_espace::SimulateEspaceTransactionOutput> for SimulateEspaceTra
     }
 }
 
+impl SimulateEspaceTransactionResponse {
+    /// Adds exact decimal strings next to fees and the raw amounts whose
+    /// decimals are known.
+    pub(crate) fn format_amounts(&mut self) {
+        self.execution.formatted_fee = Some(format_units(self.execution.fee, CFX_DECIMALS));
+        self.execution.formatted_burnt_fee = self
+            .execution
+            .burnt_fee
+            .map(|burnt_fee| format_units(burnt_fee, CFX_DECIMALS));
+
+        for change in &mut self.changes {
+            change.format_amounts();
+        }
+    }
+}
+
 impl From<service_espace::EspaceExecution> for Execution {
     fn from(execution: service_espace::EspaceExecution) -> Self {
         let service_espace::EspaceExecution {
@@ -126,6 +166,8 @@ impl From<service_espace::EspaceExecution> for Execution {
             gas_charged,
             fee,
             burnt_fee,
+            formatted_fee: None,
+            formatted_burnt_fee: None,
             output,
             failure,
         }
@@ -137,6 +179,20 @@ impl From<service_espace::SimulatedBlock> for SimulatedBlock {
         Self {
             number: block.number.into(),
             hash: b256_to_wire(block.hash),
+            overrides: (!block.overrides.is_empty()).then(|| block.overrides.into()),
+        }
+    }
+}
+
+impl From<service_espace::EspaceBlockOverrides> for BlockOverrides {
+    fn from(overrides: service_espace::EspaceBlockOverrides) -> Self {
+        Self {
+            number: overrides.number.map(Into::into),
+            timestamp: overrides.timestamp.map(Into::into),
+            base_fee_per_gas: overrides.base_fee_per_gas.map(u256_to_wire),
+            coinbase: overrides.coinbase,
+            gas_limit: overrides.gas_limit.map(Into::into),
+            prev_randao: overrides.prevrandao.map(b256_to_wire),
         }
     }
 }
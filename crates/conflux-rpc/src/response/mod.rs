@@ -5,10 +5,13 @@ mod espace;
 
 use alloy_primitives::{B256, U256 as AlloyU256};
 use cfx_types::{H256, U256};
+use simulation_changes::format_units;
 
-pub(crate) use core_space::SimulateCoreSpaceTransactionResponse;
+pub(crate) use core_space::{SimulateBundleResponse, SimulateCoreSpaceTransactionResponse};
 pub(crate) use espace::SimulateEspaceTransactionResponse;
 
+const CFX_DECIMALS: u8 = 18;
+
 fn u256_to_wire(value: AlloyU256) -> U256 {
     U256::from_big_endian(&value.to_be_bytes::<32>())
 }
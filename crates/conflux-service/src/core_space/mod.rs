@@ -1,11 +1,15 @@
 mod types;
 
 pub use types::{
-    Change, CoreAddress, CoreAddressNetwork, CoreSpaceAccessListItem, CoreSpaceChange,
-    CoreSpaceEpochRef, CoreSpaceExecutedDetails, CoreSpaceExecution, CoreSpaceExecutionFailure,
-    CoreSpaceExecutionFailureCode, CoreSpaceExecutionOutcome, CoreSpaceSimulation,
-    CoreSpaceStateAnchor, CoreSpaceTransactionInput, CoreSpaceTransactionRequest,
-    CoreSpaceTransactionVariantRequest, CrossSpaceAddress, Erc20Metadata, Erc721CollectionMetadata,
-    NativeMetadata, SimulateCoreSpaceTransactionInput, SimulateCoreSpaceTransactionOutput,
-    SponsoredResource, SponsorshipConfiguration, SponsorshipEligibilityTarget,
+    Change, CoreAddress, CoreAddressNetwork, CoreSpaceAccessListItem, CoreSpaceAccountOverride,
+    CoreSpaceBundleSimulation, CoreSpaceBundleStep, CoreSpaceBundleTransaction, CoreSpaceChange,
+    CoreSpaceDeposit, CoreSpaceEpochOverrides, CoreSpaceEpochRef, CoreSpaceExecutedDetails,
+    CoreSpaceExecution, CoreSpaceExecutionFailure, CoreSpaceExecutionFailureCode,
+    CoreSpaceExecutionOutcome, CoreSpaceSimulation, CoreSpaceSponsorOverride, CoreSpaceStateAnchor,
+    CoreSpaceStateOverrides, CoreSpaceStorageOverride, CoreSpaceTransactionInput,
+    CoreSpaceTransactionRequest, CoreSpaceTransactionVariantRequest, CoreSpaceVoteStake,
+    CrossSpaceAddress, Erc20Metadata, Erc721CollectionMetadata, NativeMetadata,
+    ReplayCoreSpaceTransactionInput, SimulateCoreSpaceBundleInput, SimulateCoreSpaceBundleOutput,
+    SimulateCoreSpaceTransactionInput, SimulateCoreSpaceTransactionOutput, SponsoredResource,
+    SponsorshipConfiguration, SponsorshipEligibilityTarget,
 };
@@ -1,15 +1,19 @@
+use alloy_primitives::B256;
 use conflux_simulation as simulation;
 
 pub use simulation::core_space::{
-    Change, CoreSpaceChange, CoreSpaceEpochRef, CoreSpaceExecutedDetails, CoreSpaceExecution,
+    Change, CoreSpaceBundleSimulation, CoreSpaceBundleStep, CoreSpaceBundleTransaction,
+    CoreSpaceChange, CoreSpaceEpochRef, CoreSpaceExecutedDetails, CoreSpaceExecution,
     CoreSpaceExecutionFailure, CoreSpaceExecutionFailureCode, CoreSpaceExecutionOutcome,
     CoreSpaceSimulation, CoreSpaceStateAnchor, CrossSpaceAddress, Erc20Metadata,
     Erc721CollectionMetadata, NativeMetadata, SponsoredResource, SponsorshipConfiguration,
     SponsorshipEligibilityTarget,
 };
 pub use simulation::core_space::{
-    CoreAddress, CoreAddressNetwork, CoreSpaceAccessListItem,
-    CoreSpaceTransactionRequest as CoreSpaceTransactionInput, CoreSpaceTransactionVariantRequest,
+    CoreAddress, CoreAddressNetwork, CoreSpaceAccessListItem, CoreSpaceAccountOverride,
+    CoreSpaceDeposit, CoreSpaceEpochOverrides, CoreSpaceSponsorOverride, CoreSpaceStateOverrides,
+    CoreSpaceStorageOverride, CoreSpaceTransactionRequest as CoreSpaceTransactionInput,
+    CoreSpaceTransactionVariantRequest, CoreSpaceVoteStake,
 };
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -23,6 +27,23 @@ pub struct CoreSpaceTransactionRequest {
 pub struct SimulateCoreSpaceTransactionInput {
     pub epoch: CoreSpaceEpochRef,
     pub transaction: CoreSpaceTransactionRequest,
+    pub state_overrides: CoreSpaceStateOverrides,
+    pub epoch_overrides: CoreSpaceEpochOverrides,
 }
 
 pub type SimulateCoreSpaceTransactionOutput = CoreSpaceSimulation;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SimulateCoreSpaceBundleInput {
+    pub epoch: CoreSpaceEpochRef,
+    pub transactions: Vec<CoreSpaceBundleTransaction>,
+    pub state_overrides: CoreSpaceStateOverrides,
+    pub epoch_overrides: CoreSpaceEpochOverrides,
+}
+
+pub type SimulateCoreSpaceBundleOutput = CoreSpaceBundleSimulation;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReplayCoreSpaceTransactionInput {
+    pub transaction_hash: B256,
+}
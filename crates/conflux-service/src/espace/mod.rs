@@ -1,8 +1,9 @@
 mod types;
 
 pub use types::{
-    Change, ConfluxTransactionRequest, Erc20Metadata, Erc721CollectionMetadata, EspaceBlockRef,
-    EspaceExecutedDetails, EspaceExecution, EspaceExecutionFailure, EspaceExecutionFailureCode,
-    EspaceExecutionOutcome, EspaceSimulation, NativeMetadata, SimulateEspaceTransactionInput,
-    SimulateEspaceTransactionOutput, SimulatedBlock,
+    Change, ConfluxTransactionRequest, Erc20Metadata, Erc721CollectionMetadata,
+    EspaceBlockOverrides, EspaceBlockRef, EspaceExecutedDetails, EspaceExecution,
+    EspaceExecutionFailure, EspaceExecutionFailureCode, EspaceExecutionOutcome, EspaceSimulation,
+    NativeMetadata, ProxySlot, SimulateEspaceTransactionInput, SimulateEspaceTransactionOutput,
+    SimulatedBlock,
 };
@@ -1,15 +1,16 @@
 pub use crate::ConfluxTransactionRequest;
 use conflux_simulation as simulation;
 pub use simulation::espace::{
-    Change, Erc20Metadata, Erc721CollectionMetadata, EspaceBlockRef, EspaceExecutedDetails,
-    EspaceExecution, EspaceExecutionFailure, EspaceExecutionFailureCode, EspaceExecutionOutcome,
-    EspaceSimulation, NativeMetadata, SimulatedBlock,
+    Change, Erc20Metadata, Erc721CollectionMetadata, EspaceBlockOverrides, EspaceBlockRef,
+    EspaceExecutedDetails, EspaceExecution, EspaceExecutionFailure, EspaceExecutionFailureCode,
+    EspaceExecutionOutcome, EspaceSimulation, NativeMetadata, ProxySlot, SimulatedBlock,
 };
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct SimulateEspaceTransactionInput {
     pub block: EspaceBlockRef,
     pub transaction: ConfluxTransactionRequest,
+    pub block_overrides: EspaceBlockOverrides,
 }
 
 pub type SimulateEspaceTransactionOutput = EspaceSimulation;
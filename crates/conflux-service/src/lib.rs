@@ -43,13 +43,19 @@ impl ConfluxService {
         &self,
         input: espace::SimulateEspaceTransactionInput,
     ) -> Result<espace::SimulateEspaceTransactionOutput, ConfluxServiceError> {
-        let espace::SimulateEspaceTransactionInput { block, transaction } = input;
+        let espace::SimulateEspaceTransactionInput {
+            block,
+            transaction,
+            block_overrides,
+        } = input;
         let preparer = Arc::clone(&self.espace_preparer);
         let simulator = Arc::clone(&self.espace_simulator);
         let simulation = self
             .simulation_tasks
             .run(move || async move {
-                let prepared = preparer.prepare_transaction(block, transaction).await?;
+                let prepared = preparer
+                    .prepare_transaction(block, transaction, block_overrides)
+                    .await?;
 
                 let simulation = tokio::task::spawn_blocking(move || simulator.simulate(prepared))
                     .await
@@ -69,7 +75,12 @@ impl ConfluxService {
         &self,
         input: core_space::SimulateCoreSpaceTransactionInput,
     ) -> Result<core_space::SimulateCoreSpaceTransactionOutput, ConfluxServiceError> {
-        let core_space::SimulateCoreSpaceTransactionInput { epoch, transaction } = input;
+        let core_space::SimulateCoreSpaceTransactionInput {
+            epoch,
+            transaction,
+            state_overrides,
+            epoch_overrides,
+        } = input;
         let preparer = Arc::clone(&self.core_space_preparer);
         let simulator = Arc::clone(&self.core_space_simulator);
         let simulation = self
@@ -81,6 +92,8 @@ impl ConfluxService {
                         transaction.transaction,
                         transaction.storage_limit,
                         transaction.epoch_height,
+                        state_overrides,
+                        epoch_overrides,
                     )
                     .await?;
 
@@ -97,6 +110,67 @@ impl ConfluxService {
 
         Ok(simulation)
     }
+
+    pub async fn simulate_core_space_bundle(
+        &self,
+        input: core_space::SimulateCoreSpaceBundleInput,
+    ) -> Result<core_space::SimulateCoreSpaceBundleOutput, ConfluxServiceError> {
+        let core_space::SimulateCoreSpaceBundleInput {
+            epoch,
+            transactions,
+            state_overrides,
+            epoch_overrides,
+        } = input;
+        let preparer = Arc::clone(&self.core_space_preparer);
+        let simulator = Arc::clone(&self.core_space_simulator);
+        let simulation = self
+            .simulation_tasks
+            .run(move || async move {
+                let prepared = preparer
+                    .prepare_bundle(epoch, transactions, state_overrides, epoch_overrides)
+                    .await?;
+
+                let simulation =
+                    tokio::task::spawn_blocking(move || simulator.simulate_bundle(prepared))
+                        .await
+                        .map_err(|source| ConfluxServiceError::ExecutionTask {
+                            space: "Core Space bundle",
+                            source,
+                        })??;
+
+                Ok::<_, ConfluxServiceError>(simulation)
+            })
+            .await??;
+
+        Ok(simulation)
+    }
+
+    pub async fn replay_core_space_transaction(
+        &self,
+        input: core_space::ReplayCoreSpaceTransactionInput,
+    ) -> Result<core_space::SimulateCoreSpaceTransactionOutput, ConfluxServiceError> {
+        let core_space::ReplayCoreSpaceTransactionInput { transaction_hash } = input;
+        let preparer = Arc::clone(&self.core_space_preparer);
+        let simulator = Arc::clone(&self.core_space_simulator);
+        let simulation = self
+            .simulation_tasks
+            .run(move || async move {
+                let prepared = preparer.prepare_replay(transaction_hash).await?;
+
+                let simulation =
+                    tokio::task::spawn_blocking(move || simulator.simulate_replay(prepared))
+                        .await
+                        .map_err(|source| ConfluxServiceError::ExecutionTask {
+                            space: "Core Space replay",
+                            source,
+                        })??;
+
+                Ok::<_, ConfluxServiceError>(simulation)
+            })
+            .await??;
+
+        Ok(simulation)
+    }
 }
 
 #[derive(Debug, Error)]
@@ -157,7 +231,8 @@ fn simulation_error_code(error: &conflux_simulation::ConfluxSimulationError) ->
         ConfluxSimulationError::BlockContext(_)
         | ConfluxSimulationError::InvalidBlockContext { .. }
         | ConfluxSimulationError::StateAnchorInconsistent => "block_context_error",
-        ConfluxSimulationError::TransactionCompletion { .. } => "transaction_resolution_error",
+        ConfluxSimulationError::TransactionCompletion { .. }
+        | ConfluxSimulationError::TransactionResolution { .. } => "transaction_resolution_error",
         ConfluxSimulationError::Provider(_) => "rpc_error",
         ConfluxSimulationError::StateAccess { .. } => "state_access_error",
         ConfluxSimulationError::Analysis { .. } => "analysis_failed",
@@ -1,10 +1,13 @@
 use cfx_addr::Network;
+use cfx_parameters::consensus::{BN128_ENABLE_NUMBER, TANZANITE_HEIGHT};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ConfluxChainConfig {
     pub core_space_chain_id: u32,
     pub evm_chain_id: u32,
+    pub network_id: u64,
     pub core_space_address_network: Network,
+    pub transitions: ConfluxTransitions,
 }
 
 impl ConfluxChainConfig {
@@ -12,7 +15,107 @@ impl ConfluxChainConfig {
         Self {
             core_space_chain_id: 1029,
             evm_chain_id: 1030,
+            network_id: 1029,
             core_space_address_network: Network::Main,
+            transitions: ConfluxTransitions::mainnet(),
+        }
+    }
+
+    pub fn testnet() -> Self {
+        Self {
+            core_space_chain_id: 1,
+            evm_chain_id: 71,
+            network_id: 1,
+            core_space_address_network: Network::Test,
+            transitions: ConfluxTransitions::testnet(),
+        }
+    }
+
+    /// A private network. Core Space addresses carry the chain id as their
+    /// network prefix.
+    pub fn custom(
+        core_space_chain_id: u32,
+        evm_chain_id: u32,
+        network_id: u64,
+        transitions: ConfluxTransitions,
+    ) -> Self {
+        let core_space_address_network = match core_space_chain_id {
+            1029 => Network::Main,
+            1 => Network::Test,
+            chain_id => Network::Id(chain_id.into()),
+        };
+
+        Self {
+            core_space_chain_id,
+            evm_chain_id,
+            network_id,
+            core_space_address_network,
+            transitions,
+        }
+    }
+}
+
+/// Hardfork activation points, named after the upstream node configuration
+/// keys. `_number` fields are block numbers and `_height` fields are epoch
+/// heights.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ConfluxTransitions {
+    pub tanzanite_transition_height: u64,
+    /// CIP-62, the BN128 precompiles.
+    pub bn128_enable_number: u64,
+    pub hydra_transition_number: u64,
+    pub hydra_transition_height: u64,
+    pub cip43_init_end_number: u64,
+    pub dao_vote_transition_number: u64,
+    pub dao_vote_transition_height: u64,
+    pub sigma_fix_transition_number: u64,
+    /// CIP-107, CIP-118 and CIP-119.
+    pub burn_collateral_transition_number: u64,
+    pub cip112_transition_height: u64,
+    pub base_fee_burn_transition_number: u64,
+    pub base_fee_burn_transition_height: u64,
+    pub c2_fix_transition_height: u64,
+    pub eoa_code_transition_height: u64,
+}
+
+impl ConfluxTransitions {
+    /// Mainnet heights mirrored from the current upstream commit.
+    pub fn mainnet() -> Self {
+        Self {
+            tanzanite_transition_height: TANZANITE_HEIGHT,
+            bn128_enable_number: BN128_ENABLE_NUMBER,
+            hydra_transition_number: 92_060_600,
+            hydra_transition_height: 36_935_000,
+            cip43_init_end_number: 92_751_800,
+            dao_vote_transition_number: 133_800_000,
+            dao_vote_transition_height: 56_800_000,
+            sigma_fix_transition_number: 137_740_000,
+            burn_collateral_transition_number: 188_900_000,
+            cip112_transition_height: 79_050_000,
+            base_fee_burn_transition_number: 247_480_000,
+            base_fee_burn_transition_height: 101_900_000,
+            c2_fix_transition_height: 118_580_000,
+            eoa_code_transition_height: 129_680_000,
+        }
+    }
+
+    /// Testnet heights mirrored from the current upstream commit.
+    pub fn testnet() -> Self {
+        Self {
+            tanzanite_transition_height: TANZANITE_HEIGHT,
+            bn128_enable_number: BN128_ENABLE_NUMBER,
+            hydra_transition_number: 61_465_000,
+            hydra_transition_height: 40_280_000,
+            cip43_init_end_number: 61_800_000,
+            dao_vote_transition_number: 112_400_000,
+            dao_vote_transition_height: 77_340_000,
+            sigma_fix_transition_number: 114_200_000,
+            burn_collateral_transition_number: 148_600_000,
+            cip112_transition_height: 85_000_000,
+            base_fee_burn_transition_number: 188_900_000,
+            base_fee_burn_transition_height: 124_400_000,
+            c2_fix_transition_height: 163_000_000,
+            eoa_code_transition_height: 188_400_000,
         }
     }
 }
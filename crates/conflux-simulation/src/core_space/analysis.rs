@@ -18,7 +18,7 @@ use super::changes::{
     CfxAnalysisInput, CfxStateValues, CommittedStakingCalls, CoreSpaceChange, PoSAnalysisInput,
     PoSStateReader, PoSStateValues, PositionedCoreSpaceChange, StakingContractActivation,
     collect_committed_staking_calls, order_and_enrich_core_space_changes,
-    verify_pos_staking_changes, verify_vote_lock_changes,
+    verify_pos_staking_changes, verify_vote_lists_before_execution, verify_vote_lock_changes,
 };
 
 struct CoreSpaceAnalysisInput {
@@ -114,6 +114,7 @@ impl CoreSpaceStateReader {
 pub(super) struct CoreSpaceChangeAnalysis {
     input: CoreSpaceAnalysisInput,
     state_reader: CoreSpaceStateReader,
+    masked_sponsor_whitelist_entries: MaskedSponsorWhitelistEntries,
     anchored_vote_lists: AnchoredVoteLists,
 }
 
@@ -131,6 +132,7 @@ impl CoreSpaceChangeAnalysis {
                 masked_sponsor_whitelist_entries,
             )?,
             state_reader: CoreSpaceStateReader::default(),
+            masked_sponsor_whitelist_entries: masked_sponsor_whitelist_entries.clone(),
             anchored_vote_lists: anchored_vote_lists.clone(),
         })
     }
@@ -142,6 +144,14 @@ impl CoreSpaceChangeAnalysis {
         prepared_execution: &PreparedTransactionExecution,
         phase: StatePhase,
     ) -> Result<CoreSpaceStateValues, ConfluxSimulationError> {
+        if phase == StatePhase::Before {
+            verify_vote_lists_before_execution(
+                state,
+                self.input.committed_staking_calls.vote_lock_calls(),
+                &self.anchored_vote_lists,
+                prepared_execution.env.number,
+            )?;
+        }
         self.state_reader
             .read(state, machine, prepared_execution, &self.input, phase)
     }
@@ -155,6 +165,7 @@ impl CoreSpaceChangeAnalysis {
     ) -> Result<Vec<CoreSpaceChange>, ConfluxSimulationError> {
         let Self {
             input: analysis_input,
+            masked_sponsor_whitelist_entries,
             anchored_vote_lists,
             ..
         } = self;
@@ -185,6 +196,9 @@ impl CoreSpaceChangeAnalysis {
             &anchored_vote_lists,
             prepared_execution.env.number,
         )?);
+        analysis_input
+            .cfx
+            .unmask_written_sponsorship_access_rules(&masked_sponsor_whitelist_entries)?;
 
         match (before_pos_state, after_pos_state) {
             (Some(before), Some(after)) => {
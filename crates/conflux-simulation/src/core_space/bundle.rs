@@ -0,0 +1,87 @@
+use simulation_changes::Change;
+use simulation_execution::ExecutionOutcome;
+use simulation_transaction::TransactionRequest;
+
+use crate::espace::EspaceSimulation;
+
+use super::{CoreSpaceChange, CoreSpaceSimulation, CoreSpaceTransactionRequest};
+
+/// A bundle transaction in either space. eSpace transactions execute in the
+/// eSpace block of the anchored epoch.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CoreSpaceBundleTransaction {
+    CoreSpace {
+        transaction: CoreSpaceTransactionRequest,
+        storage_limit: Option<u64>,
+        epoch_height: Option<u64>,
+    },
+    Espace(TransactionRequest),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CoreSpaceBundleStep {
+    CoreSpace(CoreSpaceSimulation),
+    Espace(EspaceSimulation),
+}
+
+impl CoreSpaceBundleStep {
+    pub(crate) fn succeeded(&self) -> bool {
+        match self {
+            Self::CoreSpace(simulation) => {
+                matches!(simulation.execution().outcome, ExecutionOutcome::Success(_))
+            }
+            Self::Espace(simulation) => {
+                matches!(simulation.execution().outcome, ExecutionOutcome::Success(_))
+            }
+        }
+    }
+}
+
+/// Results of an ordered bundle. Execution stops after the first transaction
+/// that does not succeed, so `steps` may be shorter than the bundle. The
+/// combined changes list the changes of every step in order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CoreSpaceBundleSimulation {
+    steps: Vec<CoreSpaceBundleStep>,
+    core_space_changes: Vec<CoreSpaceChange>,
+    espace_changes: Vec<Change>,
+}
+
+impl CoreSpaceBundleSimulation {
+    pub(crate) fn new(steps: Vec<CoreSpaceBundleStep>) -> Self {
+        let mut core_space_changes = Vec::new();
+        let mut espace_changes = Vec::new();
+        for step in &steps {
+            match step {
+                CoreSpaceBundleStep::CoreSpace(simulation) => {
+                    core_space_changes.extend_from_slice(simulation.changes());
+                }
+                CoreSpaceBundleStep::Espace(simulation) => {
+                    espace_changes.extend_from_slice(simulation.changes());
+                }
+            }
+        }
+
+        Self {
+            steps,
+            core_space_changes,
+            espace_changes,
+        }
+    }
+
+    pub fn steps(&self) -> &[CoreSpaceBundleStep] {
+        &self.steps
+    }
+
+    pub fn core_space_changes(&self) -> &[CoreSpaceChange] {
+        &self.core_space_changes
+    }
+
+    pub fn espace_changes(&self) -> &[Change] {
+        &self.espace_changes
+    }
+
+    pub fn into_parts(self) -> (Vec<CoreSpaceBundleStep>, Vec<CoreSpaceChange>, Vec<Change>) {
+        (self.steps, self.core_space_changes, self.espace_changes)
+    }
+}
@@ -82,4 +82,12 @@ impl CfxAnalysisInput {
     pub(crate) fn staking_balance_effects(&self) -> &StakingBalanceEffects {
         &self.staking_balance_effects
     }
+
+    pub(crate) fn unmask_written_sponsorship_access_rules(
+        &self,
+        masked_sponsor_whitelist_entries: &MaskedSponsorWhitelistEntries,
+    ) -> Result<(), ConfluxSimulationError> {
+        self.operations
+            .unmask_written_sponsorship_access_rules(masked_sponsor_whitelist_entries)
+    }
 }
@@ -243,6 +243,28 @@ impl CfxOperations {
         }
         Ok(())
     }
+
+    /// The committed access rules wrote their raw whitelist entries, so later
+    /// transactions on the same state no longer read masked values.
+    pub(crate) fn unmask_written_sponsorship_access_rules(
+        &self,
+        masked_entries: &MaskedSponsorWhitelistEntries,
+    ) -> Result<(), ConfluxSimulationError> {
+        for key in &self.sponsorship_access_rule_keys {
+            let SponsorshipEligibilityTarget::Account(account_address) = key.account_scope else {
+                continue;
+            };
+            masked_entries
+                .unmask(&SponsorWhitelistStorageKey {
+                    contract_address: address_to_cfx(key.contract_address),
+                    account_address: address_to_cfx(account_address),
+                })
+                .map_err(|error| ConfluxSimulationError::StateAccess {
+                    message: error.to_string(),
+                })?;
+        }
+        Ok(())
+    }
 }
 
 #[derive(Debug, Clone)]
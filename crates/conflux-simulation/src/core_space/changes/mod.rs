@@ -9,7 +9,7 @@ pub(crate) use cfx::{CfxAnalysisInput, CfxStateValues};
 pub(crate) use staking::{
     CommittedStakingCalls, PoSAnalysisInput, PoSStateReader, PoSStateValues,
     StakingContractActivation, collect_committed_staking_calls, verify_pos_staking_changes,
-    verify_vote_lock_changes,
+    verify_vote_lists_before_execution, verify_vote_lock_changes,
 };
 
 #[derive(Debug, Clone, PartialEq, Eq)]
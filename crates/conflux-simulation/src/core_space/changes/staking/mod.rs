@@ -9,7 +9,7 @@ pub(crate) use collection::{
 };
 pub(crate) use pos::{PoSAnalysisInput, verify_pos_staking_changes};
 pub(crate) use pos_state::{PoSStateReader, PoSStateValues};
-pub(crate) use vote_lock::verify_vote_lock_changes;
+pub(crate) use vote_lock::{verify_vote_lists_before_execution, verify_vote_lock_changes};
 
 use alloy_primitives::{Address, B256, U256};
 use contract_standards::Position;
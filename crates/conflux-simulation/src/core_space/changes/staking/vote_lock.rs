@@ -1,7 +1,8 @@
-use std::collections::{BTreeMap, btree_map::Entry};
+use std::collections::{BTreeMap, BTreeSet, btree_map::Entry};
 
 use alloy_primitives::{Address, U256};
 use cfx_executor::state::State;
+use contract_standards::StatePhase;
 use primitives::VoteStakeList;
 
 use super::CommittedVoteLockCall;
@@ -66,11 +67,53 @@ pub(crate) fn verify_vote_lock_changes(
     }
 
     for (account, vote_list) in &vote_lists_by_account {
-        verify_vote_list_after_execution(state, vote_list, *account)?;
+        verify_vote_list_in_state(state, vote_list, *account, StatePhase::After)?;
+    }
+    // Later transactions on the same state start from the committed lists.
+    for (account, vote_list) in vote_lists_by_account {
+        anchored_vote_lists
+            .commit(address_to_cfx(account), vote_list.0)
+            .map_err(|error| ConfluxSimulationError::StateAccess {
+                message: format!("failed to record committed vote list for {account}: {error}"),
+            })?;
     }
     Ok(positioned_changes)
 }
 
+/// Checks that the vote lists the analysis starts from are those of the
+/// state the transaction executed on. Earlier transactions the analysis did
+/// not follow may have changed them.
+pub(crate) fn verify_vote_lists_before_execution(
+    state: &State,
+    committed_vote_lock_calls: &[CommittedVoteLockCall],
+    anchored_vote_lists: &AnchoredVoteLists,
+    current_block_number: u64,
+) -> Result<(), ConfluxSimulationError> {
+    let accounts = committed_vote_lock_calls
+        .iter()
+        .map(|committed_call| committed_call.account)
+        .collect::<BTreeSet<_>>();
+    for account in accounts {
+        let mut vote_list = anchored_vote_lists
+            .for_account(address_to_cfx(account))
+            .map(VoteStakeList)
+            .map_err(|error| ConfluxSimulationError::StateAccess {
+                message: format!(
+                    "failed to obtain execution-read anchored vote list for {account}: {error}"
+                ),
+            })?;
+        // Withdrawals drop expired entries without a vote lock.
+        let length = state
+            .vote_stake_list_length(&address_to_cfx(account))
+            .map_err(|error| state_access(account, StatePhase::Before, error))?;
+        if length != vote_list.len() {
+            vote_list.remove_expired_vote_stake_info(current_block_number);
+        }
+        verify_vote_list_in_state(state, &vote_list, account, StatePhase::Before)?;
+    }
+    Ok(())
+}
+
 fn required_locked_amount_before_call(vote_list: &VoteStakeList, unlock_block_number: u64) -> U256 {
     let unlock_block_number = cfx_types::U256::from(unlock_block_number);
     let index = match vote_list
@@ -83,19 +126,20 @@ fn required_locked_amount_before_call(vote_list: &VoteStakeList, unlock_block_nu
         .map_or(U256::ZERO, |vote_info| u256_from_cfx(vote_info.amount))
 }
 
-fn verify_vote_list_after_execution(
+fn verify_vote_list_in_state(
     state: &State,
     vote_list: &VoteStakeList,
     account: Address,
+    phase: StatePhase,
 ) -> Result<(), ConfluxSimulationError> {
     verify_canonical_vote_list(vote_list, account)?;
     let cfx_account = address_to_cfx(account);
     let actual_length = state
         .vote_stake_list_length(&cfx_account)
-        .map_err(|error| after_state_access(account, error))?;
+        .map_err(|error| state_access(account, phase, error))?;
     if actual_length != vote_list.len() {
         return Err(ConfluxSimulationError::analysis_failed(format!(
-            "Core Space vote-list length mismatch for {account}: expected {}, got {actual_length}",
+            "Core Space {phase} vote-list length mismatch for {account}: expected {}, got {actual_length}",
             vote_list.len()
         )));
     }
@@ -108,21 +152,21 @@ fn verify_vote_list_after_execution(
         let previous_block = unlock_block_number - 1;
         let before_unlock = state
             .locked_staking_balance_at_block_number(&cfx_account, previous_block)
-            .map_err(|error| after_state_access(account, error))?;
+            .map_err(|error| state_access(account, phase, error))?;
         if u256_from_cfx(before_unlock) != u256_from_cfx(vote_info.amount) {
             return Err(ConfluxSimulationError::analysis_failed(format!(
-                "Core Space vote-list locked balance before boundary mismatched for {account}"
+                "Core Space {phase} vote-list locked balance before boundary mismatched for {account}"
             )));
         }
         let locked_at_unlock = state
             .locked_staking_balance_at_block_number(&cfx_account, unlock_block_number)
-            .map_err(|error| after_state_access(account, error))?;
+            .map_err(|error| state_access(account, phase, error))?;
         let required_at_unlock = vote_list
             .get(index + 1)
             .map_or(U256::ZERO, |next| u256_from_cfx(next.amount));
         if u256_from_cfx(locked_at_unlock) != required_at_unlock {
             return Err(ConfluxSimulationError::analysis_failed(format!(
-                "Core Space vote-list locked balance at boundary mismatched for {account}"
+                "Core Space {phase} vote-list locked balance at boundary mismatched for {account}"
             )));
         }
     }
@@ -150,8 +194,14 @@ fn verify_canonical_vote_list(
     Ok(())
 }
 
-fn after_state_access(account: Address, error: cfx_statedb::Error) -> ConfluxSimulationError {
+fn state_access(
+    account: Address,
+    phase: StatePhase,
+    error: cfx_statedb::Error,
+) -> ConfluxSimulationError {
     ConfluxSimulationError::StateAccess {
-        message: format!("failed to read after Core Space vote-list state for {account}: {error}"),
+        message: format!(
+            "failed to read {phase} Core Space vote-list state for {account}: {error}"
+        ),
     }
 }
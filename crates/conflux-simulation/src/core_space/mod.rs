@@ -1,19 +1,29 @@
 mod analysis;
+mod bundle;
 mod changes;
 mod execution;
 mod outcome;
+mod overrides;
 mod preparer;
+mod replay;
 mod result;
 pub(crate) mod simulation;
 mod simulator;
 mod transaction;
 
 pub(crate) use outcome::{build_core_space_execution, build_core_space_not_executed};
+pub(crate) use overrides::{
+    apply_core_space_epoch_overrides, build_state_overrides, validate_core_space_state_overrides,
+};
+pub(crate) use replay::{
+    is_espace_transaction, mined_core_space_transaction, mined_espace_transaction,
+};
 pub(crate) use transaction::{
     PreparedStoragePayer, build_core_space_transaction_input, prepare_storage_payer,
     validate_core_space_transaction_network,
 };
 
+pub use bundle::{CoreSpaceBundleSimulation, CoreSpaceBundleStep, CoreSpaceBundleTransaction};
 pub use changes::{
     CoreSpaceChange, CrossSpaceAddress, SponsoredResource, SponsorshipConfiguration,
     SponsorshipEligibilityTarget,
@@ -23,6 +33,10 @@ pub use execution::{
     CoreSpaceExecutedDetails, CoreSpaceExecution, CoreSpaceExecutionFailure,
     CoreSpaceExecutionFailureCode, CoreSpaceExecutionOutcome, CoreSpaceStateAnchor,
 };
+pub use overrides::{
+    CoreSpaceAccountOverride, CoreSpaceDeposit, CoreSpaceEpochOverrides, CoreSpaceSponsorOverride,
+    CoreSpaceStateOverrides, CoreSpaceStorageOverride, CoreSpaceVoteStake,
+};
 pub use preparer::CoreSpaceSimulationPreparer;
 pub use result::CoreSpaceSimulation;
 pub use simulation_changes::{Change, Erc20Metadata, Erc721CollectionMetadata, NativeMetadata};
@@ -0,0 +1,222 @@
+use std::collections::{BTreeMap, HashMap};
+
+use alloy_primitives::{B256, Bytes, U256};
+use cfx_types::address_util::AddressUtil;
+use conflux_provider::{CoreAddress, Network};
+use primitives::{DepositInfo, VoteStakeInfo};
+
+use crate::{
+    ConfluxSimulationError,
+    execution::ExecutionBlockContext,
+    primitive::{b256_to_cfx, u256_to_cfx},
+    state::{
+        ConfluxStateOverrides, CoreSpaceAccountStateOverride, SponsorStateOverride,
+        StorageStateOverride,
+    },
+};
+
+pub type CoreSpaceStateOverrides = HashMap<CoreAddress, CoreSpaceAccountOverride>;
+
+/// Replaces parts of a Core Space account as read at the anchored epoch.
+/// Global totals such as the total staking are not adjusted.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CoreSpaceAccountOverride {
+    pub balance: Option<U256>,
+    pub nonce: Option<u64>,
+    pub code: Option<Bytes>,
+    pub storage: Option<CoreSpaceStorageOverride>,
+    pub staking_balance: Option<U256>,
+    /// Only contract addresses carry sponsor info.
+    pub sponsor: Option<CoreSpaceSponsorOverride>,
+    pub deposit_list: Option<Vec<CoreSpaceDeposit>>,
+    pub vote_list: Option<Vec<CoreSpaceVoteStake>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CoreSpaceStorageOverride {
+    /// Replaces the whole account storage; unlisted slots read as zero.
+    Replace(BTreeMap<B256, B256>),
+    /// Patches the listed slots on top of the anchored storage.
+    Patch(BTreeMap<B256, B256>),
+}
+
+/// Unset fields keep the anchored sponsor info.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CoreSpaceSponsorOverride {
+    pub sponsor_for_gas: Option<CoreAddress>,
+    pub sponsor_for_collateral: Option<CoreAddress>,
+    pub sponsor_gas_bound: Option<U256>,
+    pub sponsor_balance_for_gas: Option<U256>,
+    pub sponsor_balance_for_collateral: Option<U256>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CoreSpaceDeposit {
+    pub amount: U256,
+    /// Block number of the deposit.
+    pub deposit_time: u64,
+    pub accumulated_interest_rate: U256,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CoreSpaceVoteStake {
+    pub amount: U256,
+    pub unlock_block_number: u64,
+}
+
+// Block-number fork rules and vote unlocks follow an overridden epoch height,
+// as the execution block number moves with it.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CoreSpaceEpochOverrides {
+    pub epoch_height: Option<u64>,
+    pub timestamp: Option<u64>,
+}
+
+pub(crate) fn apply_core_space_epoch_overrides(
+    context: &mut ExecutionBlockContext,
+    overrides: &CoreSpaceEpochOverrides,
+) {
+    if let Some(epoch_height) = overrides.epoch_height {
+        context.execution_epoch_height = Some(epoch_height);
+    }
+
+    if let Some(timestamp) = overrides.timestamp {
+        context.timestamp = timestamp;
+    }
+}
+
+pub(crate) fn validate_core_space_state_overrides(
+    overrides: &CoreSpaceStateOverrides,
+    expected_network: Network,
+) -> Result<(), ConfluxSimulationError> {
+    for (address, account_override) in overrides {
+        let field = format!("options.stateOverrides.{}", address.to_cip37());
+        validate_override_address_network(address, expected_network, &field)?;
+
+        let Some(sponsor) = &account_override.sponsor else {
+            continue;
+        };
+        if !cfx_address(address).is_contract_address() {
+            return Err(ConfluxSimulationError::transaction_completion_failed(
+                format!("`{field}.sponsorInfo` is only supported for contract addresses"),
+            ));
+        }
+        for (sponsor_address, name) in [
+            (&sponsor.sponsor_for_gas, "sponsorForGas"),
+            (&sponsor.sponsor_for_collateral, "sponsorForCollateral"),
+        ] {
+            if let Some(sponsor_address) = sponsor_address {
+                validate_override_address_network(
+                    sponsor_address,
+                    expected_network,
+                    &format!("{field}.sponsorInfo.{name}"),
+                )?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn validate_override_address_network(
+    address: &CoreAddress,
+    expected_network: Network,
+    field: &str,
+) -> Result<(), ConfluxSimulationError> {
+    if address.network() != expected_network {
+        return Err(ConfluxSimulationError::transaction_completion_failed(
+            format!(
+                "`{field}` uses address network {}, expected {}",
+                address.network(),
+                expected_network
+            ),
+        ));
+    }
+
+    Ok(())
+}
+
+pub(crate) fn build_state_overrides(overrides: CoreSpaceStateOverrides) -> ConfluxStateOverrides {
+    let mut state_overrides = ConfluxStateOverrides::default();
+    for (address, account_override) in overrides {
+        let CoreSpaceAccountOverride {
+            balance,
+            nonce,
+            code,
+            storage,
+            staking_balance,
+            sponsor,
+            deposit_list,
+            vote_list,
+        } = account_override;
+
+        state_overrides.insert_core_space_account(
+            cfx_address(&address),
+            CoreSpaceAccountStateOverride {
+                balance: balance.map(u256_to_cfx),
+                nonce: nonce.map(Into::into),
+                code: code.map(|code| code.to_vec().into()),
+                storage: storage.map(|storage| match storage {
+                    CoreSpaceStorageOverride::Replace(slots) => {
+                        StorageStateOverride::Replace(storage_slots(slots))
+                    }
+                    CoreSpaceStorageOverride::Patch(slots) => {
+                        StorageStateOverride::Patch(storage_slots(slots))
+                    }
+                }),
+                staking_balance: staking_balance.map(u256_to_cfx),
+                sponsor: sponsor.map(|sponsor| SponsorStateOverride {
+                    sponsor_for_gas: sponsor.sponsor_for_gas.as_ref().map(cfx_address),
+                    sponsor_for_collateral: sponsor
+                        .sponsor_for_collateral
+                        .as_ref()
+                        .map(cfx_address),
+                    sponsor_gas_bound: sponsor.sponsor_gas_bound.map(u256_to_cfx),
+                    sponsor_balance_for_gas: sponsor.sponsor_balance_for_gas.map(u256_to_cfx),
+                    sponsor_balance_for_collateral: sponsor
+                        .sponsor_balance_for_collateral
+                        .map(u256_to_cfx),
+                }),
+                deposit_list: deposit_list.map(|deposits| {
+                    deposits
+                        .into_iter()
+                        .map(|deposit| DepositInfo {
+                            amount: u256_to_cfx(deposit.amount),
+                            deposit_time: deposit.deposit_time.into(),
+                            accumulated_interest_rate: u256_to_cfx(
+                                deposit.accumulated_interest_rate,
+                            ),
+                        })
+                        .collect()
+                }),
+                vote_list: vote_list.map(|votes| {
+                    votes
+                        .into_iter()
+                        .map(|vote| VoteStakeInfo {
+                            amount: u256_to_cfx(vote.amount),
+                            unlock_block_number: vote.unlock_block_number.into(),
+                        })
+                        .collect()
+                }),
+            },
+        );
+    }
+
+    state_overrides
+}
+
+fn storage_slots(slots: BTreeMap<B256, B256>) -> HashMap<cfx_types::H256, cfx_types::U256> {
+    slots
+        .into_iter()
+        .map(|(slot, value)| {
+            (
+                b256_to_cfx(slot),
+                cfx_types::U256::from_big_endian(value.as_slice()),
+            )
+        })
+        .collect()
+}
+
+fn cfx_address(address: &CoreAddress) -> cfx_types::Address {
+    cfx_types::Address::from_slice(&address.bytes())
+}
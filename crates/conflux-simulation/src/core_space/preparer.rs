@@ -1,42 +1,88 @@
-use std::sync::Arc;
+use std::{collections::HashMap, sync::Arc};
+
+use alloy_primitives::{Address, B256};
+use conflux_provider::CoreRpcTransaction;
 
 use crate::{
-    ConfluxSimulationError, ConfluxSimulationProvider, PreparedCoreSpaceSimulation,
+    ConfluxSimulationError, ConfluxSimulationProvider, ConfluxSnapshotStore, ConfluxStateCache,
+    PreparedCoreSpaceBundle, PreparedCoreSpaceReplay, PreparedCoreSpaceSimulation,
     config::ConfluxChainConfig,
-    execution::{DryRunTransactionInput, TransactionExecutionInput},
+    espace::{EspaceBlockOverrides, build_espace_transaction_input, prepare_espace_transaction},
+    execution::{DryRunTransactionInput, ExecutionBlockContext, TransactionExecutionInput},
     preparation::{
-        CoreSpaceSimulationContext, PreparedCoreSpaceSimulationState, ReadyCoreSpaceSimulation,
-        complete_core_space_transaction, load_core_space_context, prepare_state_source,
+        CoreSpaceSimulationContext, PreparedBundleTransaction, PreparedCoreSpaceSimulationState,
+        PreparedCoreSpaceTransaction, ReadyCoreSpaceSimulation, ReadyCoreSpaceTransaction,
+        ReplayedBlock, ReplayedExecution, ReplayedTransaction, complete_core_space_transaction,
+        complete_espace_transaction, load_core_space_context, load_core_space_replay_context,
+        prepare_state_source,
     },
+    primitive::b256_to_cfx,
 };
 
 use super::{
-    CoreSpaceEpochRef, CoreSpaceExecutionFailure, CoreSpaceExecutionFailureCode,
-    CoreSpaceStateAnchor, CoreSpaceTransaction, CoreSpaceTransactionRequest,
-    CoreSpaceTransactionVariant, build_core_space_not_executed, build_core_space_transaction_input,
-    prepare_storage_payer, validate_core_space_transaction_network,
+    CoreSpaceBundleTransaction, CoreSpaceEpochOverrides, CoreSpaceEpochRef,
+    CoreSpaceExecutionFailure, CoreSpaceExecutionFailureCode, CoreSpaceStateAnchor,
+    CoreSpaceStateOverrides, CoreSpaceTransaction, CoreSpaceTransactionRequest,
+    CoreSpaceTransactionVariant, apply_core_space_epoch_overrides, build_core_space_not_executed,
+    build_core_space_transaction_input, build_state_overrides, is_espace_transaction,
+    mined_core_space_transaction, mined_espace_transaction, prepare_storage_payer,
+    validate_core_space_state_overrides, validate_core_space_transaction_network,
 };
 
 #[derive(Clone)]
 pub struct CoreSpaceSimulationPreparer {
     chain: ConfluxChainConfig,
     provider: Arc<ConfluxSimulationProvider>,
+    state_cache: ConfluxStateCache,
+    snapshot_store: Option<ConfluxSnapshotStore>,
 }
 
 impl CoreSpaceSimulationPreparer {
     pub fn new(chain: ConfluxChainConfig, provider: Arc<ConfluxSimulationProvider>) -> Self {
-        Self { chain, provider }
+        Self {
+            chain,
+            provider,
+            state_cache: ConfluxStateCache::default(),
+            snapshot_store: None,
+        }
+    }
+
+    /// Shares upstream state reads with every preparer holding the same cache.
+    pub fn with_state_cache(mut self, state_cache: ConfluxStateCache) -> Self {
+        self.state_cache = state_cache;
+        self
+    }
+
+    /// Records the state reads of every successful simulation into `store`.
+    pub fn with_snapshot_store(mut self, store: ConfluxSnapshotStore) -> Self {
+        self.snapshot_store = Some(store);
+        self
     }
 
     pub async fn prepare_transaction(
         &self,
         epoch: CoreSpaceEpochRef,
-        request: CoreSpaceTransactionRequest,
+        mut request: CoreSpaceTransactionRequest,
         storage_limit: Option<u64>,
         epoch_height: Option<u64>,
+        state_overrides: CoreSpaceStateOverrides,
+        epoch_overrides: CoreSpaceEpochOverrides,
     ) -> Result<PreparedCoreSpaceSimulation, ConfluxSimulationError> {
         validate_core_space_transaction_network(&request, self.provider.provider_network())?;
-        let context = load_core_space_context(self.provider.as_ref(), &epoch).await?;
+        validate_core_space_state_overrides(&state_overrides, self.provider.provider_network())?;
+        // Upstream estimation cannot see overridden state.
+        if !state_overrides.is_empty() && (request.gas_limit.is_none() || storage_limit.is_none()) {
+            return Err(ConfluxSimulationError::transaction_completion_failed(
+                "a transaction with state overrides must set a gas limit and a storage limit",
+            ));
+        }
+        let mut context = load_core_space_context(self.provider.as_ref(), &epoch).await?;
+        apply_core_space_epoch_overrides(&mut context.block_context, &epoch_overrides);
+        if request.nonce.is_none() {
+            request.nonce = state_overrides
+                .get(&request.from)
+                .and_then(|account_override| account_override.nonce);
+        }
         let transaction = complete_core_space_transaction(
             self.provider.as_ref(),
             &context,
@@ -45,15 +91,303 @@ impl CoreSpaceSimulationPreparer {
             epoch_height,
         )
         .await?;
-        self.prepare_completed_transaction(context, transaction)
+        self.prepare_completed_transaction(context, transaction, state_overrides)
             .await
     }
 
+    /// Completes an ordered bundle against the selected epoch. Later
+    /// transactions depend on the effects of earlier ones, so only the first
+    /// transaction may rely on upstream estimation, and only without state
+    /// overrides. Nonces of repeated senders continue from the previous
+    /// transaction in the same space.
+    pub async fn prepare_bundle(
+        &self,
+        epoch: CoreSpaceEpochRef,
+        transactions: Vec<CoreSpaceBundleTransaction>,
+        state_overrides: CoreSpaceStateOverrides,
+        epoch_overrides: CoreSpaceEpochOverrides,
+    ) -> Result<PreparedCoreSpaceBundle, ConfluxSimulationError> {
+        validate_core_space_state_overrides(&state_overrides, self.provider.provider_network())?;
+        let mut context = load_core_space_context(self.provider.as_ref(), &epoch).await?;
+        apply_core_space_epoch_overrides(&mut context.block_context, &epoch_overrides);
+        let espace_context = context.espace_context();
+        let mut next_core_space_nonces = state_overrides
+            .iter()
+            .filter_map(|(address, account_override)| Some((*address, account_override.nonce?)))
+            .collect::<HashMap<_, _>>();
+        let mut next_espace_nonces = HashMap::<Address, u64>::new();
+        let estimates_first = state_overrides.is_empty();
+        let mut prepared = Vec::with_capacity(transactions.len());
+
+        for (index, transaction) in transactions.into_iter().enumerate() {
+            match transaction {
+                CoreSpaceBundleTransaction::CoreSpace {
+                    mut transaction,
+                    storage_limit,
+                    epoch_height,
+                } => {
+                    if (index > 0 || !estimates_first)
+                        && (transaction.gas_limit.is_none() || storage_limit.is_none())
+                    {
+                        return Err(ConfluxSimulationError::transaction_completion_failed(
+                            format!(
+                                "bundle transaction {index} must set a gas limit and a storage limit"
+                            ),
+                        ));
+                    }
+                    if transaction.nonce.is_none() {
+                        transaction.nonce = next_core_space_nonces.get(&transaction.from).copied();
+                    }
+
+                    let transaction = complete_core_space_transaction(
+                        self.provider.as_ref(),
+                        &context,
+                        transaction,
+                        storage_limit,
+                        epoch_height,
+                    )
+                    .await?;
+                    next_core_space_nonces
+                        .insert(transaction.from, transaction.nonce.saturating_add(1));
+                    // The upstream payer check cannot see earlier bundle
+                    // transactions, so the executor's `storage_sponsor_paid`
+                    // decides the payer instead.
+                    prepared.push(PreparedBundleTransaction::CoreSpace(
+                        self.prepare_core_space_transaction(&context, transaction, false)
+                            .await?,
+                    ));
+                }
+                CoreSpaceBundleTransaction::Espace(mut transaction) => {
+                    if (index > 0 || !estimates_first) && transaction.gas_limit.is_none() {
+                        return Err(ConfluxSimulationError::transaction_completion_failed(
+                            format!("bundle transaction {index} must set a gas limit"),
+                        ));
+                    }
+                    if transaction.nonce.is_none() {
+                        transaction.nonce = next_espace_nonces.get(&transaction.from).copied();
+                    }
+
+                    let transaction = complete_espace_transaction(
+                        self.provider.as_ref(),
+                        &espace_context,
+                        transaction,
+                        &EspaceBlockOverrides::default(),
+                    )
+                    .await?;
+                    next_espace_nonces
+                        .insert(transaction.from, transaction.nonce.saturating_add(1));
+                    prepared.push(PreparedBundleTransaction::Espace(
+                        prepare_espace_transaction(&espace_context, transaction, &self.chain)?,
+                    ));
+                }
+            }
+        }
+
+        let state_source = prepare_state_source(
+            Arc::clone(&self.provider),
+            self.state_cache.clone(),
+            self.snapshot_store.clone(),
+            context.state_anchor,
+        )
+        .await?
+        .with_state_overrides(build_state_overrides(state_overrides));
+
+        Ok(PreparedCoreSpaceBundle {
+            transactions: prepared,
+            state_source,
+        })
+    }
+
+    /// Loads an executed Core Space transaction together with the
+    /// transactions its epoch executed before it, in both spaces, anchored at
+    /// the parent epoch state.
+    pub async fn prepare_replay(
+        &self,
+        transaction_hash: B256,
+    ) -> Result<PreparedCoreSpaceReplay, ConfluxSimulationError> {
+        let transaction = self
+            .provider
+            .cfx_get_transaction_by_hash(transaction_hash)
+            .await?
+            .ok_or_else(|| {
+                ConfluxSimulationError::transaction_resolution_failed(format!(
+                    "provider did not return transaction {transaction_hash}"
+                ))
+            })?;
+        if is_espace_transaction(&transaction) {
+            return Err(ConfluxSimulationError::transaction_resolution_failed(
+                format!("transaction {transaction_hash} is an eSpace transaction"),
+            ));
+        }
+        let not_executed = || {
+            ConfluxSimulationError::transaction_resolution_failed(format!(
+                "transaction {transaction_hash} has not been executed"
+            ))
+        };
+        let receipt = self
+            .provider
+            .cfx_get_transaction_receipt(transaction_hash)
+            .await?
+            .ok_or_else(not_executed)?;
+        let epoch_number = receipt.epoch_number.ok_or_else(not_executed)?;
+        let epoch_number = u64::try_from(epoch_number).map_err(|_| {
+            ConfluxSimulationError::transaction_resolution_failed(format!(
+                "transaction {transaction_hash} epoch {epoch_number} exceeds u64"
+            ))
+        })?;
+        let target_block_hash = b256_to_cfx(receipt.block_hash);
+
+        let context = load_core_space_replay_context(self.provider.as_ref(), epoch_number).await?;
+        let state_anchor = CoreSpaceStateAnchor {
+            epoch_number: context.state_anchor.epoch_number(),
+            pivot_hash: context.state_anchor.pivot_hash(),
+        };
+        let mut blocks = Vec::new();
+        let mut target = None;
+        'blocks: for block in context.blocks {
+            let mut transactions = Vec::new();
+            for (transaction, receipt) in block.transactions {
+                if block.hash == target_block_hash && transaction.hash == transaction_hash {
+                    target = Some((transaction, receipt, block.block_context));
+                    blocks.push(ReplayedBlock {
+                        hash: block.hash,
+                        block_number: block.block_number,
+                        transactions,
+                    });
+                    break 'blocks;
+                }
+
+                transactions.push(ReplayedTransaction {
+                    receipt,
+                    execution: self.mined_execution(
+                        &transaction,
+                        state_anchor.clone(),
+                        block.block_context.clone(),
+                    )?,
+                });
+            }
+            blocks.push(ReplayedBlock {
+                hash: block.hash,
+                block_number: block.block_number,
+                transactions,
+            });
+        }
+        let Some((transaction, target_receipt, block_context)) = target else {
+            return Err(ConfluxSimulationError::transaction_resolution_failed(
+                format!("epoch {epoch_number} did not execute transaction {transaction_hash}"),
+            ));
+        };
+
+        let target = self.mined_core_space_execution(&transaction, state_anchor, block_context)?;
+        let state_source = prepare_state_source(
+            Arc::clone(&self.provider),
+            self.state_cache.clone(),
+            self.snapshot_store.clone(),
+            context.state_anchor,
+        )
+        .await?;
+
+        Ok(PreparedCoreSpaceReplay {
+            epoch: context.epoch,
+            blocks,
+            target,
+            target_receipt,
+            state_source,
+        })
+    }
+
+    fn mined_execution(
+        &self,
+        transaction: &CoreRpcTransaction,
+        state_anchor: CoreSpaceStateAnchor,
+        block_context: ExecutionBlockContext,
+    ) -> Result<ReplayedExecution, ConfluxSimulationError> {
+        if is_espace_transaction(transaction) {
+            let chain_id = self.chain.evm_chain_id;
+            let transaction = mined_espace_transaction(transaction, u64::from(chain_id))?;
+            return Ok(ReplayedExecution::Espace(TransactionExecutionInput {
+                block_context,
+                transaction: DryRunTransactionInput::Espace(build_espace_transaction_input(
+                    transaction,
+                    chain_id,
+                )?),
+            }));
+        }
+
+        Ok(ReplayedExecution::CoreSpace(Box::new(
+            self.mined_core_space_execution(transaction, state_anchor, block_context)?,
+        )))
+    }
+
+    fn mined_core_space_execution(
+        &self,
+        transaction: &CoreRpcTransaction,
+        state_anchor: CoreSpaceStateAnchor,
+        block_context: ExecutionBlockContext,
+    ) -> Result<ReadyCoreSpaceTransaction, ConfluxSimulationError> {
+        let chain_id = self.chain.core_space_chain_id;
+        let transaction = mined_core_space_transaction(transaction, u64::from(chain_id))?;
+
+        Ok(ReadyCoreSpaceTransaction {
+            chain_id,
+            state_anchor,
+            gas_limit: transaction.gas_limit,
+            // The upstream payer check cannot see the earlier transactions
+            // of the epoch, so the executor's `storage_sponsor_paid` decides
+            // the payer instead.
+            storage_payer: None,
+            execution_input: TransactionExecutionInput {
+                block_context,
+                transaction: DryRunTransactionInput::CoreSpace(build_core_space_transaction_input(
+                    transaction,
+                    chain_id,
+                )),
+            },
+        })
+    }
+
     async fn prepare_completed_transaction(
         &self,
         context: CoreSpaceSimulationContext,
         transaction: CoreSpaceTransaction,
+        state_overrides: CoreSpaceStateOverrides,
     ) -> Result<PreparedCoreSpaceSimulation, ConfluxSimulationError> {
+        // The upstream payer check cannot see overridden state, so the
+        // executor's `storage_sponsor_paid` decides the payer instead.
+        let transaction = match self
+            .prepare_core_space_transaction(&context, transaction, state_overrides.is_empty())
+            .await?
+        {
+            PreparedCoreSpaceTransaction::Finished(core_execution) => {
+                return Ok(PreparedCoreSpaceSimulation {
+                    state: PreparedCoreSpaceSimulationState::Finished(core_execution),
+                });
+            }
+            PreparedCoreSpaceTransaction::Ready(transaction) => *transaction,
+        };
+        let state_source = prepare_state_source(
+            Arc::clone(&self.provider),
+            self.state_cache.clone(),
+            self.snapshot_store.clone(),
+            context.state_anchor,
+        )
+        .await?
+        .with_state_overrides(build_state_overrides(state_overrides));
+
+        Ok(PreparedCoreSpaceSimulation {
+            state: PreparedCoreSpaceSimulationState::Ready(Box::new(ReadyCoreSpaceSimulation {
+                transaction,
+                state_source,
+            })),
+        })
+    }
+
+    async fn prepare_core_space_transaction(
+        &self,
+        context: &CoreSpaceSimulationContext,
+        transaction: CoreSpaceTransaction,
+        check_storage_payer: bool,
+    ) -> Result<PreparedCoreSpaceTransaction, ConfluxSimulationError> {
         let gas_limit = transaction.gas_limit;
         let chain_id = self.chain.core_space_chain_id;
         let state_anchor = CoreSpaceStateAnchor {
@@ -62,37 +396,37 @@ impl CoreSpaceSimulationPreparer {
         };
 
         if let Err(failure) = validate_core_space_transaction(&transaction, chain_id) {
-            return Ok(PreparedCoreSpaceSimulation {
-                state: PreparedCoreSpaceSimulationState::Finished(Box::new(
-                    build_core_space_not_executed(chain_id, state_anchor, gas_limit, failure),
-                )),
-            });
+            return Ok(PreparedCoreSpaceTransaction::Finished(Box::new(
+                build_core_space_not_executed(chain_id, state_anchor, gas_limit, failure),
+            )));
         }
 
-        let storage_payer = prepare_storage_payer(
-            self.provider.as_ref(),
-            context.state_anchor.core_space_epoch(),
-            &transaction,
-        )
-        .await?;
-        let transaction = build_core_space_transaction_input(transaction, chain_id);
-        let execution_input = TransactionExecutionInput {
-            block_context: context.block_context,
-            transaction: DryRunTransactionInput::CoreSpace(transaction),
+        let storage_payer = if check_storage_payer {
+            Some(
+                prepare_storage_payer(
+                    self.provider.as_ref(),
+                    context.state_anchor.core_space_epoch(),
+                    &transaction,
+                )
+                .await?,
+            )
+        } else {
+            None
         };
-        let state_source =
-            prepare_state_source(Arc::clone(&self.provider), context.state_anchor).await?;
+        let transaction = build_core_space_transaction_input(transaction, chain_id);
 
-        Ok(PreparedCoreSpaceSimulation {
-            state: PreparedCoreSpaceSimulationState::Ready(Box::new(ReadyCoreSpaceSimulation {
+        Ok(PreparedCoreSpaceTransaction::Ready(Box::new(
+            ReadyCoreSpaceTransaction {
                 chain_id,
                 state_anchor,
                 gas_limit,
                 storage_payer,
-                execution_input,
-                state_source,
-            })),
-        })
+                execution_input: TransactionExecutionInput {
+                    block_context: context.block_context.clone(),
+                    transaction: DryRunTransactionInput::CoreSpace(transaction),
+                },
+            },
+        )))
     }
 }
 
@@ -0,0 +1,357 @@
+use alloy_primitives::{Address, U256};
+use conflux_provider::{CoreRpcTransaction, CoreSpace};
+use simulation_transaction::AccessListItem;
+
+use crate::{
+    ConfluxSimulationError,
+    espace::{EspaceTransaction, EspaceTransactionVariant},
+};
+
+use super::{CoreSpaceAccessListItem, CoreSpaceTransaction, CoreSpaceTransactionVariant};
+
+pub(crate) fn is_espace_transaction(transaction: &CoreRpcTransaction) -> bool {
+    transaction.space == Some(CoreSpace::Ethereum)
+}
+
+/// Rebuilds a mined Core Space transaction. The simulation chain id is used
+/// when the provider omits the transaction's own.
+pub(crate) fn mined_core_space_transaction(
+    transaction: &CoreRpcTransaction,
+    fallback_chain_id: u64,
+) -> Result<CoreSpaceTransaction, ConfluxSimulationError> {
+    let variant = match mined_transaction_type(transaction)? {
+        MinedTransactionType::Legacy => CoreSpaceTransactionVariant::Legacy {
+            gas_price: mined_u128(transaction, transaction.gas_price, "gasPrice")?,
+        },
+        MinedTransactionType::AccessList => CoreSpaceTransactionVariant::AccessList {
+            gas_price: mined_u128(transaction, transaction.gas_price, "gasPrice")?,
+            access_list: core_space_access_list(transaction),
+        },
+        MinedTransactionType::DynamicFee => {
+            let (max_fee_per_gas, max_priority_fee_per_gas) = mined_dynamic_fees(transaction)?;
+            CoreSpaceTransactionVariant::DynamicFee {
+                max_fee_per_gas,
+                max_priority_fee_per_gas,
+                access_list: core_space_access_list(transaction),
+            }
+        }
+    };
+
+    Ok(CoreSpaceTransaction {
+        from: transaction.from.clone(),
+        to: transaction.to.clone(),
+        nonce: mined_u64(transaction, transaction.nonce, "nonce")?,
+        gas_limit: mined_u64(transaction, transaction.gas, "gas")?,
+        value: transaction.value,
+        data: transaction.data.clone(),
+        chain_id: mined_chain_id(transaction, fallback_chain_id)?,
+        variant,
+        storage_limit: mined_u64(transaction, transaction.storage_limit, "storageLimit")?,
+        epoch_height: mined_u64(transaction, transaction.epoch_height, "epochHeight")?,
+    })
+}
+
+/// Rebuilds a mined eSpace transaction from its Core Space RPC view, which
+/// lists eSpace transactions packed in Core Space blocks.
+pub(crate) fn mined_espace_transaction(
+    transaction: &CoreRpcTransaction,
+    fallback_chain_id: u64,
+) -> Result<EspaceTransaction, ConfluxSimulationError> {
+    let variant = match mined_transaction_type(transaction)? {
+        MinedTransactionType::Legacy => EspaceTransactionVariant::Legacy {
+            gas_price: mined_u128(transaction, transaction.gas_price, "gasPrice")?,
+        },
+        MinedTransactionType::AccessList => EspaceTransactionVariant::AccessList {
+            gas_price: mined_u128(transaction, transaction.gas_price, "gasPrice")?,
+            access_list: espace_access_list(transaction),
+        },
+        MinedTransactionType::DynamicFee => {
+            let (max_fee_per_gas, max_priority_fee_per_gas) = mined_dynamic_fees(transaction)?;
+            EspaceTransactionVariant::DynamicFee {
+                max_fee_per_gas,
+                max_priority_fee_per_gas,
+                access_list: espace_access_list(transaction),
+            }
+        }
+    };
+
+    Ok(EspaceTransaction {
+        from: Address::from_slice(&transaction.from.bytes()),
+        to: transaction
+            .to
+            .as_ref()
+            .map(|to| Address::from_slice(&to.bytes())),
+        nonce: mined_u64(transaction, transaction.nonce, "nonce")?,
+        gas_limit: mined_u64(transaction, transaction.gas, "gas")?,
+        value: transaction.value,
+        data: transaction.data.clone(),
+        chain_id: mined_chain_id(transaction, fallback_chain_id)?,
+        variant,
+    })
+}
+
+enum MinedTransactionType {
+    Legacy,
+    AccessList,
+    DynamicFee,
+}
+
+fn mined_transaction_type(
+    transaction: &CoreRpcTransaction,
+) -> Result<MinedTransactionType, ConfluxSimulationError> {
+    match transaction.transaction_type {
+        None => Ok(MinedTransactionType::Legacy),
+        Some(value) if value.is_zero() => Ok(MinedTransactionType::Legacy),
+        Some(value) if value == U256::from(1_u8) => Ok(MinedTransactionType::AccessList),
+        Some(value) if value == U256::from(2_u8) => Ok(MinedTransactionType::DynamicFee),
+        Some(value) => Err(ConfluxSimulationError::transaction_resolution_failed(
+            format!(
+                "transaction {} has unsupported type {value}",
+                transaction.hash
+            ),
+        )),
+    }
+}
+
+fn mined_dynamic_fees(
+    transaction: &CoreRpcTransaction,
+) -> Result<(u128, u128), ConfluxSimulationError> {
+    let (Some(max_fee_per_gas), Some(max_priority_fee_per_gas)) = (
+        transaction.max_fee_per_gas,
+        transaction.max_priority_fee_per_gas,
+    ) else {
+        return Err(ConfluxSimulationError::transaction_resolution_failed(
+            format!(
+                "dynamic fee transaction {} is missing its fee caps",
+                transaction.hash
+            ),
+        ));
+    };
+
+    Ok((
+        mined_u128(transaction, max_fee_per_gas, "maxFeePerGas")?,
+        mined_u128(
+            transaction,
+            max_priority_fee_per_gas,
+            "maxPriorityFeePerGas",
+        )?,
+    ))
+}
+
+fn mined_chain_id(
+    transaction: &CoreRpcTransaction,
+    fallback_chain_id: u64,
+) -> Result<u64, ConfluxSimulationError> {
+    transaction
+        .chain_id
+        .map_or(Ok(fallback_chain_id), |chain_id| {
+            mined_u64(transaction, chain_id, "chainId")
+        })
+}
+
+fn core_space_access_list(transaction: &CoreRpcTransaction) -> Vec<CoreSpaceAccessListItem> {
+    transaction
+        .access_list
+        .iter()
+        .flatten()
+        .map(|item| CoreSpaceAccessListItem {
+            address: item.address.clone(),
+            storage_keys: item.storage_keys.clone(),
+        })
+        .collect()
+}
+
+fn espace_access_list(transaction: &CoreRpcTransaction) -> Vec<AccessListItem> {
+    transaction
+        .access_list
+        .iter()
+        .flatten()
+        .map(|item| AccessListItem {
+            address: Address::from_slice(&item.address.bytes()),
+            storage_keys: item.storage_keys.clone(),
+        })
+        .collect()
+}
+
+fn mined_u64(
+    transaction: &CoreRpcTransaction,
+    value: U256,
+    field: &str,
+) -> Result<u64, ConfluxSimulationError> {
+    u64::try_from(value).map_err(|_| mined_value_out_of_range(transaction, value, field))
+}
+
+fn mined_u128(
+    transaction: &CoreRpcTransaction,
+    value: U256,
+    field: &str,
+) -> Result<u128, ConfluxSimulationError> {
+    u128::try_from(value).map_err(|_| mined_value_out_of_range(transaction, value, field))
+}
+
+fn mined_value_out_of_range(
+    transaction: &CoreRpcTransaction,
+    value: U256,
+    field: &str,
+) -> ConfluxSimulationError {
+    ConfluxSimulationError::transaction_resolution_failed(format!(
+        "transaction {} field `{field}` value {value:#x} exceeds the simulator maximum",
+        transaction.hash
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy_primitives::B256;
+    use conflux_provider::{CoreAddress, Network};
+    use serde_json::json;
+
+    use super::*;
+
+    const CHAIN_ID: u64 = 1029;
+
+    fn address(byte: u8) -> CoreAddress {
+        CoreAddress::from_bytes([byte; 20], Network::Main).expect("valid address")
+    }
+
+    fn mined(fields: serde_json::Value) -> CoreRpcTransaction {
+        let mut transaction = json!({
+            "type": null,
+            "space": "native",
+            "hash": B256::repeat_byte(0xaa),
+            "nonce": "0x7",
+            "blockHash": B256::repeat_byte(0xbb),
+            "transactionIndex": "0x0",
+            "from": address(0x11),
+            "to": address(0x22),
+            "value": "0x64",
+            "gasPrice": "0x3b9aca00",
+            "gas": "0x5208",
+            "contractCreated": null,
+            "data": "0x1234",
+            "storageLimit": "0x40",
+            "epochHeight": "0x100",
+            "chainId": "0x405",
+            "status": "0x0",
+            "accessList": null,
+            "maxPriorityFeePerGas": null,
+            "maxFeePerGas": null,
+            "v": "0x0",
+            "r": "0x1",
+            "s": "0x1",
+            "yParity": null,
+        });
+        let object = transaction.as_object_mut().expect("transaction object");
+        for (field, value) in fields.as_object().expect("field overrides") {
+            object.insert(field.clone(), value.clone());
+        }
+        serde_json::from_value(transaction).expect("valid mined transaction")
+    }
+
+    #[test]
+    fn rebuilds_a_legacy_transaction() {
+        let transaction = mined_core_space_transaction(&mined(json!({})), CHAIN_ID).unwrap();
+
+        assert_eq!(
+            transaction,
+            CoreSpaceTransaction {
+                from: address(0x11),
+                to: Some(address(0x22)),
+                nonce: 7,
+                gas_limit: 21_000,
+                value: U256::from(100),
+                data: vec![0x12, 0x34].into(),
+                chain_id: 1029,
+                variant: CoreSpaceTransactionVariant::Legacy {
+                    gas_price: 1_000_000_000,
+                },
+                storage_limit: 64,
+                epoch_height: 256,
+            }
+        );
+    }
+
+    #[test]
+    fn rebuilds_typed_transactions_with_their_access_lists() {
+        let access_list = json!([{
+            "address": address(0x33),
+            "storageKeys": [B256::repeat_byte(0x01)],
+        }]);
+        let expected_access_list = vec![CoreSpaceAccessListItem {
+            address: address(0x33),
+            storage_keys: vec![B256::repeat_byte(0x01)],
+        }];
+
+        let transaction = mined_core_space_transaction(
+            &mined(json!({ "type": "0x1", "accessList": access_list })),
+            CHAIN_ID,
+        )
+        .unwrap();
+        assert_eq!(
+            transaction.variant,
+            CoreSpaceTransactionVariant::AccessList {
+                gas_price: 1_000_000_000,
+                access_list: expected_access_list.clone(),
+            }
+        );
+
+        let transaction = mined_core_space_transaction(
+            &mined(json!({
+                "type": "0x2",
+                "accessList": access_list,
+                "maxFeePerGas": "0x77359400",
+                "maxPriorityFeePerGas": "0x3b9aca00",
+            })),
+            CHAIN_ID,
+        )
+        .unwrap();
+        assert_eq!(
+            transaction.variant,
+            CoreSpaceTransactionVariant::DynamicFee {
+                max_fee_per_gas: 2_000_000_000,
+                max_priority_fee_per_gas: 1_000_000_000,
+                access_list: expected_access_list,
+            }
+        );
+    }
+
+    #[test]
+    fn falls_back_to_the_simulation_chain_id() {
+        let transaction =
+            mined_core_space_transaction(&mined(json!({ "chainId": null })), 1).unwrap();
+
+        assert_eq!(transaction.chain_id, 1);
+    }
+
+    #[test]
+    fn rejects_unsupported_and_incomplete_transactions() {
+        let hash = B256::repeat_byte(0xaa);
+
+        let error =
+            mined_core_space_transaction(&mined(json!({ "type": "0x3" })), CHAIN_ID).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            format!("transaction resolution failed: transaction {hash} has unsupported type 3")
+        );
+
+        let error =
+            mined_core_space_transaction(&mined(json!({ "type": "0x2" })), CHAIN_ID).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            format!(
+                "transaction resolution failed: dynamic fee transaction {hash} is missing its fee caps"
+            )
+        );
+
+        let error =
+            mined_core_space_transaction(&mined(json!({ "gas": "0x10000000000000000" })), CHAIN_ID)
+                .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            format!(
+                "transaction resolution failed: transaction {hash} field `gas` value \
+                 0x10000000000000000 exceeds the simulator maximum"
+            )
+        );
+    }
+}
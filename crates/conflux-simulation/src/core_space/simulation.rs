@@ -1,63 +1,284 @@
-use cfx_executor::executive::ExecutionError;
+use cfx_executor::{executive::ExecutionError, machine::Machine, state::State};
 use cfx_types::Space;
 use cfx_vm_types as vm;
 use tokio::runtime::Handle;
 
 use crate::{
     ConfluxSimulationError,
+    config::ConfluxChainConfig,
+    espace::{EspaceSimulation, simulate_espace_transaction},
     execution::{
         ConfluxTransactionExecution, ConfluxTransactionExecutor, ObservationObserver,
-        TransactionExecutionOutcome, build_conflux_state, build_mainnet_machine,
+        TransactionExecutionOutcome, apply_block_transitions, apply_epoch_transitions,
+        build_conflux_state, build_machine,
     },
     preparation::{
-        PreparedCoreSpaceSimulation, PreparedCoreSpaceSimulationState, ReadyCoreSpaceSimulation,
+        PreparedBundleTransaction, PreparedCoreSpaceBundle, PreparedCoreSpaceReplay,
+        PreparedCoreSpaceSimulation, PreparedCoreSpaceSimulationState,
+        PreparedCoreSpaceTransaction, PreparedEspaceTransaction, ReadyCoreSpaceSimulation,
+        ReadyCoreSpaceTransaction, ReplayedBlock, ReplayedExecution, ReplayedReceipt,
+        ReplayedTransaction,
+    },
+    state::{
+        AnchoredVoteLists, ConfluxStateSource, MaskedSponsorWhitelistEntries,
+        execute_with_state_phases,
     },
-    state::execute_with_state_phases,
 };
 
 use super::{
-    CoreSpaceChange, CoreSpaceSimulation, PreparedStoragePayer, analysis::CoreSpaceChangeAnalysis,
-    build_core_space_execution,
+    CoreSpaceBundleSimulation, CoreSpaceBundleStep, CoreSpaceChange, CoreSpaceSimulation,
+    PreparedStoragePayer, analysis::CoreSpaceChangeAnalysis, build_core_space_execution,
 };
 
 pub(crate) fn simulate(
     prepared_simulation: PreparedCoreSpaceSimulation,
+    chain: &ConfluxChainConfig,
     runtime_handle: &Handle,
 ) -> Result<CoreSpaceSimulation, ConfluxSimulationError> {
-    match prepared_simulation.state {
+    let ReadyCoreSpaceSimulation {
+        transaction,
+        state_source,
+    } = match prepared_simulation.state {
         PreparedCoreSpaceSimulationState::Finished(core_execution) => {
-            Ok(CoreSpaceSimulation::new(*core_execution, Vec::new()))
+            return Ok(CoreSpaceSimulation::new(*core_execution, Vec::new()));
         }
-        PreparedCoreSpaceSimulationState::Ready(ready_simulation) => {
-            simulate_ready(*ready_simulation, runtime_handle)
+        PreparedCoreSpaceSimulationState::Ready(ready_simulation) => *ready_simulation,
+    };
+    let analysis_sources = CoreSpaceAnalysisSources::new(&state_source);
+    let snapshot_recorder = state_source.snapshot_recorder();
+    let mut state = build_state(state_source, runtime_handle)?;
+    let machine = build_machine(chain);
+    let simulation =
+        simulate_transaction(&mut state, &machine, &analysis_sources, transaction, None)?;
+    if let Some(recorder) = &snapshot_recorder {
+        recorder.save()?;
+    }
+
+    Ok(simulation)
+}
+
+/// Runs the bundle on one state, so each transaction sees the effects of
+/// the earlier ones.
+pub(crate) fn simulate_bundle(
+    prepared_bundle: PreparedCoreSpaceBundle,
+    chain: &ConfluxChainConfig,
+    runtime_handle: &Handle,
+) -> Result<CoreSpaceBundleSimulation, ConfluxSimulationError> {
+    let PreparedCoreSpaceBundle {
+        transactions,
+        state_source,
+    } = prepared_bundle;
+    let analysis_sources = CoreSpaceAnalysisSources::new(&state_source);
+    let snapshot_recorder = state_source.snapshot_recorder();
+    let mut state = build_state(state_source, runtime_handle)?;
+    let machine = build_machine(chain);
+    let mut steps = Vec::with_capacity(transactions.len());
+
+    for transaction in transactions {
+        let step = match transaction {
+            PreparedBundleTransaction::CoreSpace(PreparedCoreSpaceTransaction::Finished(
+                core_execution,
+            )) => CoreSpaceBundleStep::CoreSpace(CoreSpaceSimulation::new(
+                *core_execution,
+                Vec::new(),
+            )),
+            PreparedBundleTransaction::CoreSpace(PreparedCoreSpaceTransaction::Ready(
+                transaction,
+            )) => CoreSpaceBundleStep::CoreSpace(simulate_transaction(
+                &mut state,
+                &machine,
+                &analysis_sources,
+                *transaction,
+                None,
+            )?),
+            PreparedBundleTransaction::Espace(PreparedEspaceTransaction::Finished(
+                espace_execution,
+            )) => CoreSpaceBundleStep::Espace(EspaceSimulation::new(*espace_execution, Vec::new())),
+            PreparedBundleTransaction::Espace(PreparedEspaceTransaction::Ready(transaction)) => {
+                CoreSpaceBundleStep::Espace(simulate_espace_transaction(
+                    &mut state,
+                    &machine,
+                    *transaction,
+                )?)
+            }
+        };
+        let succeeded = step.succeeded();
+        steps.push(step);
+        if !succeeded {
+            break;
         }
     }
+
+    if let Some(recorder) = &snapshot_recorder {
+        recorder.save()?;
+    }
+
+    Ok(CoreSpaceBundleSimulation::new(steps))
 }
 
-fn simulate_ready(
-    ready_simulation: ReadyCoreSpaceSimulation,
+/// Re-executes the epoch of the target up to it on the parent epoch state,
+/// applying the system steps upstream runs before the epoch and before each
+/// block, and checks every execution against its receipt. Core Space
+/// transactions are analyzed like bundle steps so the target's analysis
+/// starts from the state they committed.
+pub(crate) fn simulate_replay(
+    prepared_replay: PreparedCoreSpaceReplay,
+    chain: &ConfluxChainConfig,
     runtime_handle: &Handle,
 ) -> Result<CoreSpaceSimulation, ConfluxSimulationError> {
-    let ReadyCoreSpaceSimulation {
+    let PreparedCoreSpaceReplay {
+        epoch,
+        blocks,
+        target,
+        target_receipt,
+        state_source,
+    } = prepared_replay;
+    let analysis_sources = CoreSpaceAnalysisSources::new(&state_source);
+    let snapshot_recorder = state_source.snapshot_recorder();
+    let mut state = build_state(state_source, runtime_handle)?;
+    let machine = build_machine(chain);
+
+    apply_epoch_transitions(&mut state, &machine, epoch.height, epoch.pivot_hash).map_err(
+        |error| ConfluxSimulationError::StateAccess {
+            message: format!(
+                "failed to apply the transitions of epoch {}: {error}",
+                epoch.height
+            ),
+        },
+    )?;
+    for ReplayedBlock {
+        hash,
+        block_number,
+        transactions,
+    } in blocks
+    {
+        apply_block_transitions(&mut state, &machine, block_number, hash).map_err(|error| {
+            ConfluxSimulationError::StateAccess {
+                message: format!("failed to apply the transitions of block {hash:?}: {error}"),
+            }
+        })?;
+        for ReplayedTransaction { receipt, execution } in transactions {
+            match execution {
+                ReplayedExecution::CoreSpace(transaction) => {
+                    simulate_transaction(
+                        &mut state,
+                        &machine,
+                        &analysis_sources,
+                        *transaction,
+                        Some(receipt),
+                    )?;
+                }
+                ReplayedExecution::Espace(execution_input) => {
+                    let execution = ConfluxTransactionExecutor::new(&mut state, &machine)
+                        .execute(execution_input, ObservationObserver::new(Space::Ethereum))?;
+                    verify_replayed_outcome(&execution.outcome, receipt)?;
+                }
+            }
+        }
+    }
+
+    let simulation = simulate_transaction(
+        &mut state,
+        &machine,
+        &analysis_sources,
+        target,
+        Some(target_receipt),
+    )?;
+    if let Some(recorder) = &snapshot_recorder {
+        recorder.save()?;
+    }
+
+    Ok(simulation)
+}
+
+/// Fails the replay when an execution differs from what its receipt
+/// reports, since every later execution would then read a different state.
+fn verify_replayed_outcome(
+    outcome: &TransactionExecutionOutcome,
+    receipt: ReplayedReceipt,
+) -> Result<(), ConfluxSimulationError> {
+    let ReplayedReceipt {
+        transaction_hash,
+        succeeded,
+        gas_used,
+    } = receipt;
+    let diverged = |detail: String| ConfluxSimulationError::ExecutionInternal {
+        message: format!("replay diverged: transaction {transaction_hash} {detail}"),
+    };
+    let (replayed_succeeded, replayed_gas_used) = match outcome {
+        TransactionExecutionOutcome::Success(details) => (true, details.common.gas_used),
+        TransactionExecutionOutcome::Failed { details, .. } => (false, details.common.gas_used),
+        TransactionExecutionOutcome::NotExecutedDrop(_)
+        | TransactionExecutionOutcome::NotExecutedToReconsiderPacking(_) => {
+            return Err(diverged("was not executed".to_string()));
+        }
+    };
+    if replayed_succeeded != succeeded {
+        let outcome_name = |succeeded: bool| if succeeded { "succeeded" } else { "failed" };
+        return Err(diverged(format!(
+            "{} but its receipt reports it {}",
+            outcome_name(replayed_succeeded),
+            outcome_name(succeeded)
+        )));
+    }
+    if replayed_gas_used != gas_used {
+        return Err(diverged(format!(
+            "used {replayed_gas_used} gas but its receipt reports {gas_used}"
+        )));
+    }
+
+    Ok(())
+}
+
+/// Reads the change analysis consults, shared by every transaction executed
+/// on the same state. Each analyzed transaction hands its committed vote
+/// lists and whitelist writes on to the next, which checks its vote lists
+/// against the state it executes on.
+struct CoreSpaceAnalysisSources {
+    masked_sponsor_whitelist_entries: MaskedSponsorWhitelistEntries,
+    anchored_vote_lists: AnchoredVoteLists,
+}
+
+impl CoreSpaceAnalysisSources {
+    fn new(state_source: &ConfluxStateSource) -> Self {
+        Self {
+            masked_sponsor_whitelist_entries: state_source.masked_sponsor_whitelist_entries(),
+            anchored_vote_lists: state_source.anchored_vote_lists(),
+        }
+    }
+}
+
+fn build_state(
+    state_source: ConfluxStateSource,
+    runtime_handle: &Handle,
+) -> Result<State, ConfluxSimulationError> {
+    build_conflux_state(state_source, runtime_handle.clone()).map_err(|error| {
+        ConfluxSimulationError::StateAccess {
+            message: error.to_string(),
+        }
+    })
+}
+
+/// Replayed transactions pass their receipt, which the execution is checked
+/// against before its changes are analyzed.
+fn simulate_transaction(
+    state: &mut State,
+    machine: &Machine,
+    analysis_sources: &CoreSpaceAnalysisSources,
+    transaction: ReadyCoreSpaceTransaction,
+    receipt: Option<ReplayedReceipt>,
+) -> Result<CoreSpaceSimulation, ConfluxSimulationError> {
+    let ReadyCoreSpaceTransaction {
         chain_id,
         state_anchor,
         gas_limit,
         storage_payer,
         execution_input,
-        state_source,
-    } = ready_simulation;
-    let masked_sponsor_whitelist_entries = state_source.masked_sponsor_whitelist_entries();
-    let anchored_vote_lists = state_source.anchored_vote_lists();
-    let mut state = build_conflux_state(state_source, runtime_handle.clone()).map_err(|error| {
-        ConfluxSimulationError::StateAccess {
-            message: error.to_string(),
-        }
-    })?;
-    let machine = build_mainnet_machine();
+    } = transaction;
     let (execution, phase_values) = execute_with_state_phases(
-        &mut state,
+        state,
         |state| {
-            ConfluxTransactionExecutor::new(state, &machine)
+            ConfluxTransactionExecutor::new(state, machine)
                 .execute(execution_input, ObservationObserver::new(Space::Native))
                 .map_err(ConfluxSimulationError::from)
         },
@@ -67,28 +288,26 @@ fn simulate_ready(
             }
             CoreSpaceChangeAnalysis::from_execution(
                 execution,
-                &machine,
-                &masked_sponsor_whitelist_entries,
-                &anchored_vote_lists,
+                machine,
+                &analysis_sources.masked_sponsor_whitelist_entries,
+                &analysis_sources.anchored_vote_lists,
             )
             .map(Some)
         },
         |state, execution, analysis, state_phase| {
-            analysis.read_state(state, &machine, &execution.prepared, state_phase)
+            analysis.read_state(state, machine, &execution.prepared, state_phase)
         },
     )?;
+    if let Some(receipt) = receipt {
+        verify_replayed_outcome(&execution.outcome, receipt)?;
+    }
 
-    let Some((analysis, phase_values)) = phase_values else {
-        return Ok(build_core_space_simulation(
-            chain_id,
-            state_anchor,
-            gas_limit,
-            storage_payer,
-            execution,
-            Vec::new(),
-        ));
+    let core_changes = match phase_values {
+        Some((analysis, phase_values)) => {
+            analysis.analyze(state, machine, &execution.prepared, phase_values)?
+        }
+        None => Vec::new(),
     };
-    let core_changes = analysis.analyze(&mut state, &machine, &execution.prepared, phase_values)?;
 
     Ok(build_core_space_simulation(
         chain_id,
@@ -104,7 +323,7 @@ fn build_core_space_simulation(
     chain_id: u32,
     state_anchor: super::CoreSpaceStateAnchor,
     gas_limit: u64,
-    storage_payer: PreparedStoragePayer,
+    storage_payer: Option<PreparedStoragePayer>,
     execution: ConfluxTransactionExecution,
     changes: Vec<CoreSpaceChange>,
 ) -> CoreSpaceSimulation {
@@ -120,8 +339,11 @@ fn build_core_space_simulation(
     CoreSpaceSimulation::new(core_execution, changes)
 }
 
+/// Keeps the upstream payer only for outcomes whose receipts report it.
+/// Otherwise, and when no upstream payer was prepared, the executor's
+/// `storage_sponsor_paid` decides `storage_covered_by_sponsor`.
 fn storage_payer_for_outcome(
-    storage_payer: PreparedStoragePayer,
+    storage_payer: Option<PreparedStoragePayer>,
     outcome: &TransactionExecutionOutcome,
     spec: &cfx_vm_types::Spec,
 ) -> Option<PreparedStoragePayer> {
@@ -138,11 +360,7 @@ fn storage_payer_for_outcome(
         }
     };
 
-    if storage_payer_is_reported(outcome, spec.cip78a, spec.cip78b) {
-        Some(storage_payer)
-    } else {
-        None
-    }
+    storage_payer.filter(|_| storage_payer_is_reported(outcome, spec.cip78a, spec.cip78b))
 }
 
 #[derive(Clone, Copy)]
@@ -1,23 +1,44 @@
 use tokio::runtime::Handle;
 
-use crate::{ConfluxSimulationError, PreparedCoreSpaceSimulation};
+use crate::{
+    ConfluxSimulationError, PreparedCoreSpaceBundle, PreparedCoreSpaceReplay,
+    PreparedCoreSpaceSimulation, config::ConfluxChainConfig,
+};
 
-use super::{CoreSpaceSimulation, simulation};
+use super::{CoreSpaceBundleSimulation, CoreSpaceSimulation, simulation};
 
 #[derive(Clone)]
 pub struct CoreSpaceSimulator {
+    chain: ConfluxChainConfig,
     runtime_handle: Handle,
 }
 
 impl CoreSpaceSimulator {
-    pub fn new(runtime_handle: Handle) -> Self {
-        Self { runtime_handle }
+    pub fn new(chain: ConfluxChainConfig, runtime_handle: Handle) -> Self {
+        Self {
+            chain,
+            runtime_handle,
+        }
     }
 
     pub fn simulate(
         &self,
         prepared_simulation: PreparedCoreSpaceSimulation,
     ) -> Result<CoreSpaceSimulation, ConfluxSimulationError> {
-        simulation::simulate(prepared_simulation, &self.runtime_handle)
+        simulation::simulate(prepared_simulation, &self.chain, &self.runtime_handle)
+    }
+
+    pub fn simulate_bundle(
+        &self,
+        prepared_bundle: PreparedCoreSpaceBundle,
+    ) -> Result<CoreSpaceBundleSimulation, ConfluxSimulationError> {
+        simulation::simulate_bundle(prepared_bundle, &self.chain, &self.runtime_handle)
+    }
+
+    pub fn simulate_replay(
+        &self,
+        prepared_replay: PreparedCoreSpaceReplay,
+    ) -> Result<CoreSpaceSimulation, ConfluxSimulationError> {
+        simulation::simulate_replay(prepared_replay, &self.chain, &self.runtime_handle)
     }
 }
@@ -17,7 +17,14 @@ use crate::{
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum CoreSpaceEpochRef {
     LatestState,
+    /// Resolves to the newest executed epoch, as later mined epochs have no
+    /// state yet.
+    LatestMined,
+    LatestConfirmed,
+    LatestFinalized,
     Number(u64),
+    /// Hash of the pivot block of the epoch.
+    Hash(B256),
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -97,6 +104,9 @@ impl CoreSpaceTransactionVariantRequest {
                     access_list: access_list.unwrap_or_default(),
                 })
             }
+            TransactionType::Blob | TransactionType::SetCode => {
+                Err(TransactionVariantError::Unsupported { transaction_type })
+            }
         }
     }
 }
@@ -23,6 +23,9 @@ pub enum ConfluxSimulationError {
     #[error("transaction completion failed: {message}")]
     TransactionCompletion { message: String },
 
+    #[error("transaction resolution failed: {message}")]
+    TransactionResolution { message: String },
+
     #[error(transparent)]
     Provider(#[from] ConfluxRpcError),
 
@@ -43,6 +46,12 @@ impl ConfluxSimulationError {
         }
     }
 
+    pub(crate) fn transaction_resolution_failed(message: impl Into<String>) -> Self {
+        Self::TransactionResolution {
+            message: message.into(),
+        }
+    }
+
     pub(crate) fn analysis_failed(message: impl Into<String>) -> Self {
         Self::Analysis {
             message: message.into(),
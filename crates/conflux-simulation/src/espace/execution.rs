@@ -1,10 +1,14 @@
 use alloy_primitives::{B256, Bytes, U256};
 use simulation_execution::ExecutionOutcome;
 
+use super::EspaceBlockOverrides;
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct SimulatedBlock {
     pub number: u64,
     pub hash: B256,
+    /// Block overrides the simulation executed with on top of this block.
+    pub overrides: EspaceBlockOverrides,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -13,8 +13,16 @@ pub use execution::{
 };
 pub(crate) use outcome::{build_espace_execution, build_espace_not_executed};
 pub use preparer::EspaceSimulationPreparer;
+pub(crate) use preparer::prepare_espace_transaction;
 pub use result::EspaceSimulation;
-pub use simulation_changes::{Change, Erc20Metadata, Erc721CollectionMetadata, NativeMetadata};
+pub use simulation_changes::{
+    Change, Erc20Metadata, Erc721CollectionMetadata, NativeMetadata, ProxySlot,
+};
 pub use simulator::EspaceSimulator;
-pub use transaction::{EspaceBlockRef, EspaceTransaction, EspaceTransactionVariant};
-pub(crate) use transaction::{build_espace_transaction_input, validate_espace_transaction};
+pub(crate) use simulator::simulate_espace_transaction;
+pub use transaction::{
+    EspaceBlockOverrides, EspaceBlockRef, EspaceTransaction, EspaceTransactionVariant,
+};
+pub(crate) use transaction::{
+    apply_espace_block_overrides, build_espace_transaction_input, validate_espace_transaction,
+};
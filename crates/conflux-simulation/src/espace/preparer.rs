@@ -1,39 +1,68 @@
 use std::sync::Arc;
 
 use crate::{
-    ConfluxSimulationError, ConfluxSimulationProvider, PreparedEspaceSimulation,
+    ConfluxSimulationError, ConfluxSimulationProvider, ConfluxSnapshotStore, ConfluxStateCache,
+    PreparedEspaceSimulation,
     config::ConfluxChainConfig,
     execution::{DryRunTransactionInput, TransactionExecutionInput},
     preparation::{
-        EspaceSimulationContext, PreparedEspaceSimulationState, ReadyEspaceSimulation,
-        complete_espace_transaction, load_espace_context, prepare_state_source,
+        EspaceSimulationContext, PreparedEspaceSimulationState, PreparedEspaceTransaction,
+        ReadyEspaceSimulation, ReadyEspaceTransaction, complete_espace_transaction,
+        load_espace_context, prepare_state_source,
     },
 };
 
 use super::{
-    EspaceBlockRef, EspaceTransaction, build_espace_not_executed, build_espace_transaction_input,
-    validate_espace_transaction,
+    EspaceBlockOverrides, EspaceBlockRef, EspaceTransaction, apply_espace_block_overrides,
+    build_espace_not_executed, build_espace_transaction_input, validate_espace_transaction,
 };
 
 #[derive(Clone)]
 pub struct EspaceSimulationPreparer {
     chain: ConfluxChainConfig,
     provider: Arc<ConfluxSimulationProvider>,
+    state_cache: ConfluxStateCache,
+    snapshot_store: Option<ConfluxSnapshotStore>,
 }
 
 impl EspaceSimulationPreparer {
     pub fn new(chain: ConfluxChainConfig, provider: Arc<ConfluxSimulationProvider>) -> Self {
-        Self { chain, provider }
+        Self {
+            chain,
+            provider,
+            state_cache: ConfluxStateCache::default(),
+            snapshot_store: None,
+        }
+    }
+
+    /// Shares upstream state reads with every preparer holding the same cache.
+    pub fn with_state_cache(mut self, state_cache: ConfluxStateCache) -> Self {
+        self.state_cache = state_cache;
+        self
+    }
+
+    /// Records the state reads of every successful simulation into `store`.
+    pub fn with_snapshot_store(mut self, store: ConfluxSnapshotStore) -> Self {
+        self.snapshot_store = Some(store);
+        self
     }
 
     pub async fn prepare_transaction(
         &self,
         block: EspaceBlockRef,
         request: simulation_transaction::TransactionRequest,
+        block_overrides: EspaceBlockOverrides,
     ) -> Result<PreparedEspaceSimulation, ConfluxSimulationError> {
-        let context = load_espace_context(self.provider.as_ref(), &block).await?;
-        let transaction =
-            complete_espace_transaction(self.provider.as_ref(), &context, request).await?;
+        let mut context = load_espace_context(self.provider.as_ref(), &block).await?;
+        apply_espace_block_overrides(&mut context.block_context, &block_overrides);
+        let transaction = complete_espace_transaction(
+            self.provider.as_ref(),
+            &context,
+            request,
+            &block_overrides,
+        )
+        .await?;
+        context.simulated_block.overrides = block_overrides;
         self.prepare_completed_transaction(context, transaction)
             .await
     }
@@ -43,38 +72,63 @@ impl EspaceSimulationPreparer {
         context: EspaceSimulationContext,
         transaction: EspaceTransaction,
     ) -> Result<PreparedEspaceSimulation, ConfluxSimulationError> {
-        let gas_limit = transaction.gas_limit;
-        let chain_id = self.chain.evm_chain_id;
-
-        if let Err(failure) = validate_espace_transaction(&transaction, chain_id) {
-            return Ok(PreparedEspaceSimulation {
-                state: PreparedEspaceSimulationState::Finished(Box::new(
-                    build_espace_not_executed(
-                        chain_id,
-                        context.simulated_block,
-                        gas_limit,
-                        failure,
-                    ),
-                )),
-            });
-        }
-
-        let transaction = build_espace_transaction_input(transaction, chain_id);
-        let execution_input = TransactionExecutionInput {
-            block_context: context.block_context,
-            transaction: DryRunTransactionInput::Espace(transaction),
+        let transaction = match prepare_espace_transaction(&context, transaction, &self.chain)? {
+            PreparedEspaceTransaction::Finished(espace_execution) => {
+                return Ok(PreparedEspaceSimulation {
+                    state: PreparedEspaceSimulationState::Finished(espace_execution),
+                });
+            }
+            PreparedEspaceTransaction::Ready(transaction) => *transaction,
         };
-        let state_source =
-            prepare_state_source(Arc::clone(&self.provider), context.state_anchor).await?;
+        let state_source = prepare_state_source(
+            Arc::clone(&self.provider),
+            self.state_cache.clone(),
+            self.snapshot_store.clone(),
+            context.state_anchor,
+        )
+        .await?;
 
         Ok(PreparedEspaceSimulation {
             state: PreparedEspaceSimulationState::Ready(Box::new(ReadyEspaceSimulation {
-                chain_id,
-                simulated_block: context.simulated_block,
-                gas_limit,
-                execution_input,
+                transaction,
                 state_source,
             })),
         })
     }
 }
+
+/// Validates a completed transaction against the chain and builds its
+/// execution input at the context block.
+pub(crate) fn prepare_espace_transaction(
+    context: &EspaceSimulationContext,
+    transaction: EspaceTransaction,
+    chain: &ConfluxChainConfig,
+) -> Result<PreparedEspaceTransaction, ConfluxSimulationError> {
+    let gas_limit = transaction.gas_limit;
+    let chain_id = chain.evm_chain_id;
+
+    if let Err(failure) = validate_espace_transaction(&transaction, chain_id) {
+        return Ok(PreparedEspaceTransaction::Finished(Box::new(
+            build_espace_not_executed(
+                chain_id,
+                context.simulated_block.clone(),
+                gas_limit,
+                failure,
+            ),
+        )));
+    }
+
+    let transaction = build_espace_transaction_input(transaction, chain_id)?;
+
+    Ok(PreparedEspaceTransaction::Ready(Box::new(
+        ReadyEspaceTransaction {
+            chain_id,
+            simulated_block: context.simulated_block.clone(),
+            gas_limit,
+            execution_input: TransactionExecutionInput {
+                block_context: context.block_context.clone(),
+                transaction: DryRunTransactionInput::Espace(transaction),
+            },
+        },
+    )))
+}
@@ -1,13 +1,18 @@
+use cfx_executor::{machine::Machine, state::State};
 use cfx_types::Space;
 use tokio::runtime::Handle;
 
 use crate::{
     ConfluxSimulationError,
+    config::ConfluxChainConfig,
     execution::{
         ConfluxTransactionExecutor, ObservationObserver, TransactionExecutionOutcome,
-        build_conflux_state, build_mainnet_machine,
+        build_conflux_state, build_machine,
+    },
+    preparation::{
+        PreparedEspaceSimulation, PreparedEspaceSimulationState, ReadyEspaceSimulation,
+        ReadyEspaceTransaction,
     },
-    preparation::{PreparedEspaceSimulation, PreparedEspaceSimulationState, ReadyEspaceSimulation},
     state::execute_with_state_phases,
 };
 
@@ -19,12 +24,16 @@ use super::{
 
 #[derive(Clone)]
 pub struct EspaceSimulator {
+    chain: ConfluxChainConfig,
     runtime_handle: Handle,
 }
 
 impl EspaceSimulator {
-    pub fn new(runtime_handle: Handle) -> Self {
-        Self { runtime_handle }
+    pub fn new(chain: ConfluxChainConfig, runtime_handle: Handle) -> Self {
+        Self {
+            chain,
+            runtime_handle,
+        }
     }
 
     pub fn simulate(
@@ -46,60 +55,74 @@ impl EspaceSimulator {
         ready_simulation: ReadyEspaceSimulation,
     ) -> Result<EspaceSimulation, ConfluxSimulationError> {
         let ReadyEspaceSimulation {
-            chain_id,
-            simulated_block,
-            gas_limit,
-            execution_input,
+            transaction,
             state_source,
         } = ready_simulation;
+        let snapshot_recorder = state_source.snapshot_recorder();
         let mut state =
             build_conflux_state(state_source, self.runtime_handle.clone()).map_err(|error| {
                 ConfluxSimulationError::StateAccess {
                     message: error.to_string(),
                 }
             })?;
-        let machine = build_mainnet_machine();
-        let (execution, phase_values) = execute_with_state_phases(
-            &mut state,
-            |state| {
-                ConfluxTransactionExecutor::new(state, &machine)
-                    .execute(execution_input, ObservationObserver::new(Space::Ethereum))
-                    .map_err(ConfluxSimulationError::from)
-            },
-            |execution| {
-                if !matches!(&execution.outcome, TransactionExecutionOutcome::Success(_)) {
-                    return Ok(None);
-                }
+        let machine = build_machine(&self.chain);
+        let simulation = simulate_espace_transaction(&mut state, &machine, transaction)?;
+        if let Some(recorder) = &snapshot_recorder {
+            recorder.save()?;
+        }
 
-                EspaceAnalysisInput::from_execution(execution).map(Some)
-            },
-            |state, execution, analysis_input, state_phase| {
-                read_espace_state_values(
-                    state,
-                    &machine,
-                    &execution.prepared,
-                    &*analysis_input,
-                    state_phase,
-                )
-            },
-        )?;
+        Ok(simulation)
+    }
+}
+
+pub(crate) fn simulate_espace_transaction(
+    state: &mut State,
+    machine: &Machine,
+    transaction: ReadyEspaceTransaction,
+) -> Result<EspaceSimulation, ConfluxSimulationError> {
+    let ReadyEspaceTransaction {
+        chain_id,
+        simulated_block,
+        gas_limit,
+        execution_input,
+    } = transaction;
+    let (execution, phase_values) = execute_with_state_phases(
+        state,
+        |state| {
+            ConfluxTransactionExecutor::new(state, machine)
+                .execute(execution_input, ObservationObserver::new(Space::Ethereum))
+                .map_err(ConfluxSimulationError::from)
+        },
+        |execution| {
+            if !matches!(&execution.outcome, TransactionExecutionOutcome::Success(_)) {
+                return Ok(None);
+            }
 
-        let Some((analysis_input, phase_values)) = phase_values else {
-            let espace_execution =
-                build_espace_execution(chain_id, simulated_block, gas_limit, execution.outcome)?;
-            return Ok(EspaceSimulation::new(espace_execution, Vec::new()));
-        };
+            EspaceAnalysisInput::from_execution(execution).map(Some)
+        },
+        |state, execution, analysis_input, state_phase| {
+            read_espace_state_values(
+                state,
+                machine,
+                &execution.prepared,
+                &*analysis_input,
+                state_phase,
+            )
+        },
+    )?;
 
-        let changes = analyze_espace_changes(
-            &mut state,
-            &machine,
+    let changes = match phase_values {
+        Some((analysis_input, phase_values)) => analyze_espace_changes(
+            state,
+            machine,
             &execution.prepared,
             analysis_input,
             phase_values,
-        )?;
-        let espace_execution =
-            build_espace_execution(chain_id, simulated_block, gas_limit, execution.outcome)?;
+        )?,
+        None => Vec::new(),
+    };
+    let espace_execution =
+        build_espace_execution(chain_id, simulated_block, gas_limit, execution.outcome)?;
 
-        Ok(EspaceSimulation::new(espace_execution, changes))
-    }
+    Ok(EspaceSimulation::new(espace_execution, changes))
 }
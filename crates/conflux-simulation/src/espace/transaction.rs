@@ -1,10 +1,12 @@
 use super::{EspaceExecutionFailure, EspaceExecutionFailureCode};
+use alloy_primitives::{Address, B256, U256};
 use primitives::transaction::{
     Action, Eip155Transaction, Eip1559Transaction, Eip2930Transaction, EthereumTransaction,
 };
 
 use crate::{
-    execution::EspaceTransactionInput,
+    ConfluxSimulationError,
+    execution::{EspaceTransactionInput, ExecutionBlockContext},
     primitive::{access_list_to_cfx, address_to_cfx, u256_to_cfx},
 };
 pub use simulation_transaction::{
@@ -15,19 +17,70 @@ pub use simulation_transaction::{
 pub enum EspaceBlockRef {
     Latest,
     Number(u64),
+    Hash(B256),
+}
+
+// `number` is the eSpace block number, which is the Conflux epoch number.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EspaceBlockOverrides {
+    pub number: Option<u64>,
+    pub timestamp: Option<u64>,
+    pub base_fee_per_gas: Option<U256>,
+    pub coinbase: Option<Address>,
+    pub gas_limit: Option<u64>,
+    pub prevrandao: Option<B256>,
+}
+
+impl EspaceBlockOverrides {
+    pub fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+}
+
+pub(crate) fn apply_espace_block_overrides(
+    context: &mut ExecutionBlockContext,
+    overrides: &EspaceBlockOverrides,
+) {
+    if let Some(number) = overrides.number {
+        context.execution_epoch_height = Some(number);
+    }
+
+    if let Some(timestamp) = overrides.timestamp {
+        context.timestamp = timestamp;
+    }
+
+    if let Some(base_fee_per_gas) = overrides.base_fee_per_gas {
+        context.base_fees.espace_base_fee_per_gas = Some(u256_to_cfx(base_fee_per_gas));
+    }
+
+    if let Some(coinbase) = overrides.coinbase {
+        context.author = address_to_cfx(coinbase);
+    }
+
+    if let Some(gas_limit) = overrides.gas_limit {
+        context.gas_limit = Some(gas_limit.into());
+    }
+
+    // The Conflux VM serves PREVRANDAO from the difficulty field.
+    if let Some(prevrandao) = overrides.prevrandao {
+        context.difficulty = Some(u256_to_cfx(U256::from_be_bytes(prevrandao.0)));
+    }
 }
 
 pub(crate) fn build_espace_transaction_input(
     input: EspaceTransaction,
     chain_id: u32,
-) -> EspaceTransactionInput {
+) -> Result<EspaceTransactionInput, ConfluxSimulationError> {
     let sender = address_to_cfx(input.from);
-    let tx = build_ethereum_transaction(input, chain_id);
+    let tx = build_ethereum_transaction(input, chain_id)?;
 
-    EspaceTransactionInput { tx, sender }
+    Ok(EspaceTransactionInput { tx, sender })
 }
 
-fn build_ethereum_transaction(input: EspaceTransaction, chain_id: u32) -> EthereumTransaction {
+fn build_ethereum_transaction(
+    input: EspaceTransaction,
+    chain_id: u32,
+) -> Result<EthereumTransaction, ConfluxSimulationError> {
     let EspaceTransaction {
         to,
         nonce,
@@ -46,7 +99,7 @@ fn build_ethereum_transaction(input: EspaceTransaction, chain_id: u32) -> Ethere
     let value = u256_to_cfx(value);
     let data = data.to_vec();
 
-    match variant {
+    let transaction = match variant {
         EspaceTransactionVariant::Legacy { gas_price } => {
             EthereumTransaction::Eip155(Eip155Transaction {
                 nonce,
@@ -86,7 +139,14 @@ fn build_ethereum_transaction(input: EspaceTransaction, chain_id: u32) -> Ethere
             data,
             access_list: access_list_to_cfx(access_list),
         }),
-    }
+        EspaceTransactionVariant::Blob { .. } | EspaceTransactionVariant::SetCode { .. } => {
+            return Err(ConfluxSimulationError::transaction_completion_failed(
+                "eSpace does not support blob or set-code transactions",
+            ));
+        }
+    };
+
+    Ok(transaction)
 }
 
 pub(crate) fn validate_espace_transaction(
@@ -119,6 +179,16 @@ pub(crate) fn validate_espace_transaction(
             max_fee_per_gas,
             max_priority_fee_per_gas,
             ..
+        }
+        | EspaceTransactionVariant::Blob {
+            max_fee_per_gas,
+            max_priority_fee_per_gas,
+            ..
+        }
+        | EspaceTransactionVariant::SetCode {
+            max_fee_per_gas,
+            max_priority_fee_per_gas,
+            ..
         } => {
             if *max_fee_per_gas == 0 {
                 return Err(EspaceExecutionFailure {
@@ -53,6 +53,11 @@ pub struct ExecutionBlockContext {
     pub(crate) epoch_hash: H256,
     pub(crate) consensus: ExecutionConsensusContext,
     pub(crate) base_fees: ExecutionBaseFees,
+    // Request-level block overrides for values that are not read from the
+    // pivot block. `None` keeps the default next-block execution environment.
+    pub(crate) execution_epoch_height: Option<u64>,
+    pub(crate) gas_limit: Option<U256>,
+    pub(crate) difficulty: Option<U256>,
 }
 
 #[derive(Debug, Error)]
@@ -65,6 +70,13 @@ pub enum ExecutionBlockContextError {
     NextBlockNumberOverflow { pivot_block_number: BlockNumber },
     #[error("next execution epoch height overflows u64 after {pivot_epoch_height}")]
     NextEpochHeightOverflow { pivot_epoch_height: u64 },
+    #[error("execution block number is out of range for overridden epoch height {epoch_height}")]
+    OverriddenBlockNumberOutOfRange { epoch_height: u64 },
+    #[error("replayed block {block_number} in epoch {epoch_height} has no parent to execute on")]
+    ReplayWithoutParent {
+        block_number: BlockNumber,
+        epoch_height: u64,
+    },
 }
 
 pub(crate) fn build_core_space_pivot_block_context(
@@ -102,9 +114,54 @@ pub(crate) fn build_execution_block_context(
             core_space_base_fee_per_gas: pivot.base_fee_per_gas,
             espace_base_fee_per_gas: espace.base_fee_per_gas,
         },
+        execution_epoch_height: None,
+        gas_limit: None,
+        difficulty: None,
     }
 }
 
+/// Builds the context a block of an executed epoch ran with. Block-level
+/// values come from the block itself and epoch-level values from the pivot.
+pub(crate) fn build_replay_block_context(
+    pivot: &CoreSpacePivotBlockContext,
+    block: &CoreSpacePivotBlockContext,
+    gas_limit: U256,
+    difficulty: U256,
+    espace: &EspaceBlockContext,
+    consensus: ExecutionConsensusContext,
+) -> Result<ExecutionBlockContext, ExecutionBlockContextError> {
+    let without_parent = || ExecutionBlockContextError::ReplayWithoutParent {
+        block_number: block.block_number,
+        epoch_height: pivot.epoch_height,
+    };
+    // The transaction env executes one block and one epoch after the
+    // context, so the context points just before the replayed block.
+    let pivot_block_number = block
+        .block_number
+        .checked_sub(1)
+        .ok_or_else(without_parent)?;
+    let pivot_epoch_height = pivot
+        .epoch_height
+        .checked_sub(1)
+        .ok_or_else(without_parent)?;
+
+    Ok(ExecutionBlockContext {
+        pivot_block_number,
+        pivot_epoch_height,
+        author: block.author,
+        timestamp: pivot.timestamp,
+        epoch_hash: pivot.hash,
+        consensus,
+        base_fees: ExecutionBaseFees {
+            core_space_base_fee_per_gas: pivot.base_fee_per_gas,
+            espace_base_fee_per_gas: espace.base_fee_per_gas,
+        },
+        execution_epoch_height: None,
+        gas_limit: Some(gas_limit),
+        difficulty: Some(difficulty),
+    })
+}
+
 fn required_block_number(value: Option<U256>) -> Result<BlockNumber, ExecutionBlockContextError> {
     value
         .ok_or(ExecutionBlockContextError::MissingBlockNumber)
@@ -118,3 +175,114 @@ fn u256_to_u64(value: U256, field: &'static str) -> Result<u64, ExecutionBlockCo
 
     Ok(value.as_u64())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn block(block_number: BlockNumber, epoch_height: u64, byte: u8) -> CoreSpacePivotBlockContext {
+        CoreSpacePivotBlockContext {
+            block_number,
+            epoch_height,
+            author: Address::repeat_byte(byte),
+            timestamp: 1_000 + block_number,
+            hash: H256::repeat_byte(byte),
+            base_fee_per_gas: Some(U256::from(byte)),
+        }
+    }
+
+    fn espace() -> EspaceBlockContext {
+        EspaceBlockContext {
+            base_fee_per_gas: Some(U256::from(7)),
+        }
+    }
+
+    fn consensus() -> ExecutionConsensusContext {
+        ExecutionConsensusContext {
+            pos_view: Some(3),
+            finalized_epoch: Some(90),
+        }
+    }
+
+    #[test]
+    fn replay_context_points_just_before_the_block() {
+        let pivot = block(205, 100, 0x02);
+        let context = build_replay_block_context(
+            &pivot,
+            &block(203, 100, 0x01),
+            U256::from(30_000_000),
+            U256::from(5),
+            &espace(),
+            consensus(),
+        )
+        .unwrap();
+
+        assert_eq!(context.pivot_block_number, 202);
+        assert_eq!(context.pivot_epoch_height, 99);
+        assert_eq!(context.execution_epoch_height, None);
+    }
+
+    #[test]
+    fn replay_context_takes_block_values_from_the_block() {
+        let context = build_replay_block_context(
+            &block(205, 100, 0x02),
+            &block(203, 100, 0x01),
+            U256::from(30_000_000),
+            U256::from(5),
+            &espace(),
+            consensus(),
+        )
+        .unwrap();
+
+        assert_eq!(context.author, Address::repeat_byte(0x01));
+        assert_eq!(context.gas_limit, Some(U256::from(30_000_000)));
+        assert_eq!(context.difficulty, Some(U256::from(5)));
+    }
+
+    #[test]
+    fn replay_context_takes_epoch_values_from_the_pivot() {
+        let context = build_replay_block_context(
+            &block(205, 100, 0x02),
+            &block(203, 100, 0x01),
+            U256::from(30_000_000),
+            U256::from(5),
+            &espace(),
+            consensus(),
+        )
+        .unwrap();
+
+        assert_eq!(context.timestamp, 1_205);
+        assert_eq!(context.epoch_hash, H256::repeat_byte(0x02));
+        assert_eq!(
+            context.base_fees.core_space_base_fee_per_gas,
+            Some(U256::from(0x02))
+        );
+        assert_eq!(
+            context.base_fees.espace_base_fee_per_gas,
+            Some(U256::from(7))
+        );
+        assert_eq!(context.consensus.pos_view, Some(3));
+        assert_eq!(context.consensus.finalized_epoch, Some(90));
+    }
+
+    #[test]
+    fn replay_context_rejects_blocks_without_a_parent() {
+        let error = build_replay_block_context(
+            &block(0, 0, 0x02),
+            &block(0, 0, 0x02),
+            U256::from(30_000_000),
+            U256::zero(),
+            &espace(),
+            consensus(),
+        )
+        .unwrap_err();
+
+        assert!(matches!(
+            error,
+            ExecutionBlockContextError::ReplayWithoutParent {
+                block_number: 0,
+                epoch_height: 0,
+            }
+        ));
+    }
+}
@@ -9,9 +9,12 @@ use cfx_vm_types::{Env, Spec};
 use primitives::{BlockNumber, SignedTransaction};
 use tokio::runtime::Handle;
 
-use crate::state::{ConfluxStateSource, new_conflux_state};
+use crate::{
+    config::ConfluxChainConfig,
+    state::{ConfluxStateSource, new_conflux_state},
+};
 
-use super::{ExecutionBlockContext, ExecutionBlockContextError, params::mainnet_common_params};
+use super::{ExecutionBlockContext, ExecutionBlockContextError, params::common_params};
 
 pub(crate) fn build_conflux_state(
     source: ConfluxStateSource,
@@ -38,14 +41,38 @@ fn next_execution_epoch_height(pivot_epoch_height: u64) -> Result<u64, Execution
         .ok_or(ExecutionBlockContextError::NextEpochHeightOverflow { pivot_epoch_height })
 }
 
+fn overridden_execution_block_number(
+    next_block_number: BlockNumber,
+    next_epoch_height: u64,
+    epoch_height: u64,
+) -> Result<BlockNumber, ExecutionBlockContextError> {
+    // Block-number fork rules must move with an overridden epoch, so the
+    // execution block number is shifted by the same distance.
+    let block_number = if epoch_height >= next_epoch_height {
+        next_block_number.checked_add(epoch_height - next_epoch_height)
+    } else {
+        next_block_number.checked_sub(next_epoch_height - epoch_height)
+    };
+
+    block_number.ok_or(ExecutionBlockContextError::OverriddenBlockNumberOutOfRange { epoch_height })
+}
+
 pub fn build_transaction_env(
     machine: &Machine,
     state: &State,
     tx: &SignedTransaction,
     input: &ExecutionBlockContext,
 ) -> Result<Env, ExecutionBlockContextError> {
-    let execution_block_number = next_execution_block_number(input.pivot_block_number)?;
-    let epoch_height = next_execution_epoch_height(input.pivot_epoch_height)?;
+    let mut execution_block_number = next_execution_block_number(input.pivot_block_number)?;
+    let mut epoch_height = next_execution_epoch_height(input.pivot_epoch_height)?;
+    if let Some(overridden_epoch_height) = input.execution_epoch_height {
+        execution_block_number = overridden_execution_block_number(
+            execution_block_number,
+            epoch_height,
+            overridden_epoch_height,
+        )?;
+        epoch_height = overridden_epoch_height;
+    }
     let base_gas_price = input.base_fees.into_space_map();
     // Derived from state, not from public block RPC.
     let burnt_gas_price = base_gas_price.map_all(|x| state.burnt_gas_price(x));
@@ -55,8 +82,8 @@ pub fn build_transaction_env(
         number: execution_block_number,
         author: input.author,
         timestamp: input.timestamp,
-        difficulty: U256::zero(),
-        gas_limit: *tx.gas(),
+        difficulty: input.difficulty.unwrap_or_default(),
+        gas_limit: input.gas_limit.unwrap_or(*tx.gas()),
         last_hash: input.epoch_hash,
         accumulated_gas_used: U256::zero(),
         epoch_height,
@@ -70,8 +97,8 @@ pub fn build_transaction_env(
     })
 }
 
-pub fn build_mainnet_machine() -> Machine {
-    Machine::new_with_builtin(mainnet_common_params(), VmFactory::default())
+pub fn build_machine(chain: &ConfluxChainConfig) -> Machine {
+    Machine::new_with_builtin(common_params(chain), VmFactory::default())
 }
 
 pub fn build_execution_spec(machine: &Machine, env: &Env) -> Spec {
@@ -13,25 +13,28 @@ mod observer;
 mod outcome;
 mod params;
 mod transaction;
+mod transitions;
 
 pub use context::{
     CoreSpacePivotBlockContext, EspaceBlockContext, ExecutionBaseFees, ExecutionBlockContext,
     ExecutionBlockContextError, ExecutionConsensusContext,
 };
 pub(crate) use context::{
-    build_core_space_pivot_block_context, build_espace_block_context, build_execution_block_context,
+    build_core_space_pivot_block_context, build_espace_block_context,
+    build_execution_block_context, build_replay_block_context,
 };
 pub(crate) use env::build_conflux_state;
-pub use env::{build_execution_spec, build_mainnet_machine, build_transaction_env};
+pub use env::{build_execution_spec, build_machine, build_transaction_env};
 pub(crate) use observer::{Observation, ObservationObserver};
 pub(crate) use outcome::{
     ConfluxExecutionOutput, TransactionExecutionError, TransactionExecutionOutcome,
 };
-pub use params::mainnet_common_params;
+pub use params::common_params;
 pub use transaction::{
     CoreSpaceTransactionInput, DryRunTransactionInput, EspaceTransactionInput,
     signed_transaction_for_dryrun,
 };
+pub(crate) use transitions::{apply_block_transitions, apply_epoch_transitions};
 
 pub(crate) struct TransactionExecutionInput {
     pub(crate) block_context: ExecutionBlockContext,
@@ -0,0 +1,62 @@
+use cfx_executor::{
+    internal_contract::{block_hash_slot, epoch_hash_slot, initialize_internal_contract_accounts},
+    machine::Machine,
+    state::{State, initialize_cip107},
+};
+use cfx_statedb::Result as StateDbResult;
+use cfx_types::{H256, U256};
+
+/// Mirrors the upstream steps run once before the first block of an epoch.
+/// Rewards and PoS interest are distributed after the epoch's transactions,
+/// so no transaction of the epoch sees them.
+pub(crate) fn apply_epoch_transitions(
+    state: &mut State,
+    machine: &Machine,
+    epoch_height: u64,
+    pivot_hash: H256,
+) -> StateDbResult<()> {
+    let params = machine.params();
+    let transition_numbers = &params.transition_numbers;
+
+    let dao_vote_start = transition_numbers.cip94n;
+    if epoch_height >= dao_vote_start
+        && (epoch_height - dao_vote_start) % params.params_dao_vote_period == 0
+    {
+        let set_pos_staking = epoch_height > transition_numbers.cip105;
+        state.initialize_or_update_dao_voted_params(set_pos_staking)?;
+    }
+    if epoch_height == transition_numbers.cip107 {
+        initialize_cip107(state)?;
+    }
+    if epoch_height >= params.transition_heights.cip133e {
+        state.set_system_storage(
+            epoch_hash_slot(epoch_height).into(),
+            U256::from_big_endian(&pivot_hash.0),
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Mirrors the upstream steps run before the transactions of each block.
+pub(crate) fn apply_block_transitions(
+    state: &mut State,
+    machine: &Machine,
+    block_number: u64,
+    block_hash: H256,
+) -> StateDbResult<()> {
+    state.bump_block_number_accumulate_interest();
+    initialize_internal_contract_accounts(
+        state,
+        machine.internal_contracts().initialized_at(block_number),
+    )?;
+    state.inc_distributable_pos_interest(block_number)?;
+    if block_number >= machine.params().transition_numbers.cip133b {
+        state.set_system_storage(
+            block_hash_slot(block_number).into(),
+            U256::from_big_endian(&block_hash.0),
+        )?;
+    }
+
+    Ok(())
+}
@@ -10,7 +10,11 @@ mod state;
 
 pub use error::ConfluxSimulationError;
 pub use preparation::{
-    CoreSpaceSimulationContext, EspaceSimulationContext, PreparedCoreSpaceSimulation,
-    PreparedEspaceSimulation,
+    CoreSpaceSimulationContext, EspaceSimulationContext, PreparedCoreSpaceBundle,
+    PreparedCoreSpaceReplay, PreparedCoreSpaceSimulation, PreparedEspaceSimulation,
+};
+pub use simulation_state_cache::StateCacheLimits;
+pub use state::{
+    ConfluxRpcError, ConfluxSimulationProvider, ConfluxSnapshotStore, ConfluxStateCache,
+    CoreSpaceResourceEstimate,
 };
-pub use state::{ConfluxRpcError, ConfluxSimulationProvider, CoreSpaceResourceEstimate};
@@ -1,23 +1,35 @@
+use std::collections::HashMap;
+
+use alloy_primitives::U256 as AlloyU256;
 use cfx_rpc_cfx_types::EpochNumber as CfxEpochNumber;
 use cfx_rpc_eth_types::BlockId as EthBlockId;
 use cfx_types::{H256, U256};
+use conflux_provider::{CoreReceipt, CoreRpcTransaction};
 
 use crate::{
     ConfluxSimulationError,
     core_space::CoreSpaceEpochRef,
-    espace::{EspaceBlockRef, SimulatedBlock},
+    espace::{EspaceBlockOverrides, EspaceBlockRef, SimulatedBlock},
     execution::{
         CoreSpacePivotBlockContext, ExecutionBlockContext, ExecutionConsensusContext,
         build_core_space_pivot_block_context, build_espace_block_context,
-        build_execution_block_context,
+        build_execution_block_context, build_replay_block_context,
     },
-    primitive::b256_from_cfx,
+    primitive::{b256_from_cfx, b256_to_cfx},
     state::{
         ConfluxSimulationProvider, ConfluxStateAnchor, CoreSpaceRpcBlock, CoreSpaceRpcPoSBlock,
         EspaceRpcBlock,
     },
 };
 
+use super::{ReplayedEpoch, ReplayedReceipt};
+
+// Receipt outcome statuses. Skipped transactions were packed in the epoch
+// but not executed.
+const SUCCEEDED_OUTCOME_STATUS: u8 = 0;
+const FAILED_OUTCOME_STATUS: u8 = 1;
+const SKIPPED_OUTCOME_STATUS: u8 = 2;
+
 pub struct EspaceSimulationContext {
     pub(crate) block_context: ExecutionBlockContext,
     pub(crate) state_anchor: ConfluxStateAnchor,
@@ -37,6 +49,7 @@ impl EspaceSimulationContext {
 pub struct CoreSpaceSimulationContext {
     pub(crate) block_context: ExecutionBlockContext,
     pub(crate) state_anchor: ConfluxStateAnchor,
+    pub(crate) espace_block: SimulatedBlock,
 }
 
 impl CoreSpaceSimulationContext {
@@ -44,6 +57,13 @@ impl CoreSpaceSimulationContext {
         self.block_context.pivot_epoch_height
     }
 
+    /// The overridden execution epoch height, or the pivot's.
+    pub fn execution_epoch_height(&self) -> u64 {
+        self.block_context
+            .execution_epoch_height
+            .unwrap_or(self.block_context.pivot_epoch_height)
+    }
+
     pub fn base_fee_per_gas(&self) -> Option<U256> {
         self.block_context.base_fees.core_space_base_fee_per_gas
     }
@@ -51,24 +71,46 @@ impl CoreSpaceSimulationContext {
     pub fn state_epoch(&self) -> CfxEpochNumber {
         self.state_anchor.core_space_epoch()
     }
+
+    /// The eSpace block of the same epoch, executing with the Core Space
+    /// block context so both spaces see the same overrides.
+    pub(crate) fn espace_context(&self) -> EspaceSimulationContext {
+        EspaceSimulationContext {
+            block_context: self.block_context.clone(),
+            state_anchor: self.state_anchor,
+            simulated_block: self.espace_block.clone(),
+        }
+    }
+}
+
+/// An executed epoch, anchored at the state of its parent epoch.
+pub(crate) struct CoreSpaceReplayContext {
+    pub(crate) state_anchor: ConfluxStateAnchor,
+    pub(crate) epoch: ReplayedEpoch,
+    pub(crate) blocks: Vec<CoreSpaceReplayBlock>,
+}
+
+/// A block of the replayed epoch and the transactions it executed, in order,
+/// each with its receipt. Skipped transactions are left out.
+pub(crate) struct CoreSpaceReplayBlock {
+    pub(crate) hash: H256,
+    pub(crate) block_number: u64,
+    pub(crate) block_context: ExecutionBlockContext,
+    pub(crate) transactions: Vec<(CoreRpcTransaction, ReplayedReceipt)>,
 }
 
 pub(crate) async fn load_espace_context(
     provider: &ConfluxSimulationProvider,
     block: &EspaceBlockRef,
 ) -> Result<EspaceSimulationContext, ConfluxSimulationError> {
-    let espace_block = provider
-        .eth_get_block_by_number(espace_block_selector(block))
-        .await?
-        .ok_or_else(|| ConfluxSimulationError::BlockNotFound {
-            block: "eSpace block".to_string(),
-        })?;
+    let espace_block = load_espace_block_by_ref(provider, block).await?;
     let state_anchor = state_anchor_from_espace_block(&espace_block)?;
     let core_space_pivot_block = load_core_space_pivot_block(provider, state_anchor).await?;
 
     let simulated_block = SimulatedBlock {
         number: state_anchor.epoch_number(),
         hash: b256_from_cfx(espace_block.hash),
+        overrides: EspaceBlockOverrides::default(),
     };
 
     let core_space_pivot = build_core_space_pivot_block_context(&core_space_pivot_block)?;
@@ -95,12 +137,7 @@ pub(crate) async fn load_core_space_context(
     provider: &ConfluxSimulationProvider,
     epoch: &CoreSpaceEpochRef,
 ) -> Result<CoreSpaceSimulationContext, ConfluxSimulationError> {
-    let core_space_pivot_block = provider
-        .cfx_get_block_by_epoch_number(core_space_epoch_selector(epoch))
-        .await?
-        .ok_or_else(|| ConfluxSimulationError::BlockNotFound {
-            block: "Core Space pivot block".to_string(),
-        })?;
+    let core_space_pivot_block = load_core_space_pivot_block_by_ref(provider, epoch).await?;
 
     let core_space_pivot = build_core_space_pivot_block_context(&core_space_pivot_block)?;
     let state_anchor = state_anchor_from_core_space_pivot(&core_space_pivot);
@@ -108,14 +145,181 @@ pub(crate) async fn load_core_space_context(
     validate_same_state_anchor(state_anchor, state_anchor_from_espace_block(&espace_block)?)?;
     let espace = build_espace_block_context(&espace_block);
     let consensus = load_core_space_consensus_context(provider, &core_space_pivot_block).await?;
+    if let CoreSpaceEpochRef::Hash(_) = epoch {
+        // Reads above select the epoch by number, so a reorganization since
+        // the hash was resolved would mix another pivot into the context.
+        let pivot_block = load_core_space_pivot_block(provider, state_anchor).await?;
+        validate_same_state_anchor(
+            state_anchor,
+            state_anchor_from_core_space_pivot(&build_core_space_pivot_block_context(
+                &pivot_block,
+            )?),
+        )?;
+    }
     let block_context = build_execution_block_context(&core_space_pivot, &espace, consensus);
 
     Ok(CoreSpaceSimulationContext {
         block_context,
         state_anchor,
+        espace_block: SimulatedBlock {
+            number: state_anchor.epoch_number(),
+            hash: b256_from_cfx(espace_block.hash),
+            overrides: EspaceBlockOverrides::default(),
+        },
     })
 }
 
+pub(crate) async fn load_core_space_replay_context(
+    provider: &ConfluxSimulationProvider,
+    epoch_number: u64,
+) -> Result<CoreSpaceReplayContext, ConfluxSimulationError> {
+    let Some(parent_epoch_number) = epoch_number.checked_sub(1) else {
+        return Err(ConfluxSimulationError::InvalidBlockContext {
+            message: "the genesis epoch has no parent state to replay on".to_string(),
+        });
+    };
+    let parent_pivot_block = load_core_space_pivot_block_by_ref(
+        provider,
+        &CoreSpaceEpochRef::Number(parent_epoch_number),
+    )
+    .await?;
+    let parent_state_anchor = state_anchor_from_core_space_pivot(
+        &build_core_space_pivot_block_context(&parent_pivot_block)?,
+    );
+
+    let mut blocks = Vec::new();
+    for hash in provider
+        .cfx_get_blocks_by_epoch(CfxEpochNumber::Num(epoch_number.into()))
+        .await?
+    {
+        blocks.push(
+            provider
+                .cfx_get_block_with_transactions(hash)
+                .await?
+                .ok_or_else(|| ConfluxSimulationError::BlockNotFound {
+                    block: format!("Core Space block {hash:?}"),
+                })?,
+        );
+    }
+    let pivot_block = blocks
+        .last()
+        .ok_or_else(|| ConfluxSimulationError::BlockNotFound {
+            block: format!("blocks of Core Space epoch {epoch_number}"),
+        })?;
+    let pivot = build_core_space_pivot_block_context(&pivot_block.block)?;
+    if pivot.epoch_height != epoch_number {
+        return Err(ConfluxSimulationError::InvalidBlockContext {
+            message: format!(
+                "Core Space pivot block height {} does not match epoch {epoch_number}",
+                pivot.epoch_height
+            ),
+        });
+    }
+    // The parent state must belong to the same pivot chain as the epoch.
+    if pivot_block.parent_hash != parent_state_anchor.pivot_hash() {
+        return Err(ConfluxSimulationError::StateAnchorInconsistent);
+    }
+
+    let state_anchor = state_anchor_from_core_space_pivot(&pivot);
+    let espace_block = load_espace_block(provider, state_anchor).await?;
+    validate_same_state_anchor(state_anchor, state_anchor_from_espace_block(&espace_block)?)?;
+    let espace = build_espace_block_context(&espace_block);
+    let consensus = load_core_space_consensus_context(provider, &pivot_block.block).await?;
+
+    let mut executed = HashMap::new();
+    for receipt in provider
+        .cfx_get_epoch_receipts(pivot.hash)
+        .await?
+        .ok_or_else(|| ConfluxSimulationError::BlockNotFound {
+            block: format!("receipts of Core Space epoch {epoch_number}"),
+        })?
+        .iter()
+        .flatten()
+    {
+        if let Some(replayed) = replayed_receipt(receipt)? {
+            executed.insert(
+                (
+                    b256_to_cfx(receipt.block_hash),
+                    b256_to_cfx(receipt.transaction_hash),
+                ),
+                replayed,
+            );
+        }
+    }
+
+    let blocks = blocks
+        .into_iter()
+        .map(|block| {
+            let hash = block.block.hash;
+            let block_pivot = build_core_space_pivot_block_context(&block.block)?;
+            let block_context = build_replay_block_context(
+                &pivot,
+                &block_pivot,
+                block.gas_limit,
+                block.difficulty,
+                &espace,
+                consensus,
+            )?;
+            let transactions = block
+                .transactions
+                .into_iter()
+                .filter_map(|transaction| {
+                    let receipt = executed.get(&(hash, b256_to_cfx(transaction.hash)))?;
+                    Some((transaction, *receipt))
+                })
+                .collect();
+
+            Ok(CoreSpaceReplayBlock {
+                hash,
+                block_number: block_pivot.block_number,
+                block_context,
+                transactions,
+            })
+        })
+        .collect::<Result<Vec<_>, ConfluxSimulationError>>()?;
+
+    Ok(CoreSpaceReplayContext {
+        state_anchor: parent_state_anchor,
+        epoch: ReplayedEpoch {
+            height: pivot.epoch_height,
+            pivot_hash: pivot.hash,
+        },
+        blocks,
+    })
+}
+
+/// What the replay checks an executed transaction against. Skipped
+/// transactions have no execution to check and read as `None`.
+fn replayed_receipt(
+    receipt: &CoreReceipt,
+) -> Result<Option<ReplayedReceipt>, ConfluxSimulationError> {
+    let transaction_hash = receipt.transaction_hash;
+    let succeeded = match receipt.outcome_status {
+        status if status == AlloyU256::from(SUCCEEDED_OUTCOME_STATUS) => true,
+        status if status == AlloyU256::from(FAILED_OUTCOME_STATUS) => false,
+        status if status == AlloyU256::from(SKIPPED_OUTCOME_STATUS) => return Ok(None),
+        status => {
+            return Err(ConfluxSimulationError::transaction_resolution_failed(
+                format!(
+                    "receipt of transaction {transaction_hash} has unknown outcome status {status}"
+                ),
+            ));
+        }
+    };
+    let gas_used = u64::try_from(receipt.gas_used).map_err(|_| {
+        ConfluxSimulationError::transaction_resolution_failed(format!(
+            "receipt of transaction {transaction_hash} gas used {} exceeds u64",
+            receipt.gas_used
+        ))
+    })?;
+
+    Ok(Some(ReplayedReceipt {
+        transaction_hash,
+        succeeded,
+        gas_used,
+    }))
+}
+
 async fn load_core_space_consensus_context(
     provider: &ConfluxSimulationProvider,
     pivot_block: &CoreSpaceRpcBlock,
@@ -185,18 +389,64 @@ async fn load_espace_block(
         })
 }
 
-fn espace_block_selector(block: &EspaceBlockRef) -> EthBlockId {
-    match block {
-        EspaceBlockRef::Latest => EthBlockId::Latest,
-        EspaceBlockRef::Number(number) => EthBlockId::Num(*number),
+async fn load_espace_block_by_ref(
+    provider: &ConfluxSimulationProvider,
+    block: &EspaceBlockRef,
+) -> Result<EspaceRpcBlock, ConfluxSimulationError> {
+    let espace_block = match block {
+        EspaceBlockRef::Latest => provider.eth_get_block_by_number(EthBlockId::Latest).await?,
+        EspaceBlockRef::Number(number) => {
+            provider
+                .eth_get_block_by_number(EthBlockId::Num(*number))
+                .await?
+        }
+        EspaceBlockRef::Hash(hash) => provider.eth_get_block_by_hash(b256_to_cfx(*hash)).await?,
+    }
+    .ok_or_else(|| ConfluxSimulationError::BlockNotFound {
+        block: "eSpace block".to_string(),
+    })?;
+
+    if let EspaceBlockRef::Hash(hash) = block
+        && espace_block.hash != b256_to_cfx(*hash)
+    {
+        return Err(ConfluxSimulationError::InvalidBlockContext {
+            message: format!(
+                "eSpace block hash does not match the requested hash: expected {hash}, got {:?}",
+                espace_block.hash
+            ),
+        });
     }
+
+    Ok(espace_block)
 }
 
-fn core_space_epoch_selector(epoch: &CoreSpaceEpochRef) -> CfxEpochNumber {
-    match epoch {
+async fn load_core_space_pivot_block_by_ref(
+    provider: &ConfluxSimulationProvider,
+    epoch: &CoreSpaceEpochRef,
+) -> Result<CoreSpaceRpcBlock, ConfluxSimulationError> {
+    let selector = match epoch {
         CoreSpaceEpochRef::LatestState => CfxEpochNumber::LatestState,
+        // Mined epochs past the newest executed one have no state to read.
+        CoreSpaceEpochRef::LatestMined => CfxEpochNumber::LatestState,
+        CoreSpaceEpochRef::LatestConfirmed => CfxEpochNumber::LatestConfirmed,
+        CoreSpaceEpochRef::LatestFinalized => CfxEpochNumber::LatestFinalized,
         CoreSpaceEpochRef::Number(number) => CfxEpochNumber::Num((*number).into()),
-    }
+        CoreSpaceEpochRef::Hash(hash) => {
+            return provider
+                .cfx_get_pivot_block_by_hash(b256_to_cfx(*hash))
+                .await?
+                .ok_or_else(|| ConfluxSimulationError::BlockNotFound {
+                    block: format!("Core Space pivot block {hash} on the current pivot chain"),
+                });
+        }
+    };
+
+    provider
+        .cfx_get_block_by_epoch_number(selector)
+        .await?
+        .ok_or_else(|| ConfluxSimulationError::BlockNotFound {
+            block: "Core Space pivot block".to_string(),
+        })
 }
 
 fn state_anchor_from_espace_block(
@@ -2,7 +2,10 @@ use std::sync::Arc;
 
 use crate::{
     ConfluxSimulationError,
-    state::{ConfluxSimulationProvider, ConfluxStateAnchor, ConfluxStateSource},
+    state::{
+        ConfluxSimulationProvider, ConfluxSnapshotStore, ConfluxStateAnchor, ConfluxStateCache,
+        ConfluxStateSource,
+    },
 };
 
 mod context;
@@ -10,19 +13,28 @@ mod prepared;
 mod transaction;
 
 pub use context::{CoreSpaceSimulationContext, EspaceSimulationContext};
-pub(crate) use context::{load_core_space_context, load_espace_context};
-pub use prepared::{PreparedCoreSpaceSimulation, PreparedEspaceSimulation};
+pub(crate) use context::{
+    load_core_space_context, load_core_space_replay_context, load_espace_context,
+};
 pub(crate) use prepared::{
-    PreparedCoreSpaceSimulationState, PreparedEspaceSimulationState, ReadyCoreSpaceSimulation,
-    ReadyEspaceSimulation,
+    PreparedBundleTransaction, PreparedCoreSpaceSimulationState, PreparedCoreSpaceTransaction,
+    PreparedEspaceSimulationState, PreparedEspaceTransaction, ReadyCoreSpaceSimulation,
+    ReadyCoreSpaceTransaction, ReadyEspaceSimulation, ReadyEspaceTransaction, ReplayedBlock,
+    ReplayedEpoch, ReplayedExecution, ReplayedReceipt, ReplayedTransaction,
+};
+pub use prepared::{
+    PreparedCoreSpaceBundle, PreparedCoreSpaceReplay, PreparedCoreSpaceSimulation,
+    PreparedEspaceSimulation,
 };
 pub(crate) use transaction::{complete_core_space_transaction, complete_espace_transaction};
 
 pub(crate) async fn prepare_state_source(
     provider: Arc<ConfluxSimulationProvider>,
+    state_cache: ConfluxStateCache,
+    snapshot_store: Option<ConfluxSnapshotStore>,
     state_anchor: ConfluxStateAnchor,
 ) -> Result<ConfluxStateSource, ConfluxSimulationError> {
-    ConfluxStateSource::prepare(state_anchor, provider)
+    ConfluxStateSource::prepare(state_anchor, provider, state_cache, snapshot_store)
         .await
         .map_err(|error| ConfluxSimulationError::StateAccess {
             message: error.to_string(),
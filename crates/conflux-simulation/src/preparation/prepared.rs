@@ -1,3 +1,6 @@
+use alloy_primitives::B256;
+use cfx_types::H256;
+
 use crate::{
     core_space::{CoreSpaceExecution, CoreSpaceStateAnchor, PreparedStoragePayer},
     espace::{EspaceExecution, SimulatedBlock},
@@ -15,11 +18,20 @@ pub(crate) enum PreparedEspaceSimulationState {
 }
 
 pub(crate) struct ReadyEspaceSimulation {
+    pub(crate) transaction: ReadyEspaceTransaction,
+    pub(crate) state_source: ConfluxStateSource,
+}
+
+pub(crate) enum PreparedEspaceTransaction {
+    Finished(Box<EspaceExecution>),
+    Ready(Box<ReadyEspaceTransaction>),
+}
+
+pub(crate) struct ReadyEspaceTransaction {
     pub(crate) chain_id: u32,
     pub(crate) simulated_block: SimulatedBlock,
     pub(crate) gas_limit: u64,
     pub(crate) execution_input: TransactionExecutionInput,
-    pub(crate) state_source: ConfluxStateSource,
 }
 
 pub struct PreparedCoreSpaceSimulation {
@@ -32,10 +44,75 @@ pub(crate) enum PreparedCoreSpaceSimulationState {
 }
 
 pub(crate) struct ReadyCoreSpaceSimulation {
+    pub(crate) transaction: ReadyCoreSpaceTransaction,
+    pub(crate) state_source: ConfluxStateSource,
+}
+
+pub(crate) enum PreparedCoreSpaceTransaction {
+    Finished(Box<CoreSpaceExecution>),
+    Ready(Box<ReadyCoreSpaceTransaction>),
+}
+
+pub(crate) struct ReadyCoreSpaceTransaction {
     pub(crate) chain_id: u32,
     pub(crate) state_anchor: CoreSpaceStateAnchor,
     pub(crate) gas_limit: u64,
-    pub(crate) storage_payer: PreparedStoragePayer,
+    /// `None` when state overrides or earlier bundle transactions make the
+    /// upstream payer check unreliable. The executor's own
+    /// `storage_sponsor_paid` then decides `storage_covered_by_sponsor`.
+    pub(crate) storage_payer: Option<PreparedStoragePayer>,
     pub(crate) execution_input: TransactionExecutionInput,
+}
+
+/// An ordered mix of Core Space and eSpace transactions sharing one state.
+pub struct PreparedCoreSpaceBundle {
+    pub(crate) transactions: Vec<PreparedBundleTransaction>,
     pub(crate) state_source: ConfluxStateSource,
 }
+
+pub(crate) enum PreparedBundleTransaction {
+    CoreSpace(PreparedCoreSpaceTransaction),
+    Espace(PreparedEspaceTransaction),
+}
+
+/// A mined Core Space transaction and the blocks its epoch executed up to
+/// it, anchored at the parent epoch state. The last block holds the
+/// transactions executed before the target in its own block.
+pub struct PreparedCoreSpaceReplay {
+    pub(crate) epoch: ReplayedEpoch,
+    pub(crate) blocks: Vec<ReplayedBlock>,
+    pub(crate) target: ReadyCoreSpaceTransaction,
+    pub(crate) target_receipt: ReplayedReceipt,
+    pub(crate) state_source: ConfluxStateSource,
+}
+
+pub(crate) struct ReplayedEpoch {
+    pub(crate) height: u64,
+    pub(crate) pivot_hash: H256,
+}
+
+pub(crate) struct ReplayedBlock {
+    pub(crate) hash: H256,
+    pub(crate) block_number: u64,
+    pub(crate) transactions: Vec<ReplayedTransaction>,
+}
+
+pub(crate) struct ReplayedTransaction {
+    pub(crate) receipt: ReplayedReceipt,
+    pub(crate) execution: ReplayedExecution,
+}
+
+/// Core Space transactions are analyzed like bundle steps, so the vote
+/// lists and whitelist writes they commit carry over to the target.
+pub(crate) enum ReplayedExecution {
+    CoreSpace(Box<ReadyCoreSpaceTransaction>),
+    Espace(TransactionExecutionInput),
+}
+
+/// What the receipt of a mined transaction reports about its execution.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct ReplayedReceipt {
+    pub(crate) transaction_hash: B256,
+    pub(crate) succeeded: bool,
+    pub(crate) gas_used: u64,
+}
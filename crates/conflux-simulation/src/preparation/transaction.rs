@@ -11,6 +11,7 @@ use crate::{
         CoreSpaceTransaction, CoreSpaceTransactionRequest, CoreSpaceTransactionVariant,
         CoreSpaceTransactionVariantRequest, validate_core_space_transaction_network,
     },
+    espace::EspaceBlockOverrides,
 };
 
 #[derive(Debug)]
@@ -43,6 +44,7 @@ pub(crate) async fn complete_espace_transaction(
     provider: &ConfluxSimulationProvider,
     context: &EspaceSimulationContext,
     request: TransactionRequest,
+    block_overrides: &EspaceBlockOverrides,
 ) -> Result<Transaction, ConfluxSimulationError> {
     let (transaction, gas_limit) =
         complete_espace_without_gas_limit(provider, context, request).await?;
@@ -59,6 +61,7 @@ pub(crate) async fn complete_espace_transaction(
                     transaction.chain_id,
                     &transaction.variant,
                     context.state_block(),
+                    block_overrides,
                 )
                 .await?;
             u64::try_from(estimate).map_err(|_| {
@@ -106,7 +109,11 @@ pub(crate) async fn complete_core_space_transaction(
     let variant = complete_core_space_transaction_variant(provider, context, variant).await?;
     let value = value.unwrap_or_default();
     let data = data.unwrap_or_default();
-    let epoch_height = requested_epoch_height.unwrap_or_else(|| context.epoch_height());
+    // A default epoch height follows an overridden execution epoch so the
+    // transaction stays within its epoch bound. Upstream estimation runs at
+    // the pivot and is given the pivot height instead.
+    let epoch_height = requested_epoch_height.unwrap_or_else(|| context.execution_epoch_height());
+    let estimation_epoch_height = requested_epoch_height.unwrap_or_else(|| context.epoch_height());
 
     let (gas_limit, storage_limit) = match (gas_limit, requested_storage_limit) {
         (Some(gas_limit), Some(storage_limit)) => (gas_limit, storage_limit),
@@ -120,7 +127,7 @@ pub(crate) async fn complete_core_space_transaction(
                     &data,
                     chain_id,
                     &variant,
-                    epoch_height,
+                    estimation_epoch_height,
                     gas_limit,
                     storage_limit,
                     context.state_epoch(),
@@ -246,6 +253,13 @@ async fn complete_espace_transaction_variant(
                 access_list,
             })
         }
+        variant @ (TransactionVariantRequest::Blob { .. }
+        | TransactionVariantRequest::SetCode { .. }) => Err(
+            ConfluxSimulationError::transaction_completion_failed(format!(
+                "eSpace does not support {} transactions",
+                variant.transaction_type()
+            )),
+        ),
     }
 }
 
@@ -4,10 +4,10 @@ use cfx_executor::{machine::Machine, state::State};
 use cfx_types::AddressSpaceUtil;
 use contract_standards::{
     CollectionStandards, ERC165_INTERFACE_ID, ERC721_INTERFACE_ID, ERC1155_INTERFACE_ID,
-    Erc20AllowanceCall, Erc20BalanceCall, Erc20TotalSupplyCall, Erc721GetApprovedCall,
-    Erc721OwnerCall, Erc721TokenKey, Erc721TokenState, Erc1155BalanceCall,
-    INVALID_ERC165_INTERFACE_ID, OperatorApprovalCall, StandardStateValues, StatePhase,
-    StateRequirements, SupportsInterfaceCall, validate_collection_standards,
+    Erc20AllowanceCall, Erc20BalanceCall, Erc20PermitNonceCall, Erc20TotalSupplyCall,
+    Erc721GetApprovedCall, Erc721OwnerCall, Erc721TokenKey, Erc721TokenState, Erc1155BalanceCall,
+    INVALID_ERC165_INTERFACE_ID, OperatorApprovalCall, Permit2AllowanceCall, StandardStateValues,
+    StatePhase, StateRequirements, SupportsInterfaceCall, validate_collection_standards,
 };
 
 use crate::{
@@ -88,6 +88,40 @@ pub(crate) fn read_standard_state_values(
             .insert(allowance_key, allowance);
     }
 
+    for &nonce_key in &standard_state_requirements.erc20_permit_nonces {
+        let nonce = read_required_value(
+            state,
+            machine,
+            prepared_execution,
+            state_phase,
+            nonce_key.token,
+            Erc20PermitNonceCall {
+                owner: nonce_key.owner,
+            },
+        )?;
+        standard_state_values
+            .erc20_permit_nonces
+            .insert(nonce_key, nonce);
+    }
+
+    for &allowance_key in &standard_state_requirements.permit2_allowances {
+        let allowance = read_required_value(
+            state,
+            machine,
+            prepared_execution,
+            state_phase,
+            allowance_key.permit2,
+            Permit2AllowanceCall {
+                user: allowance_key.owner,
+                token: allowance_key.token,
+                spender: allowance_key.spender,
+            },
+        )?;
+        standard_state_values
+            .permit2_allowances
+            .insert(allowance_key, allowance.into());
+    }
+
     for &token_key in &standard_state_requirements.erc721_tokens {
         standard_state_values.erc721_tokens.insert(
             token_key,
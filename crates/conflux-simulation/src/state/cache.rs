@@ -0,0 +1,66 @@
+use simulation_state_cache::{SharedStateCache, StateCacheLimits};
+
+use crate::state::{
+    ConfluxStateAnchor,
+    state_item::{CoreSpaceStateItem, StateItem},
+};
+
+type RawStateValue = Box<[u8]>;
+
+/// Encoded eSpace and Core Space reads shared by every simulation, keyed by
+/// the epoch they were read at.
+#[derive(Debug, Clone)]
+pub struct ConfluxStateCache {
+    reads: SharedStateCache<(ConfluxStateAnchor, StateItem), Option<RawStateValue>>,
+}
+
+impl ConfluxStateCache {
+    /// Bounds the reads to `limits`, weighing each by its encoded size.
+    pub fn new(limits: StateCacheLimits) -> Self {
+        Self {
+            reads: SharedStateCache::new(limits, raw_value_len),
+        }
+    }
+
+    pub(crate) fn get(
+        &self,
+        anchor: ConfluxStateAnchor,
+        item: StateItem,
+    ) -> Option<Option<RawStateValue>> {
+        self.reads.get(&(anchor, item))
+    }
+
+    pub(crate) fn insert(
+        &self,
+        anchor: ConfluxStateAnchor,
+        item: StateItem,
+        value: Option<RawStateValue>,
+    ) {
+        self.reads.insert((anchor, item), value);
+    }
+
+    /// Vote lists and sponsor whitelist reads also record request-local
+    /// state, and global parameters come from the request's own snapshot.
+    pub(crate) fn is_shared(item: &StateItem) -> bool {
+        match item {
+            StateItem::Espace(_) => true,
+            StateItem::CoreSpace(item) => matches!(
+                item,
+                CoreSpaceStateItem::Account { .. }
+                    | CoreSpaceStateItem::DepositList { .. }
+                    | CoreSpaceStateItem::StorageSlot { .. }
+                    | CoreSpaceStateItem::Code { .. }
+            ),
+        }
+    }
+}
+
+impl Default for ConfluxStateCache {
+    fn default() -> Self {
+        Self::new(StateCacheLimits::default())
+    }
+}
+
+fn raw_value_len(value: &Option<RawStateValue>) -> usize {
+    value.as_ref().map_or(0, |value| value.len())
+}
@@ -1,5 +1,6 @@
 use cfx_parameters::internal_contract_addresses::SPONSOR_WHITELIST_CONTROL_CONTRACT_ADDRESS;
 use cfx_types::{Address, U256};
+use serde::{Deserialize, Serialize};
 
 use crate::ConfluxRpcError;
 
@@ -9,12 +10,14 @@ const ABI_WORD_BYTES: usize = 32;
 const IS_ALL_WHITELISTED_SELECTOR: [u8; 4] = [0x79, 0xb4, 0x7f, 0xaa];
 const IS_WHITELISTED_SELECTOR: [u8; 4] = [0xb6, 0xb3, 0x52, 0x72];
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub(crate) enum CoreSpaceInternalStateItem {
     SponsorWhitelist(SponsorWhitelistStorageKey),
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub(crate) struct SponsorWhitelistStorageKey {
     pub(crate) contract_address: Address,
     pub(crate) account_address: Address,
@@ -1,8 +1,11 @@
+mod cache;
 mod core_space_internal;
+mod overrides;
 mod phases;
 mod provider;
 mod reader;
 mod rpc_types;
+mod snapshot;
 mod state_item;
 mod state_value_encoding;
 mod storage;
@@ -11,13 +14,22 @@ use cfx_rpc_cfx_types::EpochNumber as CfxEpochNumber;
 use cfx_rpc_eth_types::BlockId as EthBlockId;
 use cfx_types::{H256, U64};
 
-pub use self::provider::{ConfluxRpcError, ConfluxSimulationProvider, CoreSpaceResourceEstimate};
+pub use self::{
+    cache::ConfluxStateCache,
+    provider::{ConfluxRpcError, ConfluxSimulationProvider, CoreSpaceResourceEstimate},
+    snapshot::ConfluxSnapshotStore,
+};
 
 pub(crate) use self::{
     core_space_internal::SponsorWhitelistStorageKey,
+    overrides::{
+        ConfluxStateOverrides, CoreSpaceAccountStateOverride, SponsorStateOverride,
+        StorageStateOverride,
+    },
     phases::{StatePhaseValues, execute_with_state_phases},
     reader::{AnchoredVoteLists, ConfluxStateSource, MaskedSponsorWhitelistEntries},
-    rpc_types::{CoreSpaceRpcBlock, CoreSpaceRpcPoSBlock, EspaceRpcBlock},
+    rpc_types::{CoreSpaceRpcBlock, CoreSpaceRpcEpochBlock, CoreSpaceRpcPoSBlock, EspaceRpcBlock},
+    snapshot::ConfluxSnapshotRecorder,
     storage::new_conflux_state,
 };
 
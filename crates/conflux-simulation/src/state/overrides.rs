@@ -0,0 +1,170 @@
+use std::{collections::HashMap, sync::Arc};
+
+use cfx_types::{Address, H256, U256};
+use keccak_hash::keccak;
+use primitives::{DepositInfo, VoteStakeInfo};
+
+use crate::state::{
+    state_item::{CoreSpaceStateItem, StateItem},
+    state_value_encoding::{
+        StateValueEncodingError, decode_core_space_account, encode_code, encode_core_space_account,
+        encode_core_space_deposit_list, encode_core_space_vote_list, encode_storage_slot,
+    },
+};
+
+type StateRead = Option<Box<[u8]>>;
+
+/// Request-local Core Space state layered over the anchored reads.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct ConfluxStateOverrides {
+    core_space_accounts: HashMap<Address, CoreSpaceAccountStateOverride>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub(crate) struct CoreSpaceAccountStateOverride {
+    pub(crate) balance: Option<U256>,
+    pub(crate) nonce: Option<U256>,
+    pub(crate) code: Option<Arc<Vec<u8>>>,
+    pub(crate) storage: Option<StorageStateOverride>,
+    pub(crate) staking_balance: Option<U256>,
+    pub(crate) sponsor: Option<SponsorStateOverride>,
+    pub(crate) deposit_list: Option<Vec<DepositInfo>>,
+    pub(crate) vote_list: Option<Vec<VoteStakeInfo>>,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) enum StorageStateOverride {
+    Replace(HashMap<H256, U256>),
+    Patch(HashMap<H256, U256>),
+}
+
+#[derive(Debug, Clone, Default)]
+pub(crate) struct SponsorStateOverride {
+    pub(crate) sponsor_for_gas: Option<Address>,
+    pub(crate) sponsor_for_collateral: Option<Address>,
+    pub(crate) sponsor_gas_bound: Option<U256>,
+    pub(crate) sponsor_balance_for_gas: Option<U256>,
+    pub(crate) sponsor_balance_for_collateral: Option<U256>,
+}
+
+impl CoreSpaceAccountStateOverride {
+    fn patches_account(&self) -> bool {
+        self.balance.is_some()
+            || self.nonce.is_some()
+            || self.code.is_some()
+            || self.staking_balance.is_some()
+            || self.sponsor.is_some()
+    }
+}
+
+impl ConfluxStateOverrides {
+    pub(crate) fn insert_core_space_account(
+        &mut self,
+        address: Address,
+        account_override: CoreSpaceAccountStateOverride,
+    ) {
+        self.core_space_accounts.insert(address, account_override);
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.core_space_accounts.is_empty()
+    }
+
+    pub(crate) fn core_space_vote_list(&self, address: Address) -> Option<&[VoteStakeInfo]> {
+        self.core_space_accounts
+            .get(&address)
+            .and_then(|account| account.vote_list.as_deref())
+    }
+
+    /// Values the overrides define outright, so the anchored state is not
+    /// read for them.
+    pub(crate) fn replacement(
+        &self,
+        item: &StateItem,
+    ) -> Option<Result<StateRead, StateValueEncodingError>> {
+        let StateItem::CoreSpace(item) = item else {
+            return None;
+        };
+
+        let value = match *item {
+            CoreSpaceStateItem::StorageSlot { address, slot } => {
+                let value = match self.core_space_accounts.get(&address)?.storage.as_ref()? {
+                    StorageStateOverride::Replace(slots) => {
+                        slots.get(&slot).copied().unwrap_or_default()
+                    }
+                    StorageStateOverride::Patch(slots) => *slots.get(&slot)?,
+                };
+                (!value.is_zero()).then(|| encode_storage_slot(value))
+            }
+            CoreSpaceStateItem::Code { address, code_hash } => {
+                let code = self.core_space_accounts.get(&address)?.code.as_ref()?;
+                // Core Space code keeps the state address as its owner.
+                return Some(encode_code(code_hash, address, Arc::clone(code)).map(Some));
+            }
+            CoreSpaceStateItem::DepositList { address } => self
+                .core_space_accounts
+                .get(&address)?
+                .deposit_list
+                .clone()
+                .map(encode_core_space_deposit_list)?,
+            CoreSpaceStateItem::VoteList { address } => self
+                .core_space_vote_list(address)
+                .map(|votes| encode_core_space_vote_list(votes.to_vec()))?,
+            _ => return None,
+        };
+
+        Some(Ok(value))
+    }
+
+    /// Applies the account field overrides to an anchored account read.
+    pub(crate) fn patch(
+        &self,
+        item: &StateItem,
+        value: StateRead,
+    ) -> Result<StateRead, StateValueEncodingError> {
+        let StateItem::CoreSpace(CoreSpaceStateItem::Account { address }) = *item else {
+            return Ok(value);
+        };
+        let Some(account_override) = self
+            .core_space_accounts
+            .get(&address)
+            .filter(|account_override| account_override.patches_account())
+        else {
+            return Ok(value);
+        };
+
+        let mut account = decode_core_space_account(value.as_deref())?;
+        if let Some(balance) = account_override.balance {
+            account.balance = balance;
+        }
+        if let Some(nonce) = account_override.nonce {
+            account.nonce = nonce;
+        }
+        if let Some(code) = &account_override.code {
+            account.code_hash = keccak(code.as_slice());
+        }
+        if let Some(staking_balance) = account_override.staking_balance {
+            account.staking_balance = staking_balance;
+        }
+        if let Some(sponsor) = &account_override.sponsor {
+            let sponsor_info = &mut account.sponsor_info;
+            if let Some(sponsor_for_gas) = sponsor.sponsor_for_gas {
+                sponsor_info.sponsor_for_gas = sponsor_for_gas;
+            }
+            if let Some(sponsor_for_collateral) = sponsor.sponsor_for_collateral {
+                sponsor_info.sponsor_for_collateral = sponsor_for_collateral;
+            }
+            if let Some(sponsor_gas_bound) = sponsor.sponsor_gas_bound {
+                sponsor_info.sponsor_gas_bound = sponsor_gas_bound;
+            }
+            if let Some(sponsor_balance_for_gas) = sponsor.sponsor_balance_for_gas {
+                sponsor_info.sponsor_balance_for_gas = sponsor_balance_for_gas;
+            }
+            if let Some(sponsor_balance_for_collateral) = sponsor.sponsor_balance_for_collateral {
+                sponsor_info.sponsor_balance_for_collateral = sponsor_balance_for_collateral;
+            }
+        }
+
+        Ok(encode_core_space_account(address, account))
+    }
+}
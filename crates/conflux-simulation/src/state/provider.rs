@@ -98,6 +98,13 @@ impl ConfluxSimulationProvider {
                 Ok(ProviderEpochNumber::Number(number.as_u64()))
             }
             cfx_rpc_cfx_types::EpochNumber::LatestState => Ok(ProviderEpochNumber::LatestState),
+            cfx_rpc_cfx_types::EpochNumber::LatestMined => Ok(ProviderEpochNumber::LatestMined),
+            cfx_rpc_cfx_types::EpochNumber::LatestConfirmed => {
+                Ok(ProviderEpochNumber::LatestConfirmed)
+            }
+            cfx_rpc_cfx_types::EpochNumber::LatestFinalized => {
+                Ok(ProviderEpochNumber::LatestFinalized)
+            }
             unsupported => Err(ConfluxRpcError {
                 operation: "convert Core Space epoch selector",
                 reason: format!("unsupported epoch selector: {unsupported:?}"),
@@ -1,11 +1,12 @@
 use crate::state::{
     ConfluxRpcError,
-    rpc_types::{CoreSpaceRpcBlock, CoreSpaceRpcPoSBlock, EspaceRpcBlock},
+    rpc_types::{CoreSpaceRpcBlock, CoreSpaceRpcEpochBlock, CoreSpaceRpcPoSBlock, EspaceRpcBlock},
 };
-use alloy::{consensus::BlockHeader, primitives::B256, providers::Provider};
+use alloy::{consensus::BlockHeader, primitives::B256, providers::Provider, rpc::types::Block};
 use cfx_rpc_cfx_types::EpochNumber;
 use cfx_rpc_eth_types::BlockId;
 use cfx_types::H256;
+use conflux_provider::CoreBlockTransactions;
 
 use super::ConfluxSimulationProvider;
 
@@ -25,6 +26,92 @@ impl ConfluxSimulationProvider {
             .transpose()
     }
 
+    /// Loads the block only if it is the pivot block of its epoch on the
+    /// current pivot chain. Other blocks read as missing.
+    pub(crate) async fn cfx_get_pivot_block_by_hash(
+        &self,
+        block_hash: H256,
+    ) -> Result<Option<CoreSpaceRpcBlock>, ConfluxRpcError> {
+        let Some(block) = Self::core_request(
+            "cfx_getBlockByHash",
+            self.core_space_provider
+                .cfx_get_block_by_hash(B256::from_slice(block_hash.as_bytes()), false),
+        )
+        .await?
+        else {
+            return Ok(None);
+        };
+
+        // A pivot block's height is its epoch number.
+        let epoch_number = u64::try_from(block.height).map_err(|_| ConfluxRpcError {
+            operation: "cfx_getBlockByHash",
+            reason: format!("block height exceeds u64: {}", block.height),
+        })?;
+        let Some(pivot) = self
+            .cfx_get_block_by_epoch_number(EpochNumber::Num(epoch_number.into()))
+            .await?
+        else {
+            return Ok(None);
+        };
+        Ok((pivot.hash == block_hash).then_some(pivot))
+    }
+
+    /// Hashes of the blocks executed in the epoch, in execution order. The
+    /// pivot block is last.
+    pub(crate) async fn cfx_get_blocks_by_epoch(
+        &self,
+        epoch_number: EpochNumber,
+    ) -> Result<Vec<H256>, ConfluxRpcError> {
+        let hashes = Self::core_request(
+            "cfx_getBlocksByEpoch",
+            self.core_space_provider
+                .cfx_get_blocks_by_epoch(Self::provider_epoch(epoch_number)?),
+        )
+        .await?;
+        Ok(hashes
+            .into_iter()
+            .map(|hash| H256::from_slice(hash.as_slice()))
+            .collect())
+    }
+
+    pub(crate) async fn cfx_get_block_with_transactions(
+        &self,
+        block_hash: H256,
+    ) -> Result<Option<CoreSpaceRpcEpochBlock>, ConfluxRpcError> {
+        let Some(mut block) = Self::core_request(
+            "cfx_getBlockByHash",
+            self.core_space_provider
+                .cfx_get_block_by_hash(B256::from_slice(block_hash.as_bytes()), true),
+        )
+        .await?
+        else {
+            return Ok(None);
+        };
+
+        let transactions = match std::mem::replace(
+            &mut block.transactions,
+            CoreBlockTransactions::Hashes(Vec::new()),
+        ) {
+            CoreBlockTransactions::Full(transactions) => transactions,
+            // An empty list deserializes as full transactions, so hashes mean
+            // the provider ignored the request for full transactions.
+            CoreBlockTransactions::Hashes(_) => {
+                return Err(ConfluxRpcError {
+                    operation: "cfx_getBlockByHash",
+                    reason: "response did not include full transactions".to_string(),
+                });
+            }
+        };
+
+        Ok(Some(CoreSpaceRpcEpochBlock {
+            parent_hash: H256::from_slice(block.parent_hash.as_slice()),
+            gas_limit: crate::primitive::u256_to_cfx(block.gas_limit),
+            difficulty: crate::primitive::u256_to_cfx(block.difficulty),
+            transactions,
+            block: self.convert_core_block(block)?,
+        }))
+    }
+
     pub(crate) async fn eth_get_block_by_number(
         &self,
         block_number: BlockId,
@@ -39,17 +126,23 @@ impl ConfluxSimulationProvider {
                 reason: error.to_string(),
             })?;
 
-        block
-            .map(|block| {
-                let hash = block.hash();
-                let header = block.into_consensus_header();
-                Ok(EspaceRpcBlock {
-                    hash: H256::from_slice(hash.as_slice()),
-                    number: cfx_types::U256::from(header.number()),
-                    base_fee_per_gas: header.base_fee_per_gas().map(cfx_types::U256::from),
-                })
-            })
-            .transpose()
+        Ok(block.map(convert_espace_block))
+    }
+
+    pub(crate) async fn eth_get_block_by_hash(
+        &self,
+        block_hash: H256,
+    ) -> Result<Option<EspaceRpcBlock>, ConfluxRpcError> {
+        let block = self
+            .espace_provider
+            .get_block_by_hash(B256::from_slice(block_hash.as_bytes()))
+            .await
+            .map_err(|error| ConfluxRpcError {
+                operation: "eth_getBlockByHash",
+                reason: error.to_string(),
+            })?;
+
+        Ok(block.map(convert_espace_block))
     }
 
     pub(crate) async fn pos_get_block_by_hash(
@@ -105,3 +198,13 @@ impl ConfluxSimulationProvider {
         })
     }
 }
+
+fn convert_espace_block(block: Block) -> EspaceRpcBlock {
+    let hash = block.hash();
+    let header = block.into_consensus_header();
+    EspaceRpcBlock {
+        hash: H256::from_slice(hash.as_slice()),
+        number: cfx_types::U256::from(header.number()),
+        base_fee_per_gas: header.base_fee_per_gas().map(cfx_types::U256::from),
+    }
+}
@@ -1,23 +1,24 @@
 use alloy::{
-    primitives::{Address as AlloyAddress, Bytes as AlloyBytes, TxKind, U256 as AlloyU256},
+    primitives::{Address as AlloyAddress, B256, Bytes as AlloyBytes, TxKind, U256 as AlloyU256},
     providers::Provider,
     rpc::types::{
-        AccessList as RpcAccessList, TransactionInput,
+        AccessList as RpcAccessList, BlockOverrides as RpcBlockOverrides, TransactionInput,
         TransactionRequest as AlloyTransactionRequest,
     },
 };
 use cfx_rpc_cfx_types::EpochNumber;
 use cfx_rpc_eth_types::BlockId;
-use cfx_types::U256;
+use cfx_types::{H256, U256};
 use conflux_provider::{
-    BalanceCheckRequest, CoreAccessListItem, CoreAddress, CoreTransactionType,
-    EstimateGasAndCollateralRequest,
+    BalanceCheckRequest, BlockHashOrEpochNumber, CoreAccessListItem, CoreAddress, CoreReceipt,
+    CoreRpcTransaction, CoreTransactionType, EstimateGasAndCollateralRequest,
 };
 use serde::Deserialize;
 use simulation_transaction::TransactionVariant;
 
 use crate::{
     core_space::{CoreSpaceAccessListItem, CoreSpaceTransactionVariant},
+    espace::EspaceBlockOverrides,
     primitive::{alloy_u256_from_u64, alloy_u256_from_u128},
 };
 
@@ -78,17 +79,21 @@ impl ConfluxSimulationProvider {
         chain_id: u64,
         variant: &TransactionVariant,
         block: BlockId,
+        block_overrides: &EspaceBlockOverrides,
     ) -> Result<U256, ConfluxRpcError> {
-        let estimate = self
-            .espace_provider_at(block)?
-            .estimate_gas(espace_estimate_gas_request(
-                from, to, nonce, value, data, chain_id, variant,
-            ))
-            .await
-            .map_err(|error| ConfluxRpcError {
-                operation: "eth_estimateGas",
-                reason: error.to_string(),
-            })?;
+        let provider = self.espace_provider_at(block)?;
+        let estimation = provider.estimate_gas(espace_estimate_gas_request(
+            from, to, nonce, value, data, chain_id, variant,
+        ));
+        let estimation = if block_overrides.is_empty() {
+            estimation
+        } else {
+            estimation.with_block_overrides(espace_rpc_block_overrides(block_overrides))
+        };
+        let estimate = estimation.await.map_err(|error| ConfluxRpcError {
+            operation: "eth_estimateGas",
+            reason: error.to_string(),
+        })?;
         Ok(U256::from(estimate))
     }
 
@@ -168,6 +173,48 @@ impl ConfluxSimulationProvider {
         })
     }
 
+    pub(crate) async fn cfx_get_transaction_by_hash(
+        &self,
+        transaction_hash: B256,
+    ) -> Result<Option<CoreRpcTransaction>, ConfluxRpcError> {
+        Self::core_request(
+            "cfx_getTransactionByHash",
+            self.core_space_provider
+                .cfx_get_transaction_by_hash(transaction_hash),
+        )
+        .await
+    }
+
+    pub(crate) async fn cfx_get_transaction_receipt(
+        &self,
+        transaction_hash: B256,
+    ) -> Result<Option<CoreReceipt>, ConfluxRpcError> {
+        Self::core_request(
+            "cfx_getTransactionReceipt",
+            self.core_space_provider
+                .cfx_get_transaction_receipt(transaction_hash),
+        )
+        .await
+    }
+
+    /// Receipts of both spaces for every block of the epoch, selected by its
+    /// pivot block so a reorganized epoch is rejected upstream.
+    pub(crate) async fn cfx_get_epoch_receipts(
+        &self,
+        pivot_hash: H256,
+    ) -> Result<Option<Vec<Vec<CoreReceipt>>>, ConfluxRpcError> {
+        let selector = BlockHashOrEpochNumber::BlockHash {
+            hash: B256::from_slice(pivot_hash.as_bytes()),
+            require_pivot: Some(true),
+        };
+        Self::core_request(
+            "cfx_getEpochReceipts",
+            self.core_space_provider
+                .cfx_get_epoch_receipts(selector, Some(true)),
+        )
+        .await
+    }
+
     pub(crate) async fn cfx_check_balance_against_transaction(
         &self,
         account: CoreAddress,
@@ -213,6 +260,18 @@ struct EstimateTransaction<'a> {
     variant: &'a CoreSpaceTransactionVariant,
 }
 
+fn espace_rpc_block_overrides(overrides: &EspaceBlockOverrides) -> RpcBlockOverrides {
+    RpcBlockOverrides {
+        number: overrides.number.map(AlloyU256::from),
+        time: overrides.timestamp,
+        base_fee: overrides.base_fee_per_gas,
+        coinbase: overrides.coinbase,
+        gas_limit: overrides.gas_limit,
+        random: overrides.prevrandao,
+        ..Default::default()
+    }
+}
+
 fn espace_estimate_gas_request(
     from: AlloyAddress,
     to: Option<AlloyAddress>,
@@ -255,6 +314,32 @@ fn espace_estimate_gas_request(
             request.max_priority_fee_per_gas = Some(*max_priority_fee_per_gas);
             request.access_list = Some(RpcAccessList(access_list.to_vec()));
         }
+        TransactionVariant::Blob {
+            max_fee_per_gas,
+            max_priority_fee_per_gas,
+            max_fee_per_blob_gas,
+            access_list,
+            blob_versioned_hashes,
+        } => {
+            request.transaction_type = Some(3);
+            request.max_fee_per_gas = Some(*max_fee_per_gas);
+            request.max_priority_fee_per_gas = Some(*max_priority_fee_per_gas);
+            request.max_fee_per_blob_gas = Some(*max_fee_per_blob_gas);
+            request.access_list = Some(RpcAccessList(access_list.to_vec()));
+            request.blob_versioned_hashes = Some(blob_versioned_hashes.clone());
+        }
+        TransactionVariant::SetCode {
+            max_fee_per_gas,
+            max_priority_fee_per_gas,
+            access_list,
+            authorization_list,
+        } => {
+            request.transaction_type = Some(4);
+            request.max_fee_per_gas = Some(*max_fee_per_gas);
+            request.max_priority_fee_per_gas = Some(*max_priority_fee_per_gas);
+            request.access_list = Some(RpcAccessList(access_list.to_vec()));
+            request.authorization_list = Some(authorization_list.clone());
+        }
     }
 
     request
@@ -10,11 +10,13 @@ use cfx_storage::{Error as StorageError, Result as StorageResult};
 use tokio::sync::Mutex as AsyncMutex;
 
 use crate::state::{
-    ConfluxRpcError, ConfluxSimulationProvider, ConfluxStateAnchor,
+    ConfluxRpcError, ConfluxSimulationProvider, ConfluxStateAnchor, ConfluxStateCache,
     core_space_internal::{
         CoreSpaceInternalStateItem, SponsorWhitelistStorageKey, decode_abi_bool,
     },
+    overrides::ConfluxStateOverrides,
     rpc_types::{CoreSpaceAccountState, CoreSpaceGlobals, EspaceAccountData},
+    snapshot::{ConfluxSnapshotRecorder, ConfluxSnapshotStore},
     state_item::{CoreSpaceStateItem, EspaceStateItem, StateItem},
     state_value_encoding::{
         StateValueEncodingError, encode_code, encode_core_space_basic_account,
@@ -43,6 +45,16 @@ impl MaskedSponsorWhitelistEntries {
         Ok(())
     }
 
+    /// A transaction on the same state wrote the raw entry, so later reads
+    /// see the written value rather than the masked one.
+    pub(crate) fn unmask(&self, key: &SponsorWhitelistStorageKey) -> StorageResult<()> {
+        self.entries
+            .lock()
+            .map_err(|_| Self::lock_error())?
+            .remove(key);
+        Ok(())
+    }
+
     pub(crate) fn snapshot(&self) -> StorageResult<HashSet<SponsorWhitelistStorageKey>> {
         self.entries
             .lock()
@@ -57,7 +69,8 @@ impl MaskedSponsorWhitelistEntries {
     }
 }
 
-/// Vote lists fetched by this request's anchored StateDB reads.
+/// Vote lists fetched by this request's anchored StateDB reads, replaced by
+/// the lists later committed on the same state.
 #[derive(Clone, Default)]
 pub(crate) struct AnchoredVoteLists {
     vote_lists_by_account: Arc<SyncMutex<HashMap<Address, Vec<VoteStakeInfo>>>>,
@@ -81,6 +94,18 @@ impl AnchoredVoteLists {
         Ok(())
     }
 
+    pub(crate) fn commit(
+        &self,
+        address: Address,
+        vote_list: Vec<VoteStakeInfo>,
+    ) -> StorageResult<()> {
+        self.vote_lists_by_account
+            .lock()
+            .map_err(|_| Self::lock_error())?
+            .insert(address, vote_list);
+        Ok(())
+    }
+
     pub(crate) fn for_account(&self, address: Address) -> StorageResult<Vec<VoteStakeInfo>> {
         self.vote_lists_by_account
             .lock()
@@ -102,6 +127,9 @@ impl AnchoredVoteLists {
 pub(crate) struct ConfluxStateSource {
     state_anchor: ConfluxStateAnchor,
     provider: Arc<ConfluxSimulationProvider>,
+    state_cache: ConfluxStateCache,
+    snapshot_recorder: Option<ConfluxSnapshotRecorder>,
+    state_overrides: ConfluxStateOverrides,
     core_space_globals: CoreSpaceGlobals,
     espace_account_cache: AsyncMutex<HashMap<Address, Arc<EspaceAccountData>>>,
     masked_sponsor_whitelist_entries: MaskedSponsorWhitelistEntries,
@@ -112,6 +140,8 @@ impl ConfluxStateSource {
     pub(crate) async fn prepare(
         state_anchor: ConfluxStateAnchor,
         provider: Arc<ConfluxSimulationProvider>,
+        state_cache: ConfluxStateCache,
+        snapshot_store: Option<ConfluxSnapshotStore>,
     ) -> StorageResult<Self> {
         let core_space_epoch = state_anchor.core_space_epoch();
         let core_space_globals = provider
@@ -124,6 +154,10 @@ impl ConfluxStateSource {
         Ok(Self {
             state_anchor,
             provider,
+            state_cache,
+            snapshot_recorder: snapshot_store
+                .map(|store| ConfluxSnapshotRecorder::new(store, state_anchor)),
+            state_overrides: ConfluxStateOverrides::default(),
             core_space_globals,
             espace_account_cache: AsyncMutex::new(HashMap::new()),
             masked_sponsor_whitelist_entries: MaskedSponsorWhitelistEntries::default(),
@@ -131,6 +165,13 @@ impl ConfluxStateSource {
         })
     }
 
+    /// Layers request-local state over the anchored reads. Cached and
+    /// recorded values stay those of the anchored state.
+    pub(crate) fn with_state_overrides(mut self, state_overrides: ConfluxStateOverrides) -> Self {
+        self.state_overrides = state_overrides;
+        self
+    }
+
     pub(crate) fn state_anchor(&self) -> ConfluxStateAnchor {
         self.state_anchor
     }
@@ -143,7 +184,49 @@ impl ConfluxStateSource {
         self.masked_sponsor_whitelist_entries.clone()
     }
 
+    /// Records the reads of this source when a snapshot store is configured.
+    pub(crate) fn snapshot_recorder(&self) -> Option<ConfluxSnapshotRecorder> {
+        self.snapshot_recorder.clone()
+    }
+
     pub(crate) async fn read(&self, item: &StateItem) -> StorageResult<StateRead> {
+        if let Some(value) = self.state_overrides.replacement(item) {
+            let value =
+                value.map_err(|error| self.encoding_error("override_core_space_code", error))?;
+            // Vote-lock analysis reads back the list execution saw.
+            if let StateItem::CoreSpace(CoreSpaceStateItem::VoteList { address }) = *item
+                && let Some(vote_list) = self.state_overrides.core_space_vote_list(address)
+            {
+                self.anchored_vote_lists
+                    .record(address, vote_list.to_vec())?;
+            }
+            return Ok(value);
+        }
+
+        let value = self.read_shared(item).await?;
+        if let Some(recorder) = &self.snapshot_recorder {
+            recorder.record(*item, value.as_ref());
+        }
+        self.state_overrides
+            .patch(item, value)
+            .map_err(|error| self.encoding_error("override_core_space_account", error))
+    }
+
+    async fn read_shared(&self, item: &StateItem) -> StorageResult<StateRead> {
+        if !ConfluxStateCache::is_shared(item) {
+            return self.read_uncached(item).await;
+        }
+        if let Some(value) = self.state_cache.get(self.state_anchor, *item) {
+            return Ok(value);
+        }
+
+        let value = self.read_uncached(item).await?;
+        self.state_cache
+            .insert(self.state_anchor, *item, value.clone());
+        Ok(value)
+    }
+
+    async fn read_uncached(&self, item: &StateItem) -> StorageResult<StateRead> {
         match item {
             StateItem::CoreSpace(item) => self.read_core_space(*item).await,
             StateItem::Espace(item) => self.read_espace(*item).await,
@@ -2,6 +2,7 @@ use std::sync::Arc;
 
 use cfx_rpc_cfx_types::RpcAddress;
 use cfx_types::{H256, U64, U256};
+use conflux_provider::CoreRpcTransaction;
 use serde::Deserialize;
 
 #[derive(Debug, Clone, Deserialize)]
@@ -103,6 +104,17 @@ pub(crate) struct CoreSpaceRpcBlock {
     pub(crate) pos_reference: Option<H256>,
 }
 
+/// A block of an executed epoch with the header fields and transactions its
+/// execution depends on.
+#[derive(Debug, Clone)]
+pub(crate) struct CoreSpaceRpcEpochBlock {
+    pub(crate) block: CoreSpaceRpcBlock,
+    pub(crate) parent_hash: H256,
+    pub(crate) gas_limit: U256,
+    pub(crate) difficulty: U256,
+    pub(crate) transactions: Vec<CoreRpcTransaction>,
+}
+
 #[derive(Debug, Clone, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub(crate) struct CoreSpaceRpcPoSBlock {
@@ -0,0 +1,124 @@
+use std::{
+    collections::{HashMap, HashSet},
+    io,
+    path::PathBuf,
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+};
+
+use alloy_primitives::Bytes;
+use cfx_types::H256;
+use serde::{Deserialize, Serialize};
+use simulation_state_cache::SnapshotStore;
+
+use crate::{
+    ConfluxSimulationError,
+    state::{ConfluxStateAnchor, state_item::StateItem},
+};
+
+type RawStateValue = Box<[u8]>;
+
+/// Snapshots on disk, one file per epoch. Recording into an epoch that
+/// already has a snapshot adds the new reads to it.
+#[derive(Debug, Clone)]
+pub struct ConfluxSnapshotStore {
+    store: SnapshotStore,
+}
+
+impl ConfluxSnapshotStore {
+    pub fn new(directory: impl Into<PathBuf>) -> Self {
+        Self {
+            store: SnapshotStore::new(directory),
+        }
+    }
+
+    fn save(&self, snapshot: ConfluxStateSnapshot) -> io::Result<()> {
+        let key = format!(
+            "conflux-{}-{:#x}",
+            snapshot.epoch_number, snapshot.pivot_hash
+        );
+        self.store
+            .merge(&key, snapshot, ConfluxStateSnapshot::merge)
+    }
+}
+
+/// Encoded eSpace and Core Space state values read at one epoch.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ConfluxStateSnapshot {
+    epoch_number: u64,
+    pivot_hash: H256,
+    reads: Vec<ConfluxSnapshotRead>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ConfluxSnapshotRead {
+    item: StateItem,
+    /// `None` records a value that was absent at the epoch.
+    value: Option<Bytes>,
+}
+
+impl ConfluxStateSnapshot {
+    fn merge(&mut self, other: Self) {
+        let recorded = self
+            .reads
+            .iter()
+            .map(|read| read.item)
+            .collect::<HashSet<_>>();
+        self.reads.extend(
+            other
+                .reads
+                .into_iter()
+                .filter(|read| !recorded.contains(&read.item)),
+        );
+    }
+}
+
+/// Collects the state reads of one simulation until it is saved.
+#[derive(Debug, Clone)]
+pub(crate) struct ConfluxSnapshotRecorder {
+    store: ConfluxSnapshotStore,
+    state_anchor: ConfluxStateAnchor,
+    reads: Arc<Mutex<HashMap<StateItem, Option<Bytes>>>>,
+}
+
+impl ConfluxSnapshotRecorder {
+    pub(crate) fn new(store: ConfluxSnapshotStore, state_anchor: ConfluxStateAnchor) -> Self {
+        Self {
+            store,
+            state_anchor,
+            reads: Arc::default(),
+        }
+    }
+
+    pub(crate) fn record(&self, item: StateItem, value: Option<&RawStateValue>) {
+        self.lock()
+            .insert(item, value.map(|value| Bytes::copy_from_slice(value)));
+    }
+
+    pub(crate) fn save(&self) -> Result<(), ConfluxSimulationError> {
+        let reads = self
+            .lock()
+            .iter()
+            .map(|(item, value)| ConfluxSnapshotRead {
+                item: *item,
+                value: value.clone(),
+            })
+            .collect();
+        let snapshot = ConfluxStateSnapshot {
+            epoch_number: self.state_anchor.epoch_number(),
+            pivot_hash: self.state_anchor.pivot_hash(),
+            reads,
+        };
+
+        self.store
+            .save(snapshot)
+            .map_err(|error| ConfluxSimulationError::StateAccess {
+                message: format!("failed to save state snapshot: {error}"),
+            })
+    }
+
+    fn lock(&self) -> MutexGuard<'_, HashMap<StateItem, Option<Bytes>>> {
+        self.reads.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
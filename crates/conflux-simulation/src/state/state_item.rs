@@ -8,12 +8,14 @@ use cfx_statedb::global_params::{
 };
 use cfx_types::{Address, H256, Space};
 use primitives::{StorageKey, StorageKeyWithSpace};
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
 const ADDRESS_BYTES: usize = StorageKeyWithSpace::ACCOUNT_BYTES;
 const HASH_BYTES: usize = 32;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub(crate) enum StateItem {
     CoreSpace(CoreSpaceStateItem),
     Espace(EspaceStateItem),
@@ -30,7 +32,8 @@ impl StateItem {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub(crate) enum CoreSpaceStateItem {
     TotalIssued,
     TotalStaking,
@@ -54,7 +57,8 @@ pub(crate) enum CoreSpaceStateItem {
     Code { address: Address, code_hash: H256 },
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub(crate) enum EspaceStateItem {
     Account { address: Address },
     StorageSlot { address: Address, slot: H256 },
@@ -3,6 +3,7 @@ use std::sync::Arc;
 use cfx_types::{Address, H256, U256, address_util::AddressUtil};
 use keccak_hash::{KECCAK_EMPTY, keccak};
 
+use rlp::Rlp;
 use thiserror::Error;
 
 use cfx_parameters::staking::DRIPS_PER_STORAGE_COLLATERAL_UNIT;
@@ -14,6 +15,8 @@ use primitives::{
 
 use crate::state::rpc_types::CoreSpaceSponsorInfo;
 
+const BASIC_ACCOUNT_FIELDS: usize = 5;
+
 #[derive(Debug, Clone, PartialEq, Eq, Error)]
 pub(crate) enum StateValueEncodingError {
     #[error("code hash mismatch: expected {expected:?}, got {actual:?}")]
@@ -25,6 +28,9 @@ pub(crate) enum StateValueEncodingError {
     #[error("basic Core Space account has storage-point collateral {value}")]
     BasicAccountStoragePointCollateral { value: U256 },
 
+    #[error("invalid Core Space account value: {reason}")]
+    InvalidAccount { reason: String },
+
     #[error("available storage-point units {units} overflow when converted to collateral in drips")]
     AvailableStoragePointCollateralOverflow { units: U256 },
 }
@@ -88,32 +94,91 @@ pub(crate) fn encode_core_space_contract_account(
     let sponsor_info =
         core_space_sponsor_info_from_rpc(sponsor_info, used_storage_point_collateral)?;
 
-    if balance.is_zero()
-        && nonce.is_zero()
-        && code_hash == KECCAK_EMPTY
-        && staking_balance.is_zero()
-        && token_collateral_for_storage.is_zero()
-        && accumulated_interest_return.is_zero()
-        && admin.is_zero()
-        && sponsor_info == SponsorInfo::default()
+    Ok(encode_contract_account(ContractAccount {
+        balance,
+        nonce,
+        code_hash,
+        staking_balance,
+        collateral_for_storage: token_collateral_for_storage,
+        accumulated_interest_return,
+        admin,
+        sponsor_info,
+    }))
+}
+
+/// Encodes an account in the layout its address and code hash call for.
+pub(crate) fn encode_core_space_account(
+    address: Address,
+    account: ContractAccount,
+) -> Option<Box<[u8]>> {
+    if should_encode_core_space_contract_account(address, account.code_hash) {
+        return encode_contract_account(account);
+    }
+
+    encode_core_space_basic_account(
+        account.balance,
+        account.nonce,
+        account.staking_balance,
+        account.collateral_for_storage,
+        account.accumulated_interest_return,
+    )
+}
+
+/// Decodes either account layout into the contract layout, which holds every
+/// basic account field. An absent account decodes as empty.
+pub(crate) fn decode_core_space_account(
+    value: Option<&[u8]>,
+) -> Result<ContractAccount, StateValueEncodingError> {
+    let Some(value) = value else {
+        return Ok(empty_contract_account());
+    };
+    let rlp = Rlp::new(value);
+    let invalid = |error: rlp::DecoderError| StateValueEncodingError::InvalidAccount {
+        reason: error.to_string(),
+    };
+
+    if rlp.item_count().map_err(invalid)? == BASIC_ACCOUNT_FIELDS {
+        let account: BasicAccount = rlp.as_val().map_err(invalid)?;
+        return Ok(ContractAccount {
+            balance: account.balance,
+            nonce: account.nonce,
+            staking_balance: account.staking_balance,
+            collateral_for_storage: account.collateral_for_storage,
+            accumulated_interest_return: account.accumulated_interest_return,
+            ..empty_contract_account()
+        });
+    }
+
+    rlp.as_val().map_err(invalid)
+}
+
+fn empty_contract_account() -> ContractAccount {
+    ContractAccount {
+        balance: U256::zero(),
+        nonce: U256::zero(),
+        code_hash: KECCAK_EMPTY,
+        staking_balance: U256::zero(),
+        collateral_for_storage: U256::zero(),
+        accumulated_interest_return: U256::zero(),
+        admin: Address::zero(),
+        sponsor_info: SponsorInfo::default(),
+    }
+}
+
+fn encode_contract_account(account: ContractAccount) -> Option<Box<[u8]>> {
+    if account.balance.is_zero()
+        && account.nonce.is_zero()
+        && account.code_hash == KECCAK_EMPTY
+        && account.staking_balance.is_zero()
+        && account.collateral_for_storage.is_zero()
+        && account.accumulated_interest_return.is_zero()
+        && account.admin.is_zero()
+        && account.sponsor_info == SponsorInfo::default()
     {
-        return Ok(None);
+        return None;
     }
 
-    Ok(Some(
-        rlp::encode(&ContractAccount {
-            balance,
-            nonce,
-            code_hash,
-            staking_balance,
-            collateral_for_storage: token_collateral_for_storage,
-            accumulated_interest_return,
-            admin,
-            sponsor_info,
-        })
-        .to_vec()
-        .into_boxed_slice(),
-    ))
+    Some(rlp::encode(&account).to_vec().into_boxed_slice())
 }
 
 pub(crate) fn should_encode_core_space_contract_account(address: Address, code_hash: H256) -> bool {
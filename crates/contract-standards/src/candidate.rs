@@ -117,20 +117,57 @@ pub(crate) enum StandardCandidateKind {
         operator: Address,
         approved: bool,
     },
+    Permit2Allowance {
+        permit2: Address,
+        owner: Address,
+        token: Address,
+        spender: Address,
+        source: Permit2AllowanceSource,
+    },
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub(crate) enum AllowanceSource {
-    ApprovalEvent { value: U256 },
-    TransferFromCall { amount: U256 },
+    ApprovalEvent {
+        value: U256,
+    },
+    TransferFromCall {
+        amount: U256,
+    },
+    /// An EIP-2612 `permit` call that set the allowance from a signature.
+    PermitCall {
+        value: U256,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Permit2AllowanceSource {
+    ApprovalEvent {
+        amount: U256,
+        expiration: u64,
+    },
+    PermitEvent {
+        amount: U256,
+        expiration: u64,
+        nonce: u64,
+    },
+    LockdownEvent,
 }
 
 const TRANSFER_FROM_INPUT_LEN: usize = 100;
+// permit(address,address,uint256,uint256,uint8,bytes32,bytes32) is a 4-byte
+// selector plus seven ABI words. Owner, spender and value lead the prefix.
+const PERMIT_INPUT_LEN: usize = 228;
+const PERMIT_PREFIX_LEN: usize = 100;
 
 static TRANSFER_FROM_SELECTOR: LazyLock<[u8; 4]> = LazyLock::new(|| {
     let hash = keccak256("transferFrom(address,address,uint256)");
     [hash[0], hash[1], hash[2], hash[3]]
 });
+static PERMIT_SELECTOR: LazyLock<[u8; 4]> = LazyLock::new(|| {
+    let hash = keccak256("permit(address,address,uint256,uint256,uint8,bytes32,bytes32)");
+    [hash[0], hash[1], hash[2], hash[3]]
+});
 static DEPOSIT_TOPIC0: LazyLock<B256> = LazyLock::new(|| keccak256("Deposit(address,uint256)"));
 static WITHDRAWAL_TOPIC0: LazyLock<B256> =
     LazyLock::new(|| keccak256("Withdrawal(address,uint256)"));
@@ -158,11 +195,12 @@ enum WrappedNativeEvent {
 pub fn collect_candidates(
     records: &[Record],
 ) -> Result<Vec<StandardCandidate>, ContractStandardsError> {
-    let (decoded_events, erc20_transfer_tokens) = decode_records(records)?;
+    let decoded = decode_records(records)?;
     let mut candidates = Vec::new();
 
-    for (item, decoded_event) in records.iter().zip(decoded_events) {
-        append_transfer_from_candidate(item, &erc20_transfer_tokens, &mut candidates);
+    for (item, decoded_event) in records.iter().zip(decoded.events) {
+        append_transfer_from_candidate(item, &decoded.erc20_transfer_tokens, &mut candidates);
+        append_permit_candidate(item, &decoded.erc20_approval_tokens, &mut candidates);
 
         if let Some(event) = decoded_event {
             append_event_candidates(item.position(), event, &mut candidates);
@@ -316,11 +354,16 @@ fn decode_wrapped_native_fields(topics: &[B256], data: &[u8]) -> Option<(Address
     Some((Address::from_word(*topic), U256::from_be_slice(data)))
 }
 
-fn decode_records(
-    records: &[Record],
-) -> Result<(Vec<Option<DecodedEvent>>, HashSet<Address>), ContractStandardsError> {
+struct DecodedRecords {
+    events: Vec<Option<DecodedEvent>>,
+    erc20_transfer_tokens: HashSet<Address>,
+    erc20_approval_tokens: HashSet<Address>,
+}
+
+fn decode_records(records: &[Record]) -> Result<DecodedRecords, ContractStandardsError> {
     let mut decoded_events = Vec::with_capacity(records.len());
     let mut erc20_transfer_tokens = HashSet::new();
+    let mut erc20_approval_tokens = HashSet::new();
 
     for item in records {
         let decoded_event =
@@ -329,14 +372,24 @@ fn decode_records(
                 source,
             })?;
 
-        if let Some(DecodedEvent::Erc20Transfer { token, .. }) = &decoded_event {
-            erc20_transfer_tokens.insert(*token);
+        match &decoded_event {
+            Some(DecodedEvent::Erc20Transfer { token, .. }) => {
+                erc20_transfer_tokens.insert(*token);
+            }
+            Some(DecodedEvent::Erc20Approval { token, .. }) => {
+                erc20_approval_tokens.insert(*token);
+            }
+            _ => {}
         }
 
         decoded_events.push(decoded_event);
     }
 
-    Ok((decoded_events, erc20_transfer_tokens))
+    Ok(DecodedRecords {
+        events: decoded_events,
+        erc20_transfer_tokens,
+        erc20_approval_tokens,
+    })
 }
 
 fn append_transfer_from_candidate(
@@ -375,6 +428,44 @@ fn append_transfer_from_candidate(
     });
 }
 
+/// EIP-2612 `permit` calls are only trusted on tokens that also emitted an
+/// ERC-20 `Approval` in the transaction, as compliant implementations do.
+fn append_permit_candidate(
+    item: &Record,
+    erc20_approval_tokens: &HashSet<Address>,
+    candidates: &mut Vec<StandardCandidate>,
+) {
+    let Record::Call {
+        position,
+        target,
+        input_len,
+        input_prefix,
+        ..
+    } = item
+    else {
+        return;
+    };
+
+    if !erc20_approval_tokens.contains(target) {
+        return;
+    }
+
+    let Some((owner, spender, value)) = decode_permit_call(*input_len, input_prefix.as_ref())
+    else {
+        return;
+    };
+
+    candidates.push(StandardCandidate {
+        position: *position,
+        kind: StandardCandidateKind::Erc20Allowance {
+            token: *target,
+            owner,
+            spender,
+            source: AllowanceSource::PermitCall { value },
+        },
+    });
+}
+
 fn append_event_candidates(
     position: Position,
     event: DecodedEvent,
@@ -493,6 +584,60 @@ fn append_event_candidates(
                 approved,
             },
         ),
+        DecodedEvent::Permit2Approval {
+            permit2,
+            owner,
+            token,
+            spender,
+            amount,
+            expiration,
+        } => push(
+            0,
+            StandardCandidateKind::Permit2Allowance {
+                permit2,
+                owner,
+                token,
+                spender,
+                source: Permit2AllowanceSource::ApprovalEvent { amount, expiration },
+            },
+        ),
+        DecodedEvent::Permit2Permit {
+            permit2,
+            owner,
+            token,
+            spender,
+            amount,
+            expiration,
+            nonce,
+        } => push(
+            0,
+            StandardCandidateKind::Permit2Allowance {
+                permit2,
+                owner,
+                token,
+                spender,
+                source: Permit2AllowanceSource::PermitEvent {
+                    amount,
+                    expiration,
+                    nonce,
+                },
+            },
+        ),
+        DecodedEvent::Permit2Lockdown {
+            permit2,
+            owner,
+            token,
+            spender,
+        } => push(
+            0,
+            StandardCandidateKind::Permit2Allowance {
+                permit2,
+                owner,
+                token,
+                spender,
+                source: Permit2AllowanceSource::LockdownEvent,
+            },
+        ),
     }
 }
 
@@ -515,6 +660,21 @@ fn decode_transfer_from_call(input_len: usize, input_prefix: &[u8]) -> Option<(A
     Some((owner, amount))
 }
 
+fn decode_permit_call(input_len: usize, input_prefix: &[u8]) -> Option<(Address, Address, U256)> {
+    if input_len != PERMIT_INPUT_LEN
+        || input_prefix.len() < PERMIT_PREFIX_LEN
+        || !input_prefix.starts_with(&*PERMIT_SELECTOR)
+    {
+        return None;
+    }
+
+    let owner = calldata_address(&input_prefix[4..36])?;
+    let spender = calldata_address(&input_prefix[36..68])?;
+    let value = U256::from_be_slice(&input_prefix[68..100]);
+
+    Some((owner, spender, value))
+}
+
 fn calldata_address(word: &[u8]) -> Option<Address> {
     if word.len() != 32 || word[..12].iter().any(|byte| *byte != 0) {
         return None;
@@ -62,6 +62,29 @@ pub enum StandardChange {
         raw_amount_before: U256,
         raw_amount_after: U256,
     },
+    /// An ERC-20 allowance set by an EIP-2612 `permit` signature.
+    Erc20Permit {
+        contract_address: Address,
+        owner: Address,
+        spender: Address,
+        raw_amount_before: U256,
+        raw_amount_after: U256,
+        nonce_before: U256,
+        nonce_after: U256,
+    },
+    /// An allowance held by a Permit2 contract for `token`.
+    Permit2Allowance {
+        contract_address: Address,
+        token: Address,
+        owner: Address,
+        spender: Address,
+        raw_amount_before: U256,
+        raw_amount_after: U256,
+        expiration_before: u64,
+        expiration_after: u64,
+        nonce_before: u64,
+        nonce_after: u64,
+    },
     Erc721TokenApproval {
         contract_address: Address,
         token_id: U256,
@@ -5,9 +5,9 @@ use std::collections::{HashMap, hash_map::Entry};
 use alloy_primitives::{Address, U256};
 
 use crate::{
-    ContractStandardsError, Erc20AllowanceKey, Erc20BalanceKey, Position, PositionedStandardChange,
-    StandardCandidate, StandardCandidateKind, StandardChange, StandardStateValues,
-    StateArithmeticOperation, StatePhase, StateRequirement, StateRequirements,
+    ContractStandardsError, Erc20AllowanceKey, Erc20BalanceKey, Erc20PermitNonceKey, Position,
+    PositionedStandardChange, StandardCandidate, StandardCandidateKind, StandardChange,
+    StandardStateValues, StateArithmeticOperation, StatePhase, StateRequirement, StateRequirements,
     candidate::AllowanceSource,
 };
 
@@ -20,6 +20,8 @@ struct Erc20Replay {
 struct PositionedAllowance {
     position: Position,
     source: AllowanceSource,
+    /// The first EIP-2612 permit that set the allowance, if any.
+    permit_position: Option<Position>,
 }
 
 pub(crate) fn check_erc20_changes(
@@ -110,7 +112,9 @@ pub(crate) fn check_erc20_allowances(
         let after_allowance = allowance_value(after, key, StatePhase::After)?;
 
         match allowance.source {
-            AllowanceSource::ApprovalEvent { value } if value != after_allowance => {
+            AllowanceSource::ApprovalEvent { value } | AllowanceSource::PermitCall { value }
+                if value != after_allowance =>
+            {
                 return Err(ContractStandardsError::Erc20ApprovalValueMismatch {
                     token: key.token,
                     owner: key.owner,
@@ -119,10 +123,42 @@ pub(crate) fn check_erc20_allowances(
                     after_allowance,
                 });
             }
-            AllowanceSource::ApprovalEvent { .. } | AllowanceSource::TransferFromCall { .. } => {}
+            AllowanceSource::ApprovalEvent { .. }
+            | AllowanceSource::PermitCall { .. }
+            | AllowanceSource::TransferFromCall { .. } => {}
         }
 
-        if before_allowance != after_allowance {
+        // A permit is reported even when it re-grants the same amount: the
+        // owner's signature was still spent.
+        if let Some(permit_position) = allowance.permit_position {
+            let nonce_key = Erc20PermitNonceKey {
+                token: key.token,
+                owner: key.owner,
+            };
+            let nonce_before = permit_nonce_value(before, nonce_key, StatePhase::Before)?;
+            let nonce_after = permit_nonce_value(after, nonce_key, StatePhase::After)?;
+
+            if nonce_before == nonce_after {
+                return Err(ContractStandardsError::Erc20PermitNonceUnchanged {
+                    token: key.token,
+                    owner: key.owner,
+                    nonce: nonce_before,
+                });
+            }
+
+            changes.push(PositionedStandardChange::new(
+                permit_position,
+                StandardChange::Erc20Permit {
+                    contract_address: key.token,
+                    owner: key.owner,
+                    spender: key.spender,
+                    raw_amount_before: before_allowance,
+                    raw_amount_after: after_allowance,
+                    nonce_before,
+                    nonce_after,
+                },
+            ));
+        } else if before_allowance != after_allowance {
             changes.push(PositionedStandardChange::new(
                 allowance.position,
                 StandardChange::Erc20Allowance {
@@ -154,6 +190,21 @@ fn allowance_value(
         })
 }
 
+fn permit_nonce_value(
+    values: &StandardStateValues,
+    key: Erc20PermitNonceKey,
+    phase: StatePhase,
+) -> Result<U256, ContractStandardsError> {
+    values
+        .erc20_permit_nonces
+        .get(&key)
+        .copied()
+        .ok_or(ContractStandardsError::StateValueMissing {
+            requirement: StateRequirement::Erc20PermitNonce(key),
+            phase,
+        })
+}
+
 fn collect_allowances(
     candidates: &[StandardCandidate],
 ) -> Vec<(Erc20AllowanceKey, PositionedAllowance)> {
@@ -176,16 +227,26 @@ fn collect_allowances(
             owner,
             spender,
         };
-        let positioned = PositionedAllowance {
-            position: candidate.position,
-            source,
-        };
+        let permit_position =
+            matches!(source, AllowanceSource::PermitCall { .. }).then_some(candidate.position);
 
         match allowance_indexes.entry(key) {
-            Entry::Occupied(entry) => allowances[*entry.get()].1 = positioned,
+            Entry::Occupied(entry) => {
+                let allowance = &mut allowances[*entry.get()].1;
+                allowance.position = candidate.position;
+                allowance.source = source;
+                allowance.permit_position = allowance.permit_position.or(permit_position);
+            }
             Entry::Vacant(entry) => {
                 entry.insert(allowances.len());
-                allowances.push((key, positioned));
+                allowances.push((
+                    key,
+                    PositionedAllowance {
+                        position: candidate.position,
+                        source,
+                        permit_position,
+                    },
+                ));
             }
         }
     }
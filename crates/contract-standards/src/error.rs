@@ -2,8 +2,8 @@ use alloy_primitives::{Address, B256, U256};
 use thiserror::Error;
 
 use crate::{
-    CollectionStandards, EventCodecError, Position, StateArithmeticOperation, StatePhase,
-    StateRequirement,
+    CollectionStandards, EventCodecError, Permit2Allowance, Position, StateArithmeticOperation,
+    StatePhase, StateRequirement, SupportedEvent,
 };
 
 #[derive(Debug, Clone, PartialEq, Eq, Error)]
@@ -68,6 +68,29 @@ pub enum ContractStandardsError {
         after_allowance: U256,
     },
 
+    #[error(
+        "ERC-2612 permit for owner {owner} in token {token} did not consume a nonce: \
+         nonce {nonce} before and after"
+    )]
+    Erc20PermitNonceUnchanged {
+        token: Address,
+        owner: Address,
+        nonce: U256,
+    },
+
+    #[error(
+        "Permit2 allowance for owner {owner}, token {token} and spender {spender} in {permit2} \
+         does not match its {event} event: after state {after_allowance:?}"
+    )]
+    Permit2AllowanceMismatch {
+        permit2: Address,
+        owner: Address,
+        token: Address,
+        spender: Address,
+        event: SupportedEvent,
+        after_allowance: Permit2Allowance,
+    },
+
     #[error(
         "ERC-721 movement for token {token_id} in collection {collection} from {from} to {to} \
          is invalid for current owner {current_owner:?}"
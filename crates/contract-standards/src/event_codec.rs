@@ -16,6 +16,16 @@ static TRANSFER_SINGLE_TOPIC0: LazyLock<B256> =
     LazyLock::new(|| keccak256("TransferSingle(address,address,address,uint256,uint256)"));
 static TRANSFER_BATCH_TOPIC0: LazyLock<B256> =
     LazyLock::new(|| keccak256("TransferBatch(address,address,address,uint256[],uint256[])"));
+static PERMIT2_APPROVAL_TOPIC0: LazyLock<B256> =
+    LazyLock::new(|| keccak256("Approval(address,address,address,uint160,uint48)"));
+static PERMIT2_PERMIT_TOPIC0: LazyLock<B256> =
+    LazyLock::new(|| keccak256("Permit(address,address,address,uint160,uint48,uint48)"));
+static PERMIT2_LOCKDOWN_TOPIC0: LazyLock<B256> =
+    LazyLock::new(|| keccak256("Lockdown(address,address,address)"));
+
+const UINT160_BITS: usize = 160;
+const UINT48_BITS: usize = 48;
+
 pub(super) fn decode_event(record: &Record) -> Result<Option<DecodedEvent>, EventCodecError> {
     let Record::Log {
         address,
@@ -41,6 +51,12 @@ pub(super) fn decode_event(record: &Record) -> Result<Option<DecodedEvent>, Even
         decode_transfer_single_event(*address, topics, data)?
     } else if *topic0 == *TRANSFER_BATCH_TOPIC0 {
         decode_transfer_batch_event(*address, topics, data)?
+    } else if *topic0 == *PERMIT2_APPROVAL_TOPIC0 {
+        decode_permit2_approval_event(*address, topics, data)?
+    } else if *topic0 == *PERMIT2_PERMIT_TOPIC0 {
+        decode_permit2_permit_event(*address, topics, data)?
+    } else if *topic0 == *PERMIT2_LOCKDOWN_TOPIC0 {
+        decode_permit2_lockdown_event(*address, topics, data)?
     } else {
         return Ok(None);
     };
@@ -93,6 +109,29 @@ pub(crate) enum DecodedEvent {
         to: Address,
         items: Vec<Erc1155TransferItem>,
     },
+    Permit2Approval {
+        permit2: Address,
+        owner: Address,
+        token: Address,
+        spender: Address,
+        amount: U256,
+        expiration: u64,
+    },
+    Permit2Permit {
+        permit2: Address,
+        owner: Address,
+        token: Address,
+        spender: Address,
+        amount: U256,
+        expiration: u64,
+        nonce: u64,
+    },
+    Permit2Lockdown {
+        permit2: Address,
+        owner: Address,
+        token: Address,
+        spender: Address,
+    },
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -108,6 +147,9 @@ pub enum SupportedEvent {
     ApprovalForAll,
     TransferSingle,
     TransferBatch,
+    Permit2Approval,
+    Permit2Permit,
+    Permit2Lockdown,
 }
 
 impl fmt::Display for SupportedEvent {
@@ -118,6 +160,9 @@ impl fmt::Display for SupportedEvent {
             Self::ApprovalForAll => "ApprovalForAll",
             Self::TransferSingle => "TransferSingle",
             Self::TransferBatch => "TransferBatch",
+            Self::Permit2Approval => "Permit2 Approval",
+            Self::Permit2Permit => "Permit2 Permit",
+            Self::Permit2Lockdown => "Permit2 Lockdown",
         })
     }
 }
@@ -307,3 +352,98 @@ fn decode_transfer_batch_event(
         items,
     })
 }
+
+fn decode_permit2_approval_event(
+    contract_address: Address,
+    topics: &[B256],
+    data: &[u8],
+) -> Result<DecodedEvent, EventCodecError> {
+    let event = SupportedEvent::Permit2Approval;
+
+    if topics.len() != 4 {
+        return Err(EventCodecError::malformed(event, "expected 4 topics"));
+    }
+
+    let (amount, expiration) =
+        <(U256, U256)>::abi_decode_sequence_validate(data).map_err(|_| {
+            EventCodecError::malformed(event, "data is not a canonical (uint160,uint48) tuple")
+        })?;
+
+    Ok(DecodedEvent::Permit2Approval {
+        permit2: contract_address,
+        owner: indexed_address(&topics[1], event)?,
+        token: indexed_address(&topics[2], event)?,
+        spender: indexed_address(&topics[3], event)?,
+        amount: bounded_uint(amount, UINT160_BITS, event)?,
+        expiration: uint48(expiration, event)?,
+    })
+}
+
+fn decode_permit2_permit_event(
+    contract_address: Address,
+    topics: &[B256],
+    data: &[u8],
+) -> Result<DecodedEvent, EventCodecError> {
+    let event = SupportedEvent::Permit2Permit;
+
+    if topics.len() != 4 {
+        return Err(EventCodecError::malformed(event, "expected 4 topics"));
+    }
+
+    let (amount, expiration, nonce) = <(U256, U256, U256)>::abi_decode_sequence_validate(data)
+        .map_err(|_| {
+            EventCodecError::malformed(
+                event,
+                "data is not a canonical (uint160,uint48,uint48) tuple",
+            )
+        })?;
+
+    Ok(DecodedEvent::Permit2Permit {
+        permit2: contract_address,
+        owner: indexed_address(&topics[1], event)?,
+        token: indexed_address(&topics[2], event)?,
+        spender: indexed_address(&topics[3], event)?,
+        amount: bounded_uint(amount, UINT160_BITS, event)?,
+        expiration: uint48(expiration, event)?,
+        nonce: uint48(nonce, event)?,
+    })
+}
+
+fn decode_permit2_lockdown_event(
+    contract_address: Address,
+    topics: &[B256],
+    data: &[u8],
+) -> Result<DecodedEvent, EventCodecError> {
+    let event = SupportedEvent::Permit2Lockdown;
+
+    if topics.len() != 2 {
+        return Err(EventCodecError::malformed(event, "expected 2 topics"));
+    }
+
+    let (token, spender) =
+        <(Address, Address)>::abi_decode_sequence_validate(data).map_err(|_| {
+            EventCodecError::malformed(event, "data is not a canonical (address,address) tuple")
+        })?;
+
+    Ok(DecodedEvent::Permit2Lockdown {
+        permit2: contract_address,
+        owner: indexed_address(&topics[1], event)?,
+        token,
+        spender,
+    })
+}
+
+fn bounded_uint(value: U256, bits: usize, event: SupportedEvent) -> Result<U256, EventCodecError> {
+    if value.bit_len() > bits {
+        return Err(EventCodecError::malformed(
+            event,
+            "value exceeds its declared integer width",
+        ));
+    }
+
+    Ok(value)
+}
+
+fn uint48(value: U256, event: SupportedEvent) -> Result<u64, EventCodecError> {
+    Ok(bounded_uint(value, UINT48_BITS, event)?.to::<u64>())
+}
@@ -9,6 +9,8 @@ mod error;
 mod event_codec;
 mod metadata;
 mod operator_approval;
+mod permit2;
+mod proxy;
 mod state;
 mod state_codec;
 mod token_contract;
@@ -25,14 +27,21 @@ pub use metadata::{
     StandardMetadata, decimals_call, decode_decimals, decode_name, decode_supports_interface,
     decode_symbol, name_call, supports_interface_call, symbol_call,
 };
+pub use permit2::PERMIT2_UNLIMITED_AMOUNT;
+pub use proxy::{
+    EIP1967_ADMIN_SLOT, EIP1967_BEACON_SLOT, EIP1967_IMPLEMENTATION_SLOT, ProxySlot,
+    proxy_slot_address,
+};
 pub use state::{
-    CollectionStandards, Erc20AllowanceKey, Erc20BalanceKey, Erc721TokenKey, Erc721TokenState,
-    Erc1155BalanceKey, OperatorApprovalKey, StandardStateValues, StateArithmeticOperation,
+    CollectionStandards, Erc20AllowanceKey, Erc20BalanceKey, Erc20PermitNonceKey, Erc721TokenKey,
+    Erc721TokenState, Erc1155BalanceKey, OperatorApprovalKey, Permit2Allowance,
+    Permit2AllowanceKey, ProxyImplementation, StandardStateValues, StateArithmeticOperation,
     StatePhase, StateRequirement, StateRequirements, state_requirements,
 };
 pub use state_codec::{
-    Erc20AllowanceCall, Erc20BalanceCall, Erc20TotalSupplyCall, Erc721GetApprovedCall,
-    Erc721OwnerCall, Erc1155BalanceCall, OperatorApprovalCall, SupportsInterfaceCall,
+    BeaconImplementationCall, Erc20AllowanceCall, Erc20BalanceCall, Erc20PermitNonceCall,
+    Erc20TotalSupplyCall, Erc721GetApprovedCall, Erc721OwnerCall, Erc1155BalanceCall,
+    OperatorApprovalCall, Permit2AllowanceCall, SupportsInterfaceCall,
 };
 
 pub const ERC165_INTERFACE_ID: [u8; 4] = [0x01, 0xff, 0xc9, 0xa7];
@@ -60,6 +69,9 @@ pub fn verify(
     changes.extend(operator_approval::check_operator_approvals(
         candidates, before, after,
     )?);
+    changes.extend(permit2::check_permit2_allowances(
+        candidates, before, after,
+    )?);
 
     Ok(changes)
 }
@@ -53,6 +53,13 @@ impl MetadataRequests {
                 }
                 | StandardChange::Erc20Allowance {
                     contract_address, ..
+                }
+                | StandardChange::Erc20Permit {
+                    contract_address, ..
+                }
+                | StandardChange::Permit2Allowance {
+                    token: contract_address,
+                    ..
                 } => {
                     if seen_erc20.insert(*contract_address) {
                         requests.erc20_contracts.push(*contract_address);
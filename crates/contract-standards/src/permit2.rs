@@ -0,0 +1,157 @@
+//! Permit2 allowance state checks.
+
+use std::collections::{HashMap, hash_map::Entry};
+
+use alloy_primitives::U256;
+
+use crate::{
+    ContractStandardsError, Permit2Allowance, Permit2AllowanceKey, Position,
+    PositionedStandardChange, StandardCandidate, StandardCandidateKind, StandardChange,
+    StandardStateValues, StatePhase, StateRequirement, SupportedEvent,
+    candidate::Permit2AllowanceSource,
+};
+
+/// `type(uint160).max`, the amount Permit2 treats as an unlimited allowance.
+pub const PERMIT2_UNLIMITED_AMOUNT: U256 =
+    U256::from_limbs([u64::MAX, u64::MAX, u32::MAX as u64, 0]);
+
+// Permit2 stores the current block timestamp for a zero expiration, which the
+// event does not carry.
+const BLOCK_TIMESTAMP_EXPIRATION: u64 = 0;
+
+#[derive(Debug, Clone, Copy)]
+struct PositionedPermit2Event {
+    position: Position,
+    source: Permit2AllowanceSource,
+}
+
+pub(crate) fn check_permit2_allowances(
+    candidates: &[StandardCandidate],
+    before: &StandardStateValues,
+    after: &StandardStateValues,
+) -> Result<Vec<PositionedStandardChange>, ContractStandardsError> {
+    let last_events = collect_last_permit2_events(candidates);
+    let mut changes = Vec::new();
+
+    for (key, event) in last_events {
+        let allowance_before = permit2_allowance_value(before, key, StatePhase::Before)?;
+        let allowance_after = permit2_allowance_value(after, key, StatePhase::After)?;
+
+        if !event_matches_allowance(event.source, allowance_after) {
+            return Err(ContractStandardsError::Permit2AllowanceMismatch {
+                permit2: key.permit2,
+                owner: key.owner,
+                token: key.token,
+                spender: key.spender,
+                event: supported_event(event.source),
+                after_allowance: allowance_after,
+            });
+        }
+
+        if allowance_before == allowance_after {
+            continue;
+        }
+
+        changes.push(PositionedStandardChange::new(
+            event.position,
+            StandardChange::Permit2Allowance {
+                contract_address: key.permit2,
+                token: key.token,
+                owner: key.owner,
+                spender: key.spender,
+                raw_amount_before: allowance_before.amount,
+                raw_amount_after: allowance_after.amount,
+                expiration_before: allowance_before.expiration,
+                expiration_after: allowance_after.expiration,
+                nonce_before: allowance_before.nonce,
+                nonce_after: allowance_after.nonce,
+            },
+        ));
+    }
+
+    Ok(changes)
+}
+
+/// Later Permit2 transfers spend the amount without an event and nonce
+/// invalidations only raise the nonce, so the after state is checked against
+/// bounds rather than equality.
+fn event_matches_allowance(source: Permit2AllowanceSource, after: Permit2Allowance) -> bool {
+    let expiration_matches =
+        |expiration| expiration == BLOCK_TIMESTAMP_EXPIRATION || after.expiration == expiration;
+
+    match source {
+        Permit2AllowanceSource::ApprovalEvent { amount, expiration } => {
+            after.amount <= amount && expiration_matches(expiration)
+        }
+        Permit2AllowanceSource::PermitEvent {
+            amount,
+            expiration,
+            nonce,
+        } => after.amount <= amount && expiration_matches(expiration) && after.nonce > nonce,
+        Permit2AllowanceSource::LockdownEvent => after.amount.is_zero(),
+    }
+}
+
+const fn supported_event(source: Permit2AllowanceSource) -> SupportedEvent {
+    match source {
+        Permit2AllowanceSource::ApprovalEvent { .. } => SupportedEvent::Permit2Approval,
+        Permit2AllowanceSource::PermitEvent { .. } => SupportedEvent::Permit2Permit,
+        Permit2AllowanceSource::LockdownEvent => SupportedEvent::Permit2Lockdown,
+    }
+}
+
+fn permit2_allowance_value(
+    values: &StandardStateValues,
+    key: Permit2AllowanceKey,
+    phase: StatePhase,
+) -> Result<Permit2Allowance, ContractStandardsError> {
+    values
+        .permit2_allowances
+        .get(&key)
+        .copied()
+        .ok_or(ContractStandardsError::StateValueMissing {
+            requirement: StateRequirement::Permit2Allowance(key),
+            phase,
+        })
+}
+
+fn collect_last_permit2_events(
+    candidates: &[StandardCandidate],
+) -> Vec<(Permit2AllowanceKey, PositionedPermit2Event)> {
+    let mut event_indexes: HashMap<Permit2AllowanceKey, usize> = HashMap::new();
+    let mut events: Vec<(Permit2AllowanceKey, PositionedPermit2Event)> = Vec::new();
+
+    for candidate in candidates {
+        let StandardCandidateKind::Permit2Allowance {
+            permit2,
+            owner,
+            token,
+            spender,
+            source,
+        } = candidate.kind
+        else {
+            continue;
+        };
+
+        let key = Permit2AllowanceKey {
+            permit2,
+            owner,
+            token,
+            spender,
+        };
+        let positioned = PositionedPermit2Event {
+            position: candidate.position,
+            source,
+        };
+
+        match event_indexes.entry(key) {
+            Entry::Occupied(entry) => events[*entry.get()].1 = positioned,
+            Entry::Vacant(entry) => {
+                entry.insert(events.len());
+                events.push((key, positioned));
+            }
+        }
+    }
+
+    events
+}
@@ -0,0 +1,58 @@
+//! EIP-1967 proxy storage slots and the events announcing their changes.
+use std::sync::LazyLock;
+
+use alloy_primitives::{Address, B256, U256, b256, keccak256};
+
+/// `bytes32(uint256(keccak256("eip1967.proxy.implementation")) - 1)`
+pub const EIP1967_IMPLEMENTATION_SLOT: B256 =
+    b256!("0x360894a13ba1a3210667c828492db98dca3e2076cc3735a920a3ca505d382bbc");
+/// `bytes32(uint256(keccak256("eip1967.proxy.admin")) - 1)`
+pub const EIP1967_ADMIN_SLOT: B256 =
+    b256!("0xb53127684a568b3173ae13b9f8a6016e243e63b6e8ee1178d6a717850b5d6103");
+/// `bytes32(uint256(keccak256("eip1967.proxy.beacon")) - 1)`
+pub const EIP1967_BEACON_SLOT: B256 =
+    b256!("0xa3f0ad74e5423aebfd80d3ef4346578335a9a72aeaee59ff6cb3582b35133d50");
+
+static UPGRADED_TOPIC0: LazyLock<B256> = LazyLock::new(|| keccak256("Upgraded(address)"));
+static ADMIN_CHANGED_TOPIC0: LazyLock<B256> =
+    LazyLock::new(|| keccak256("AdminChanged(address,address)"));
+static BEACON_UPGRADED_TOPIC0: LazyLock<B256> =
+    LazyLock::new(|| keccak256("BeaconUpgraded(address)"));
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ProxySlot {
+    Implementation,
+    Admin,
+    Beacon,
+}
+
+impl ProxySlot {
+    pub const ALL: [Self; 3] = [Self::Implementation, Self::Admin, Self::Beacon];
+
+    pub const fn storage_slot(self) -> B256 {
+        match self {
+            Self::Implementation => EIP1967_IMPLEMENTATION_SLOT,
+            Self::Admin => EIP1967_ADMIN_SLOT,
+            Self::Beacon => EIP1967_BEACON_SLOT,
+        }
+    }
+
+    pub fn storage_key(self) -> U256 {
+        U256::from_be_bytes(self.storage_slot().0)
+    }
+
+    /// Returns the topic of the event that proxies emit when they change the
+    /// slot: `Upgraded`, `AdminChanged` or `BeaconUpgraded`.
+    pub fn event_topic(self) -> B256 {
+        match self {
+            Self::Implementation => *UPGRADED_TOPIC0,
+            Self::Admin => *ADMIN_CHANGED_TOPIC0,
+            Self::Beacon => *BEACON_UPGRADED_TOPIC0,
+        }
+    }
+}
+
+/// Decodes the address held in a proxy slot. An empty slot is unset.
+pub fn proxy_slot_address(value: U256) -> Option<Address> {
+    (!value.is_zero()).then(|| Address::from_word(B256::from(value)))
+}
@@ -6,7 +6,7 @@ use std::{
 
 use alloy_primitives::{Address, B256, U256};
 
-use crate::candidate::{StandardCandidate, StandardCandidateKind};
+use crate::candidate::{AllowanceSource, StandardCandidate, StandardCandidateKind};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct Erc20BalanceKey {
@@ -21,6 +21,20 @@ pub struct Erc20AllowanceKey {
     pub spender: Address,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Erc20PermitNonceKey {
+    pub token: Address,
+    pub owner: Address,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Permit2AllowanceKey {
+    pub permit2: Address,
+    pub owner: Address,
+    pub token: Address,
+    pub spender: Address,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct Erc721TokenKey {
     pub collection: Address,
@@ -48,6 +62,8 @@ pub enum StateRequirement {
     Erc20Balance(Erc20BalanceKey),
     Erc20TotalSupply(Address),
     Erc20Allowance(Erc20AllowanceKey),
+    Erc20PermitNonce(Erc20PermitNonceKey),
+    Permit2Allowance(Permit2AllowanceKey),
     Erc721Token(Erc721TokenKey),
     Erc1155Balance(Erc1155BalanceKey),
     OperatorApproval(OperatorApprovalKey),
@@ -89,6 +105,16 @@ impl fmt::Display for StateRequirement {
                 "ERC-20 allowance for owner {} and spender {} in token {}",
                 key.owner, key.spender, key.token
             ),
+            Self::Erc20PermitNonce(key) => write!(
+                formatter,
+                "ERC-2612 permit nonce for owner {} in token {}",
+                key.owner, key.token
+            ),
+            Self::Permit2Allowance(key) => write!(
+                formatter,
+                "Permit2 allowance for owner {}, token {} and spender {} in {}",
+                key.owner, key.token, key.spender, key.permit2
+            ),
             Self::Erc721Token(key) => write!(
                 formatter,
                 "ERC-721 state for token {} in collection {}",
@@ -133,6 +159,8 @@ pub struct StateRequirements {
     pub erc20_balances: Vec<Erc20BalanceKey>,
     pub erc20_total_supplies: Vec<Address>,
     pub erc20_allowances: Vec<Erc20AllowanceKey>,
+    pub erc20_permit_nonces: Vec<Erc20PermitNonceKey>,
+    pub permit2_allowances: Vec<Permit2AllowanceKey>,
     pub erc721_tokens: Vec<Erc721TokenKey>,
     pub erc1155_balances: Vec<Erc1155BalanceKey>,
     pub operator_approvals: Vec<OperatorApprovalKey>,
@@ -153,13 +181,34 @@ pub enum Erc721TokenState {
     OwnerOfReverted,
 }
 
+/// The allowance a Permit2 contract returns from `allowance(owner, token,
+/// spender)`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Permit2Allowance {
+    pub amount: U256,
+    pub expiration: u64,
+    pub nonce: u64,
+}
+
+/// The contract an EIP-1967 proxy delegates to, resolved through its beacon
+/// when it has one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProxyImplementation {
+    pub address: Address,
+    pub code_hash: B256,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Default)]
 pub struct StandardStateValues {
     pub contract_code_hashes: HashMap<Address, B256>,
+    /// Implementations of the token contracts that are proxies.
+    pub proxy_implementations: HashMap<Address, ProxyImplementation>,
     pub collection_standards: HashMap<Address, CollectionStandards>,
     pub erc20_balances: HashMap<Erc20BalanceKey, U256>,
     pub erc20_total_supplies: HashMap<Address, U256>,
     pub erc20_allowances: HashMap<Erc20AllowanceKey, U256>,
+    pub erc20_permit_nonces: HashMap<Erc20PermitNonceKey, U256>,
+    pub permit2_allowances: HashMap<Permit2AllowanceKey, Permit2Allowance>,
     pub erc721_tokens: HashMap<Erc721TokenKey, Erc721TokenState>,
     pub erc1155_balances: HashMap<Erc1155BalanceKey, U256>,
     pub operator_approvals: HashMap<OperatorApprovalKey, bool>,
@@ -204,7 +253,7 @@ pub fn state_requirements(candidates: &[StandardCandidate]) -> StateRequirements
                 token,
                 owner,
                 spender,
-                ..
+                source,
             } => {
                 keys.token_contracts.push(token);
                 keys.erc20_allowances.push(Erc20AllowanceKey {
@@ -212,6 +261,26 @@ pub fn state_requirements(candidates: &[StandardCandidate]) -> StateRequirements
                     owner,
                     spender,
                 });
+
+                if matches!(source, AllowanceSource::PermitCall { .. }) {
+                    keys.erc20_permit_nonces
+                        .push(Erc20PermitNonceKey { token, owner });
+                }
+            }
+
+            StandardCandidateKind::Permit2Allowance {
+                permit2,
+                owner,
+                token,
+                spender,
+                ..
+            } => {
+                keys.permit2_allowances.push(Permit2AllowanceKey {
+                    permit2,
+                    owner,
+                    token,
+                    spender,
+                });
             }
 
             StandardCandidateKind::Erc721Transfer {
@@ -281,6 +350,8 @@ pub fn state_requirements(candidates: &[StandardCandidate]) -> StateRequirements
     retain_unique(&mut keys.erc20_balances);
     retain_unique(&mut keys.erc20_total_supplies);
     retain_unique(&mut keys.erc20_allowances);
+    retain_unique(&mut keys.erc20_permit_nonces);
+    retain_unique(&mut keys.permit2_allowances);
     retain_unique(&mut keys.erc721_tokens);
     retain_unique(&mut keys.erc1155_balances);
     retain_unique(&mut keys.operator_approvals);
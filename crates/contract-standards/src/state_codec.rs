@@ -1,5 +1,8 @@
+use alloy_primitives::U256;
 use alloy_sol_types::sol;
 
+use crate::Permit2Allowance;
+
 sol! {
     contract IERC165State {
         function supportsInterface(bytes4 interfaceId) external view returns (bool);
@@ -23,8 +26,26 @@ sol! {
     contract IOperatorApprovalState {
         function isApprovedForAll(address owner, address operator) external view returns (bool);
     }
+
+    contract IERC20PermitState {
+        function nonces(address owner) external view returns (uint256);
+    }
+
+    contract IPermit2State {
+        function allowance(address user, address token, address spender)
+            external
+            view
+            returns (uint160 amount, uint48 expiration, uint48 nonce);
+    }
+
+    contract IBeaconState {
+        function implementation() external view returns (address);
+    }
 }
 
+pub use IBeaconState::implementationCall as BeaconImplementationCall;
+
+pub use IERC20PermitState::noncesCall as Erc20PermitNonceCall;
 pub use IERC20State::{
     allowanceCall as Erc20AllowanceCall, balanceOfCall as Erc20BalanceCall,
     totalSupplyCall as Erc20TotalSupplyCall,
@@ -33,3 +54,14 @@ pub use IERC165State::supportsInterfaceCall as SupportsInterfaceCall;
 pub use IERC721State::{getApprovedCall as Erc721GetApprovedCall, ownerOfCall as Erc721OwnerCall};
 pub use IERC1155State::balanceOfCall as Erc1155BalanceCall;
 pub use IOperatorApprovalState::isApprovedForAllCall as OperatorApprovalCall;
+pub use IPermit2State::allowanceCall as Permit2AllowanceCall;
+
+impl From<IPermit2State::allowanceReturn> for Permit2Allowance {
+    fn from(allowance: IPermit2State::allowanceReturn) -> Self {
+        Self {
+            amount: U256::from(allowance.amount),
+            expiration: allowance.expiration.to::<u64>(),
+            nonce: allowance.nonce.to::<u64>(),
+        }
+    }
+}
@@ -34,6 +34,22 @@ pub(crate) fn check_token_contracts(
                 after_code_hash,
             });
         }
+
+        // A proxy token is checked through its implementation. Upgrading to a
+        // new implementation is reported as a proxy change rather than
+        // rejected; only the code of an unchanged implementation must hold.
+        if let (Some(before_implementation), Some(after_implementation)) = (
+            before.proxy_implementations.get(&contract),
+            after.proxy_implementations.get(&contract),
+        ) && before_implementation.address == after_implementation.address
+            && before_implementation.code_hash != after_implementation.code_hash
+        {
+            return Err(ContractStandardsError::TokenContractCodeChanged {
+                contract: before_implementation.address,
+                before_code_hash: before_implementation.code_hash,
+                after_code_hash: after_implementation.code_hash,
+            });
+        }
     }
 
     for &collection in &keys.collection_standards {
@@ -87,7 +103,8 @@ pub(crate) fn check_token_contracts(
             }
 
             StandardCandidateKind::Erc20Movement { .. }
-            | StandardCandidateKind::Erc20Allowance { .. } => {}
+            | StandardCandidateKind::Erc20Allowance { .. }
+            | StandardCandidateKind::Permit2Allowance { .. } => {}
         }
     }
 
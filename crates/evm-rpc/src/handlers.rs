@@ -1,6 +1,7 @@
 use std::sync::Arc;
 
-use evm_service::{SimulationService, SimulationServiceError};
+use alloy::primitives::{B256, U64};
+use evm_service::{ReplayEvmTransactionInput, SimulationService, SimulationServiceError};
 use jsonrpsee::core::{RpcResult, async_trait};
 use jsonrpsee::types::ErrorObjectOwned;
 use tracing::{error, instrument};
@@ -8,9 +9,11 @@ use tracing::{error, instrument};
 use crate::{
     errors::{internal_error, not_supported},
     interface::{
-        BlockRef, EvmSimulateTransactionRequest, EvmSimulateTransactionResponse,
-        SimulateTransactionOptions, Transaction,
+        BlockRef, CreateAccessListOptions, EvmCreateAccessListRequest, EvmCreateAccessListResponse,
+        EvmSimulateBundleRequest, EvmSimulateBundleResponse, EvmSimulateTransactionRequest,
+        EvmSimulateTransactionResponse, IncludeField, SimulateTransactionOptions, Transaction,
     },
+    mapping::{formats_amounts, map_includes},
     rpc::DryrunRpcServer,
 };
 
@@ -34,19 +37,122 @@ impl RpcHandler {
         block: Option<BlockRef>,
         options: Option<SimulateTransactionOptions>,
     ) -> RpcResult<EvmSimulateTransactionResponse> {
+        let format_amounts = formats_amounts(
+            options
+                .as_ref()
+                .and_then(|options| options.include.as_deref()),
+        );
         let request = EvmSimulateTransactionRequest {
             transaction,
             block,
             options,
         };
         let input: evm_service::SimulateEvmTransactionInput = request.try_into()?;
+        let chain_id = input.transaction.chain_id;
         let output = self
             .simulation_service
             .simulate_evm_transaction(input)
             .await
             .map_err(map_service_error)?;
 
-        Ok(output.into())
+        let mut response = EvmSimulateTransactionResponse::from(output);
+        if format_amounts {
+            response.format_amounts(self.native_decimals(chain_id)?);
+        }
+        Ok(response)
+    }
+
+    #[instrument(
+        name = "dryrun_evm_simulateBundle",
+        skip(self, transactions, block, options)
+    )]
+    async fn handle_simulate_bundle(
+        &self,
+        transactions: Vec<Transaction>,
+        block: Option<BlockRef>,
+        options: Option<SimulateTransactionOptions>,
+    ) -> RpcResult<EvmSimulateBundleResponse> {
+        let format_amounts = formats_amounts(
+            options
+                .as_ref()
+                .and_then(|options| options.include.as_deref()),
+        );
+        let request = EvmSimulateBundleRequest {
+            transactions,
+            block,
+            options,
+        };
+        let input: evm_service::SimulateEvmBundleInput = request.try_into()?;
+        let chain_id = input.chain_id;
+        let output = self
+            .simulation_service
+            .simulate_evm_bundle(input)
+            .await
+            .map_err(map_service_error)?;
+
+        let mut response = EvmSimulateBundleResponse::from(output);
+        if format_amounts {
+            response.format_amounts(self.native_decimals(chain_id)?);
+        }
+        Ok(response)
+    }
+
+    #[instrument(
+        name = "dryrun_evm_createAccessList",
+        skip(self, transaction, block, options)
+    )]
+    async fn handle_create_access_list(
+        &self,
+        transaction: Transaction,
+        block: Option<BlockRef>,
+        options: Option<CreateAccessListOptions>,
+    ) -> RpcResult<EvmCreateAccessListResponse> {
+        let request = EvmCreateAccessListRequest {
+            transaction,
+            block,
+            options,
+        };
+        let input: evm_service::CreateEvmAccessListInput = request.try_into()?;
+        let output = self
+            .simulation_service
+            .create_evm_access_list(input)
+            .await
+            .map_err(map_service_error)?;
+
+        Ok(EvmCreateAccessListResponse::from(output))
+    }
+
+    #[instrument(name = "dryrun_evm_replayTransaction", skip(self, include))]
+    async fn handle_replay_transaction(
+        &self,
+        chain_id: u64,
+        transaction_hash: B256,
+        include: Option<Vec<IncludeField>>,
+    ) -> RpcResult<EvmSimulateTransactionResponse> {
+        let format_amounts = formats_amounts(include.as_deref());
+        let input = ReplayEvmTransactionInput {
+            chain_id,
+            transaction_hash,
+            includes: map_includes(include),
+        };
+        let output = self
+            .simulation_service
+            .replay_evm_transaction(input)
+            .await
+            .map_err(map_service_error)?;
+
+        let mut response = EvmSimulateTransactionResponse::from(output);
+        if format_amounts {
+            response.format_amounts(self.native_decimals(chain_id)?);
+        }
+        Ok(response)
+    }
+
+    fn native_decimals(&self, chain_id: u64) -> RpcResult<Option<u8>> {
+        self.simulation_service
+            .native_currency(chain_id)
+            .map(|native_currency| native_currency.decimals)
+            .map_err(map_service_error)
     }
 }
 
@@ -61,6 +167,36 @@ impl DryrunRpcServer for RpcHandler {
         self.handle_simulate_transaction(transaction, block, options)
             .await
     }
+
+    async fn dryrun_evm_simulate_bundle(
+        &self,
+        transactions: Vec<Transaction>,
+        block: Option<BlockRef>,
+        options: Option<SimulateTransactionOptions>,
+    ) -> RpcResult<EvmSimulateBundleResponse> {
+        self.handle_simulate_bundle(transactions, block, options)
+            .await
+    }
+
+    async fn dryrun_evm_create_access_list(
+        &self,
+        transaction: Transaction,
+        block: Option<BlockRef>,
+        options: Option<CreateAccessListOptions>,
+    ) -> RpcResult<EvmCreateAccessListResponse> {
+        self.handle_create_access_list(transaction, block, options)
+            .await
+    }
+
+    async fn dryrun_evm_replay_transaction(
+        &self,
+        chain_id: U64,
+        transaction_hash: B256,
+        include: Option<Vec<IncludeField>>,
+    ) -> RpcResult<EvmSimulateTransactionResponse> {
+        self.handle_replay_transaction(chain_id.to(), transaction_hash, include)
+            .await
+    }
 }
 
 fn map_service_error(error: SimulationServiceError) -> ErrorObjectOwned {
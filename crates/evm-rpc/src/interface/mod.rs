@@ -2,8 +2,13 @@ mod schema;
 mod validation;
 
 pub use schema::{
-    AccessListItem, AllowanceAsset, BlockRef, Change, Erc20Metadata, Erc721CollectionMetadata,
+    AccessListItem, AccountOverride, AccountStateDiff, AllowanceAsset, Authorization,
+    BalanceChange, BlockOverrides, BlockRef, CallFrame, CallType, Change, CreateAccessListOptions,
+    Erc20Metadata, Erc721CollectionMetadata, EvmCreateAccessListRequest,
+    EvmCreateAccessListResponse, EvmSimulateBundleRequest, EvmSimulateBundleResponse,
     EvmSimulateTransactionRequest, EvmSimulateTransactionResponse, Execution, ExecutionFailure,
-    ExecutionStatus, NativeMetadata, OperatorApprovalAsset, SimulateTransactionOptions,
-    SimulatedBlock, TokenApprovalAsset, TokenMovementAsset, Transaction, TransferAsset,
+    ExecutionStatus, IncludeField, Log, NativeMetadata, NetChange, NetChangeAsset,
+    OperatorApprovalAsset, ProxySlot, RiskCode, RiskSeverity, SimulateTransactionOptions,
+    SimulatedBlock, StateOverrides, TokenApprovalAsset, TokenMovementAsset, Transaction,
+    TransferAsset, ValueDiff, Warning,
 };
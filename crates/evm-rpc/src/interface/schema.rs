@@ -1,9 +1,10 @@
+use std::collections::BTreeMap;
+
 use alloy::{
-    primitives::{Address, B256, Bytes, U256},
+    primitives::{Address, B256, Bytes, U64, U256, aliases::I512},
     serde::quantity,
 };
 use serde::{Deserialize, Serialize};
-use serde_json::Value;
 
 mod u256_hex {
     use alloy::primitives::U256;
@@ -17,6 +18,20 @@ mod u256_hex {
     }
 }
 
+/// Signed quantities in hex, with a leading `-` when negative.
+mod signed_hex {
+    use alloy::primitives::aliases::I512;
+    use serde::Serializer;
+
+    pub(super) fn serialize<S>(value: &I512, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let sign = if value.is_negative() { "-" } else { "" };
+        serializer.collect_str(&format_args!("{sign}{:#x}", value.unsigned_abs()))
+    }
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq)]
 #[serde(rename_all = "camelCase", deny_unknown_fields)]
 pub struct EvmSimulateTransactionRequest {
@@ -25,6 +40,22 @@ pub struct EvmSimulateTransactionRequest {
     pub options: Option<SimulateTransactionOptions>,
 }
 
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct EvmSimulateBundleRequest {
+    pub transactions: Vec<Transaction>,
+    pub block: Option<BlockRef>,
+    pub options: Option<SimulateTransactionOptions>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct EvmCreateAccessListRequest {
+    pub transaction: Transaction,
+    pub block: Option<BlockRef>,
+    pub options: Option<CreateAccessListOptions>,
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq)]
 #[serde(untagged)]
 pub enum BlockRef {
@@ -42,11 +73,83 @@ pub struct BlockHashRef {
 #[serde(rename_all = "camelCase", deny_unknown_fields)]
 pub struct SimulateTransactionOptions {
     #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub state_overrides: Option<Value>,
+    pub state_overrides: Option<StateOverrides>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub block_overrides: Option<BlockOverrides>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub include: Option<Vec<IncludeField>>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct CreateAccessListOptions {
     #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub block_overrides: Option<Value>,
+    pub state_overrides: Option<StateOverrides>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub include: Option<Value>,
+    pub block_overrides: Option<BlockOverrides>,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum IncludeField {
+    CallTrace,
+    StateDiff,
+    Logs,
+    FormattedAmounts,
+}
+
+pub type StateOverrides = BTreeMap<Address, AccountOverride>;
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct AccountOverride {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub balance: Option<U256>,
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "quantity::opt"
+    )]
+    pub nonce: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub code: Option<Bytes>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub state: Option<BTreeMap<B256, B256>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub state_diff: Option<BTreeMap<B256, B256>>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct BlockOverrides {
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "quantity::opt"
+    )]
+    pub number: Option<u64>,
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "quantity::opt"
+    )]
+    pub timestamp: Option<u64>,
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "quantity::opt"
+    )]
+    pub base_fee_per_gas: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub coinbase: Option<Address>,
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "quantity::opt"
+    )]
+    pub gas_limit: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prev_randao: Option<B256>,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq)]
@@ -100,6 +203,16 @@ pub struct Transaction {
         with = "quantity::opt"
     )]
     pub max_priority_fee_per_gas: Option<u128>,
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "quantity::opt"
+    )]
+    pub max_fee_per_blob_gas: Option<u128>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub blob_versioned_hashes: Option<Vec<B256>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub authorization_list: Option<Vec<Authorization>>,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq)]
@@ -109,12 +222,151 @@ pub struct AccessListItem {
     pub storage_keys: Vec<B256>,
 }
 
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct Authorization {
+    pub chain_id: U256,
+    pub address: Address,
+    #[serde(with = "quantity")]
+    pub nonce: u64,
+    #[serde(with = "quantity")]
+    pub y_parity: u8,
+    pub r: U256,
+    pub s: U256,
+}
+
 #[derive(Debug, Clone, Serialize, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
 pub struct EvmSimulateTransactionResponse {
     pub execution: Execution,
     #[serde(default)]
     pub changes: Vec<Change>,
+    #[serde(default)]
+    pub balance_changes: Vec<BalanceChange>,
+    #[serde(default)]
+    pub warnings: Vec<Warning>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub call_trace: Option<CallFrame>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub state_diff: Option<Vec<AccountStateDiff>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub logs: Option<Vec<Log>>,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Log {
+    pub address: Address,
+    pub topics: Vec<B256>,
+    pub data: Bytes,
+    #[serde(with = "quantity")]
+    pub log_index: u64,
+    #[serde(with = "quantity")]
+    pub change_index: u64,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Warning {
+    pub code: RiskCode,
+    pub severity: RiskSeverity,
+    pub account: Address,
+    #[serde(with = "quantity::vec")]
+    pub change_indexes: Vec<u64>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum RiskCode {
+    UnlimitedAllowanceToEoa,
+    ApprovalForAllToFreshContract,
+    NativeValueToCodelessTarget,
+    OwnershipTransfer,
+    WalletDrained,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum RiskSeverity {
+    Low,
+    Medium,
+    High,
+}
+
+/// The generated access list, the gas the transaction uses with and without
+/// it, and the execution of the transaction carrying it.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct EvmCreateAccessListResponse {
+    pub access_list: Vec<AccessListItem>,
+    #[serde(with = "quantity")]
+    pub gas_used_with_access_list: u64,
+    #[serde(with = "quantity")]
+    pub gas_used_without_access_list: u64,
+    pub execution: Execution,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct EvmSimulateBundleResponse {
+    pub transactions: Vec<EvmSimulateTransactionResponse>,
+    #[serde(default)]
+    pub net_changes: Vec<NetChange>,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct NetChange {
+    pub account: Address,
+    #[serde(flatten)]
+    pub asset: NetChangeAsset,
+    #[serde(serialize_with = "signed_hex::serialize")]
+    pub raw_delta: I512,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub formatted_delta: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct BalanceChange {
+    pub account: Address,
+    #[serde(flatten)]
+    pub asset: NetChangeAsset,
+    #[serde(serialize_with = "signed_hex::serialize")]
+    pub raw_delta: I512,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub raw_balance_before: Option<U256>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub raw_balance_after: Option<U256>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub formatted_delta: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub formatted_balance_before: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub formatted_balance_after: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+#[serde(
+    tag = "assetType",
+    rename_all = "SCREAMING_SNAKE_CASE",
+    rename_all_fields = "camelCase"
+)]
+pub enum NetChangeAsset {
+    Native,
+    Erc20 {
+        contract_address: Address,
+    },
+    Erc721 {
+        contract_address: Address,
+        #[serde(serialize_with = "u256_hex::serialize")]
+        token_id: U256,
+    },
+    Erc1155 {
+        contract_address: Address,
+        #[serde(serialize_with = "u256_hex::serialize")]
+        token_id: U256,
+    },
 }
 
 #[derive(Debug, Clone, Serialize, PartialEq, Eq)]
@@ -130,6 +382,10 @@ pub struct Execution {
     pub gas_limit: u64,
     pub fee: U256,
     pub burnt_fee: U256,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub formatted_fee: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub formatted_burnt_fee: Option<String>,
     pub output: Bytes,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub failure: Option<ExecutionFailure>,
@@ -160,6 +416,57 @@ pub struct ExecutionFailure {
     pub reason: Option<String>,
 }
 
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct CallFrame {
+    #[serde(rename = "type")]
+    pub call_type: CallType,
+    pub from: Address,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub to: Option<Address>,
+    pub value: U256,
+    #[serde(with = "quantity")]
+    pub gas: u64,
+    #[serde(with = "quantity")]
+    pub gas_used: u64,
+    pub input: Bytes,
+    pub output: Bytes,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub revert_reason: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub calls: Vec<CallFrame>,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountStateDiff {
+    pub address: Address,
+    pub balance: ValueDiff<U256>,
+    pub nonce: ValueDiff<U64>,
+    pub code_hash: ValueDiff<B256>,
+    pub storage: BTreeMap<B256, ValueDiff<B256>>,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ValueDiff<T> {
+    pub before: T,
+    pub after: T,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum CallType {
+    Call,
+    CallCode,
+    DelegateCall,
+    StaticCall,
+    Create,
+    Create2,
+}
+
 #[derive(Debug, Clone, Serialize, PartialEq, Eq)]
 #[serde(
     tag = "changeType",
@@ -189,6 +496,30 @@ pub enum Change {
         owner: Address,
         spender: Address,
     },
+    /// An allowance set by an EIP-2612 `permit` signature.
+    Permit {
+        #[serde(flatten)]
+        asset: AllowanceAsset,
+        owner: Address,
+        spender: Address,
+        #[serde(serialize_with = "u256_hex::serialize")]
+        nonce_before: U256,
+        #[serde(serialize_with = "u256_hex::serialize")]
+        nonce_after: U256,
+    },
+    /// An allowance held by a Permit2 contract; the asset is the token it
+    /// lets `spender` pull.
+    Permit2Allowance {
+        #[serde(flatten)]
+        asset: AllowanceAsset,
+        permit2_address: Address,
+        owner: Address,
+        spender: Address,
+        expiration_before: U64,
+        expiration_after: U64,
+        nonce_before: U64,
+        nonce_after: U64,
+    },
     TokenApproval {
         #[serde(flatten)]
         asset: TokenApprovalAsset,
@@ -201,6 +532,25 @@ pub enum Change {
         approved_before: bool,
         approved_after: bool,
     },
+    Delegation {
+        authority: Address,
+        delegate_before: Option<Address>,
+        delegate_after: Option<Address>,
+    },
+    ProxyUpgrade {
+        proxy: Address,
+        slot: ProxySlot,
+        address_before: Option<Address>,
+        address_after: Option<Address>,
+    },
+}
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ProxySlot {
+    Implementation,
+    Admin,
+    Beacon,
 }
 
 #[derive(Debug, Clone, Serialize, PartialEq, Eq)]
@@ -213,6 +563,8 @@ pub enum TransferAsset {
     Native {
         #[serde(serialize_with = "u256_hex::serialize")]
         raw_amount: U256,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        formatted_amount: Option<String>,
         #[serde(flatten)]
         metadata: NativeMetadata,
     },
@@ -220,6 +572,8 @@ pub enum TransferAsset {
         contract_address: Address,
         #[serde(serialize_with = "u256_hex::serialize")]
         raw_amount: U256,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        formatted_amount: Option<String>,
         #[serde(flatten)]
         metadata: Erc20Metadata,
     },
@@ -250,6 +604,8 @@ pub enum TokenMovementAsset {
         contract_address: Address,
         #[serde(serialize_with = "u256_hex::serialize")]
         raw_amount: U256,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        formatted_amount: Option<String>,
         #[serde(flatten)]
         metadata: Erc20Metadata,
     },
@@ -282,6 +638,15 @@ pub enum AllowanceAsset {
         raw_amount_before: U256,
         #[serde(serialize_with = "u256_hex::serialize")]
         raw_amount_after: U256,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        formatted_amount_before: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        formatted_amount_after: Option<String>,
+        /// Set with formatted amounts; `true` for an unlimited allowance.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        unlimited_before: Option<bool>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        unlimited_after: Option<bool>,
         #[serde(flatten)]
         metadata: Erc20Metadata,
     },
@@ -352,3 +717,27 @@ pub struct Erc721CollectionMetadata {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub symbol: Option<String>,
 }
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[derive(Serialize)]
+    struct Delta(#[serde(serialize_with = "signed_hex::serialize")] I512);
+
+    #[test]
+    fn serializes_deltas_as_signed_hex() {
+        let delta =
+            |value: i64| serde_json::to_value(Delta(I512::try_from(value).unwrap())).unwrap();
+
+        assert_eq!(delta(0), json!("0x0"));
+        assert_eq!(delta(255), json!("0xff"));
+        assert_eq!(delta(-31), json!("-0x1f"));
+        assert_eq!(
+            serde_json::to_value(Delta(I512::MIN)).unwrap(),
+            json!(format!("-0x8{}", "0".repeat(127)))
+        );
+    }
+}
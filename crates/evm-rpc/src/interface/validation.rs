@@ -1,14 +1,17 @@
 use std::str::FromStr;
 
-use alloy::primitives::B256;
+use alloy::primitives::{Address, B256};
 
 use crate::errors::ValidationError;
 
 use super::{
-    AccessListItem, BlockRef, EvmSimulateTransactionRequest, SimulateTransactionOptions,
+    AccessListItem, AccountOverride, BlockRef, CreateAccessListOptions, EvmCreateAccessListRequest,
+    EvmSimulateBundleRequest, EvmSimulateTransactionRequest, SimulateTransactionOptions,
     Transaction,
 };
 
+const MAX_BUNDLE_TRANSACTIONS: usize = 16;
+
 impl EvmSimulateTransactionRequest {
     pub(crate) fn validate(&self) -> Result<(), ValidationError> {
         self.transaction.validate()?;
@@ -25,31 +28,114 @@ impl EvmSimulateTransactionRequest {
     }
 }
 
+impl EvmSimulateBundleRequest {
+    pub(crate) fn validate(&self) -> Result<(), ValidationError> {
+        if self.transactions.is_empty() {
+            return Err(ValidationError::invalid_params(
+                "`transactions` must contain at least one transaction",
+            ));
+        }
+
+        if self.transactions.len() > MAX_BUNDLE_TRANSACTIONS {
+            return Err(ValidationError::invalid_params(format!(
+                "`transactions` must not contain more than {MAX_BUNDLE_TRANSACTIONS} transactions"
+            )));
+        }
+
+        let chain_id = self.transactions[0].chain_id;
+        for (index, transaction) in self.transactions.iter().enumerate() {
+            transaction.validate()?;
+
+            if transaction.chain_id != chain_id {
+                return Err(ValidationError::invalid_params(format!(
+                    "`transactions[{index}].chainId` must match the first bundle transaction"
+                )));
+            }
+
+            // Provider gas estimation cannot see the effects of earlier
+            // bundle transactions.
+            if index > 0 && transaction.gas.is_none() {
+                return Err(ValidationError::invalid_params(format!(
+                    "`transactions[{index}].gas` is required after the first bundle transaction"
+                )));
+            }
+        }
+
+        if let Some(block) = &self.block {
+            block.validate()?;
+        }
+
+        if let Some(options) = &self.options {
+            options.validate()?;
+        }
+
+        Ok(())
+    }
+}
+
+impl EvmCreateAccessListRequest {
+    pub(crate) fn validate(&self) -> Result<(), ValidationError> {
+        self.transaction.validate()?;
+
+        if let Some(block) = &self.block {
+            block.validate()?;
+        }
+
+        if let Some(options) = &self.options {
+            options.validate()?;
+        }
+
+        Ok(())
+    }
+}
+
 impl BlockRef {
     pub(crate) fn validate(&self) -> Result<(), ValidationError> {
         match self {
             Self::Tag(value) => match value.as_str() {
-                "latest" | "safe" | "finalized" => Ok(()),
-                "pending" | "earliest" => Err(ValidationError::not_supported(
-                    "`block` supports `latest`, `safe`, `finalized`, or a hex block number",
-                )),
-                value if B256::from_str(value).is_ok() => Err(ValidationError::not_supported(
-                    "`block` does not support block hash selectors",
+                "latest" | "pending" | "safe" | "finalized" => Ok(()),
+                "earliest" => Err(ValidationError::not_supported(
+                    "`block` supports `latest`, `pending`, `safe`, `finalized`, a hex block number, or a block hash",
                 )),
+                value if B256::from_str(value).is_ok() => Ok(()),
                 value => validate_hex_param(value, "block"),
             },
-            Self::Hash(_) => Err(ValidationError::not_supported(
-                "`block.blockHash` is not supported yet",
-            )),
+            Self::Hash(_) => Ok(()),
         }
     }
 }
 
 impl SimulateTransactionOptions {
     pub(crate) fn validate(&self) -> Result<(), ValidationError> {
-        validate_reserved_option("stateOverrides", self.state_overrides.as_ref())?;
-        validate_reserved_option("blockOverrides", self.block_overrides.as_ref())?;
-        validate_reserved_option("include", self.include.as_ref())?;
+        if let Some(state_overrides) = &self.state_overrides {
+            for (address, account_override) in state_overrides {
+                account_override.validate(address)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl CreateAccessListOptions {
+    pub(crate) fn validate(&self) -> Result<(), ValidationError> {
+        if let Some(state_overrides) = &self.state_overrides {
+            for (address, account_override) in state_overrides {
+                account_override.validate(address)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl AccountOverride {
+    pub(crate) fn validate(&self, address: &Address) -> Result<(), ValidationError> {
+        if self.state.is_some() && self.state_diff.is_some() {
+            return Err(ValidationError::invalid_params(format!(
+                "`options.stateOverrides.{address}` cannot set both `state` and `stateDiff`"
+            )));
+        }
 
         Ok(())
     }
@@ -58,10 +144,19 @@ impl SimulateTransactionOptions {
 impl Transaction {
     pub(crate) fn validate(&self) -> Result<(), ValidationError> {
         if let Some(tx_type) = self.tx_type
-            && !matches!(tx_type, 0x0..=0x2)
+            && !matches!(tx_type, 0x0..=0x4)
         {
             return Err(ValidationError::not_supported(
-                "`transaction.type` only supports `0x0`, `0x1`, and `0x2`",
+                "`transaction.type` only supports `0x0`, `0x1`, `0x2`, `0x3`, and `0x4`",
+            ));
+        }
+
+        // Blob and set-code transactions cannot create contracts.
+        let is_blob = self.tx_type == Some(0x3) || self.blob_versioned_hashes.is_some();
+        let is_set_code = self.tx_type == Some(0x4) || self.authorization_list.is_some();
+        if (is_blob || is_set_code) && self.to.is_none() {
+            return Err(ValidationError::invalid_params(
+                "`transaction.to` is required for blob and set-code transactions",
             ));
         }
 
@@ -81,19 +176,6 @@ impl AccessListItem {
     }
 }
 
-fn validate_reserved_option(
-    field: &str,
-    value: Option<&serde_json::Value>,
-) -> Result<(), ValidationError> {
-    if value.is_some() {
-        return Err(ValidationError::not_supported(format!(
-            "`options.{field}` is reserved and not supported yet"
-        )));
-    }
-
-    Ok(())
-}
-
 fn validate_hex_param(value: &str, field: &str) -> Result<(), ValidationError> {
     let digits = value.strip_prefix("0x").ok_or_else(|| {
         ValidationError::invalid_params(format!("`{field}` must be a 0x-prefixed hex string"))
@@ -0,0 +1,209 @@
+use std::collections::HashMap;
+
+use alloy::primitives::{Address, U256, aliases::I512};
+use simulation_changes::{PERMIT2_UNLIMITED_AMOUNT, format_units};
+
+use crate::interface as rpc;
+
+pub(crate) fn formats_amounts(include: Option<&[rpc::IncludeField]>) -> bool {
+    include.is_some_and(|fields| fields.contains(&rpc::IncludeField::FormattedAmounts))
+}
+
+impl rpc::EvmSimulateTransactionResponse {
+    /// Adds exact decimal strings next to the raw amounts whose decimals are
+    /// known. `native_decimals` applies to fees and native balances.
+    pub(crate) fn format_amounts(&mut self, native_decimals: Option<u8>) {
+        let decimals = AssetDecimals::new(native_decimals, [&*self]);
+        self.apply_formatted_amounts(&decimals);
+    }
+
+    fn apply_formatted_amounts(&mut self, decimals: &AssetDecimals) {
+        if let Some(native) = decimals.native {
+            self.execution.formatted_fee = Some(format_units(self.execution.fee, native));
+            self.execution.formatted_burnt_fee =
+                Some(format_units(self.execution.burnt_fee, native));
+        }
+
+        for change in &mut self.changes {
+            format_change(change);
+        }
+
+        for balance_change in &mut self.balance_changes {
+            let Some(asset_decimals) = decimals.get(&balance_change.asset) else {
+                continue;
+            };
+            balance_change.formatted_delta = Some(format_signed_units(
+                balance_change.raw_delta,
+                asset_decimals,
+            ));
+            balance_change.formatted_balance_before = balance_change
+                .raw_balance_before
+                .map(|balance| format_units(balance, asset_decimals));
+            balance_change.formatted_balance_after = balance_change
+                .raw_balance_after
+                .map(|balance| format_units(balance, asset_decimals));
+        }
+    }
+}
+
+impl rpc::EvmSimulateBundleResponse {
+    pub(crate) fn format_amounts(&mut self, native_decimals: Option<u8>) {
+        let decimals = AssetDecimals::new(native_decimals, &self.transactions);
+        for transaction in &mut self.transactions {
+            transaction.apply_formatted_amounts(&decimals);
+        }
+
+        for net_change in &mut self.net_changes {
+            net_change.formatted_delta = decimals
+                .get(&net_change.asset)
+                .map(|asset_decimals| format_signed_units(net_change.raw_delta, asset_decimals));
+        }
+    }
+}
+
+/// Decimals of the fungible assets in a response. Summaries carry no token
+/// metadata, so ERC-20 decimals are taken from the changes that moved them.
+struct AssetDecimals {
+    native: Option<u8>,
+    erc20: HashMap<Address, u8>,
+}
+
+impl AssetDecimals {
+    fn new<'a>(
+        native: Option<u8>,
+        responses: impl IntoIterator<Item = &'a rpc::EvmSimulateTransactionResponse>,
+    ) -> Self {
+        let erc20 = responses
+            .into_iter()
+            .flat_map(|response| &response.changes)
+            .filter_map(|change| match change {
+                rpc::Change::Transfer {
+                    asset:
+                        rpc::TransferAsset::Erc20 {
+                            contract_address,
+                            metadata,
+                            ..
+                        },
+                    ..
+                }
+                | rpc::Change::Mint {
+                    asset:
+                        rpc::TokenMovementAsset::Erc20 {
+                            contract_address,
+                            metadata,
+                            ..
+                        },
+                    ..
+                }
+                | rpc::Change::Burn {
+                    asset:
+                        rpc::TokenMovementAsset::Erc20 {
+                            contract_address,
+                            metadata,
+                            ..
+                        },
+                    ..
+                } => metadata
+                    .decimals
+                    .map(|decimals| (*contract_address, decimals)),
+                _ => None,
+            })
+            .collect();
+
+        Self { native, erc20 }
+    }
+
+    fn get(&self, asset: &rpc::NetChangeAsset) -> Option<u8> {
+        match asset {
+            rpc::NetChangeAsset::Native => self.native,
+            rpc::NetChangeAsset::Erc20 { contract_address } => {
+                self.erc20.get(contract_address).copied()
+            }
+            rpc::NetChangeAsset::Erc721 { .. } | rpc::NetChangeAsset::Erc1155 { .. } => None,
+        }
+    }
+}
+
+fn format_change(change: &mut rpc::Change) {
+    match change {
+        rpc::Change::Transfer {
+            asset:
+                rpc::TransferAsset::Native {
+                    raw_amount,
+                    formatted_amount,
+                    metadata: rpc::NativeMetadata { decimals, .. },
+                }
+                | rpc::TransferAsset::Erc20 {
+                    raw_amount,
+                    formatted_amount,
+                    metadata: rpc::Erc20Metadata { decimals, .. },
+                    ..
+                },
+            ..
+        }
+        | rpc::Change::Mint {
+            asset:
+                rpc::TokenMovementAsset::Erc20 {
+                    raw_amount,
+                    formatted_amount,
+                    metadata: rpc::Erc20Metadata { decimals, .. },
+                    ..
+                },
+            ..
+        }
+        | rpc::Change::Burn {
+            asset:
+                rpc::TokenMovementAsset::Erc20 {
+                    raw_amount,
+                    formatted_amount,
+                    metadata: rpc::Erc20Metadata { decimals, .. },
+                    ..
+                },
+            ..
+        } => {
+            *formatted_amount = decimals.map(|decimals| format_units(*raw_amount, decimals));
+        }
+        rpc::Change::Allowance { asset, .. } | rpc::Change::Permit { asset, .. } => {
+            format_allowance(asset, U256::MAX);
+        }
+        rpc::Change::Permit2Allowance { asset, .. } => {
+            format_allowance(asset, PERMIT2_UNLIMITED_AMOUNT);
+        }
+        rpc::Change::Transfer { .. }
+        | rpc::Change::Mint { .. }
+        | rpc::Change::Burn { .. }
+        | rpc::Change::TokenApproval { .. }
+        | rpc::Change::OperatorApproval { .. }
+        | rpc::Change::Delegation { .. }
+        | rpc::Change::ProxyUpgrade { .. } => {}
+    }
+}
+
+fn format_allowance(asset: &mut rpc::AllowanceAsset, unlimited_amount: U256) {
+    let rpc::AllowanceAsset::Erc20 {
+        raw_amount_before,
+        raw_amount_after,
+        formatted_amount_before,
+        formatted_amount_after,
+        unlimited_before,
+        unlimited_after,
+        metadata,
+        ..
+    } = asset;
+
+    *unlimited_before = Some(*raw_amount_before == unlimited_amount);
+    *unlimited_after = Some(*raw_amount_after == unlimited_amount);
+    if let Some(decimals) = metadata.decimals {
+        *formatted_amount_before = Some(format_units(*raw_amount_before, decimals));
+        *formatted_amount_after = Some(format_units(*raw_amount_after, decimals));
+    }
+}
+
+fn format_signed_units(raw: I512, decimals: u8) -> String {
+    let formatted = format_units(raw.unsigned_abs(), decimals);
+    if raw.is_negative() {
+        format!("-{formatted}")
+    } else {
+        formatted
+    }
+}
@@ -1,3 +1,7 @@
+mod amounts;
 mod request;
 mod response;
 mod shared;
+
+pub(crate) use amounts::formats_amounts;
+pub(crate) use request::map_includes;
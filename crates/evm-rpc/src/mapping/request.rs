@@ -1,6 +1,9 @@
-use std::convert::TryFrom;
+use std::{convert::TryFrom, str::FromStr};
 
-use simulation_transaction::{TransactionType, TransactionVariantRequest};
+use alloy::{eips::eip7702::Authorization, primitives::B256};
+use simulation_transaction::{
+    SignedAuthorization, TransactionType, TransactionVariantFields, TransactionVariantRequest,
+};
 
 use crate::{errors::ValidationError, interface as rpc};
 
@@ -13,52 +16,143 @@ impl TryFrom<rpc::EvmSimulateTransactionRequest> for evm_service::SimulateEvmTra
         request.validate()?;
 
         let rpc::EvmSimulateTransactionRequest {
-            block, transaction, ..
+            block,
+            transaction,
+            options,
         } = request;
+        let options = options.unwrap_or_default();
 
         Ok(Self {
-            block: block
-                .map(map_block_ref)
-                .transpose()?
-                .unwrap_or(evm_service::EvmBlockSelector::Latest),
+            block: map_block(block)?,
             transaction: map_transaction(transaction)?,
+            state_overrides: options
+                .state_overrides
+                .map(map_state_overrides)
+                .unwrap_or_default(),
+            block_overrides: options
+                .block_overrides
+                .map(map_block_overrides)
+                .unwrap_or_default(),
+            includes: map_includes(options.include),
         })
     }
 }
 
+impl TryFrom<rpc::EvmSimulateBundleRequest> for evm_service::SimulateEvmBundleInput {
+    type Error = ValidationError;
+
+    fn try_from(request: rpc::EvmSimulateBundleRequest) -> Result<Self, Self::Error> {
+        request.validate()?;
+
+        let rpc::EvmSimulateBundleRequest {
+            transactions,
+            block,
+            options,
+        } = request;
+        let options = options.unwrap_or_default();
+
+        Ok(Self {
+            chain_id: transactions[0].chain_id,
+            block: map_block(block)?,
+            transactions: transactions
+                .into_iter()
+                .map(map_transaction)
+                .collect::<Result<_, _>>()?,
+            state_overrides: options
+                .state_overrides
+                .map(map_state_overrides)
+                .unwrap_or_default(),
+            block_overrides: options
+                .block_overrides
+                .map(map_block_overrides)
+                .unwrap_or_default(),
+            includes: map_includes(options.include),
+        })
+    }
+}
+
+impl TryFrom<rpc::EvmCreateAccessListRequest> for evm_service::CreateEvmAccessListInput {
+    type Error = ValidationError;
+
+    fn try_from(request: rpc::EvmCreateAccessListRequest) -> Result<Self, Self::Error> {
+        request.validate()?;
+
+        let rpc::EvmCreateAccessListRequest {
+            transaction,
+            block,
+            options,
+        } = request;
+        let options = options.unwrap_or_default();
+
+        Ok(Self {
+            block: map_block(block)?,
+            transaction: map_transaction(transaction)?,
+            state_overrides: options
+                .state_overrides
+                .map(map_state_overrides)
+                .unwrap_or_default(),
+            block_overrides: options
+                .block_overrides
+                .map(map_block_overrides)
+                .unwrap_or_default(),
+        })
+    }
+}
+
+fn map_block(
+    block: Option<rpc::BlockRef>,
+) -> Result<evm_service::EvmBlockSelector, ValidationError> {
+    Ok(block
+        .map(map_block_ref)
+        .transpose()?
+        .unwrap_or(evm_service::EvmBlockSelector::Latest))
+}
+
 fn map_block_ref(block: rpc::BlockRef) -> Result<evm_service::EvmBlockSelector, ValidationError> {
     match block {
         rpc::BlockRef::Tag(value) => match value.as_str() {
             "latest" => Ok(evm_service::EvmBlockSelector::Latest),
+            "pending" => Ok(evm_service::EvmBlockSelector::Pending),
             "safe" => Ok(evm_service::EvmBlockSelector::Safe),
             "finalized" => Ok(evm_service::EvmBlockSelector::Finalized),
-            value => Ok(evm_service::EvmBlockSelector::Number(parse_u64_param(
-                value, "block",
-            )?)),
+            value => match B256::from_str(value) {
+                Ok(hash) => Ok(evm_service::EvmBlockSelector::Hash(hash)),
+                Err(_) => Ok(evm_service::EvmBlockSelector::Number(parse_u64_param(
+                    value, "block",
+                )?)),
+            },
         },
-        rpc::BlockRef::Hash(_) => Err(ValidationError::not_supported(
-            "`block.blockHash` is not supported yet",
-        )),
+        rpc::BlockRef::Hash(block) => Ok(evm_service::EvmBlockSelector::Hash(block.block_hash)),
     }
 }
 
 fn map_transaction(
     transaction: rpc::Transaction,
 ) -> Result<evm_service::EvmTransactionRequest, ValidationError> {
-    let transaction_type = map_transaction_type(transaction.tx_type)?;
-    let transaction_type = TransactionType::infer(
-        transaction_type,
-        transaction.access_list.is_some(),
-        transaction.max_fee_per_gas.is_some() || transaction.max_priority_fee_per_gas.is_some(),
-    );
+    let transaction_type = match map_transaction_type(transaction.tx_type)? {
+        None if transaction.blob_versioned_hashes.is_some() => TransactionType::Blob,
+        None if transaction.authorization_list.is_some() => TransactionType::SetCode,
+        transaction_type => TransactionType::infer(
+            transaction_type,
+            transaction.access_list.is_some(),
+            transaction.max_fee_per_gas.is_some() || transaction.max_priority_fee_per_gas.is_some(),
+        ),
+    };
     let variant = TransactionVariantRequest::try_new(
         transaction_type,
-        transaction
-            .access_list
-            .map(|items| items.into_iter().map(to_service_access_list_item).collect()),
-        transaction.gas_price,
-        transaction.max_fee_per_gas,
-        transaction.max_priority_fee_per_gas,
+        TransactionVariantFields {
+            access_list: transaction
+                .access_list
+                .map(|items| items.into_iter().map(to_service_access_list_item).collect()),
+            gas_price: transaction.gas_price,
+            max_fee_per_gas: transaction.max_fee_per_gas,
+            max_priority_fee_per_gas: transaction.max_priority_fee_per_gas,
+            max_fee_per_blob_gas: transaction.max_fee_per_blob_gas,
+            blob_versioned_hashes: transaction.blob_versioned_hashes,
+            authorization_list: transaction
+                .authorization_list
+                .map(|items| items.into_iter().map(to_signed_authorization).collect()),
+        },
     )
     .map_err(|error| ValidationError::invalid_params(error.to_string()))?;
 
@@ -82,16 +176,91 @@ fn map_transaction_type(
             0x0 => Ok(TransactionType::Legacy),
             0x1 => Ok(TransactionType::AccessList),
             0x2 => Ok(TransactionType::DynamicFee),
+            0x3 => Ok(TransactionType::Blob),
+            0x4 => Ok(TransactionType::SetCode),
             _ => Err(ValidationError::not_supported(
-                "`transaction.type` only supports `0x0`, `0x1`, and `0x2`",
+                "`transaction.type` only supports `0x0`, `0x1`, `0x2`, `0x3`, and `0x4`",
             )),
         })
         .transpose()
 }
 
+pub(crate) fn map_includes(
+    include: Option<Vec<rpc::IncludeField>>,
+) -> evm_service::EvmSimulationIncludes {
+    let mut includes = evm_service::EvmSimulationIncludes::default();
+    for field in include.unwrap_or_default() {
+        match field {
+            rpc::IncludeField::CallTrace => includes.call_trace = true,
+            rpc::IncludeField::StateDiff => includes.state_diff = true,
+            rpc::IncludeField::Logs => includes.logs = true,
+            // Applied while mapping the response.
+            rpc::IncludeField::FormattedAmounts => {}
+        }
+    }
+
+    includes
+}
+
+fn map_block_overrides(overrides: rpc::BlockOverrides) -> evm_service::EvmBlockOverrides {
+    evm_service::EvmBlockOverrides {
+        number: overrides.number,
+        timestamp: overrides.timestamp,
+        base_fee_per_gas: overrides.base_fee_per_gas,
+        coinbase: overrides.coinbase,
+        gas_limit: overrides.gas_limit,
+        prevrandao: overrides.prev_randao,
+        excess_blob_gas: None,
+    }
+}
+
+fn map_state_overrides(overrides: rpc::StateOverrides) -> evm_service::EvmStateOverrides {
+    overrides
+        .into_iter()
+        .map(|(address, account_override)| (address, map_account_override(account_override)))
+        .collect()
+}
+
+fn map_account_override(account_override: rpc::AccountOverride) -> evm_service::EvmAccountOverride {
+    let rpc::AccountOverride {
+        balance,
+        nonce,
+        code,
+        state,
+        state_diff,
+    } = account_override;
+
+    // Validation rejects overrides that set both `state` and `stateDiff`.
+    let storage = match (state, state_diff) {
+        (Some(slots), _) => Some(evm_service::EvmStorageOverride::Replace(slots)),
+        (None, Some(slots)) => Some(evm_service::EvmStorageOverride::Patch(slots)),
+        (None, None) => None,
+    };
+
+    evm_service::EvmAccountOverride {
+        balance,
+        nonce,
+        code,
+        storage,
+    }
+}
+
 fn to_service_access_list_item(item: rpc::AccessListItem) -> evm_service::AccessListItem {
     evm_service::AccessListItem {
         address: item.address,
         storage_keys: item.storage_keys,
     }
 }
+
+fn to_signed_authorization(authorization: rpc::Authorization) -> SignedAuthorization {
+    SignedAuthorization::new_unchecked(
+        Authorization {
+            chain_id: authorization.chain_id,
+            address: authorization.address,
+            nonce: authorization.nonce,
+        },
+        authorization.y_parity,
+        authorization.r,
+        authorization.s,
+    )
+}
@@ -1,10 +1,51 @@
-use alloy::primitives::{Bytes, U256};
+use alloy::primitives::{B256, Bytes, U64, U256};
 
 use crate::interface as rpc;
 
 impl From<evm_service::SimulateEvmTransactionOutput> for rpc::EvmSimulateTransactionResponse {
     fn from(output: evm_service::SimulateEvmTransactionOutput) -> Self {
-        let (execution, changes) = output.into_parts();
+        let evm_service::SimulateEvmTransactionOutput {
+            execution,
+            changes,
+            balance_changes,
+            warnings,
+            call_trace,
+            state_diff,
+            logs,
+        } = output;
+
+        Self {
+            execution: execution.into(),
+            changes: changes.into_iter().map(Into::into).collect(),
+            balance_changes: balance_changes.into_iter().map(Into::into).collect(),
+            warnings: warnings.into_iter().map(Into::into).collect(),
+            call_trace: call_trace.map(Into::into),
+            state_diff: state_diff.map(|accounts| accounts.into_iter().map(Into::into).collect()),
+            logs: logs.map(|logs| logs.into_iter().enumerate().map(map_log).collect()),
+        }
+    }
+}
+
+impl From<evm_service::CreateEvmAccessListOutput> for rpc::EvmCreateAccessListResponse {
+    fn from(output: evm_service::CreateEvmAccessListOutput) -> Self {
+        Self {
+            access_list: output
+                .access_list
+                .into_iter()
+                .map(|item| rpc::AccessListItem {
+                    address: item.address,
+                    storage_keys: item.storage_keys,
+                })
+                .collect(),
+            gas_used_with_access_list: output.gas_used_with_access_list,
+            gas_used_without_access_list: output.gas_used_without_access_list,
+            execution: output.execution.into(),
+        }
+    }
+}
+
+impl From<evm_service::SimulationExecution> for rpc::Execution {
+    fn from(execution: evm_service::SimulationExecution) -> Self {
         let evm_service::SimulationExecution {
             chain_id,
             context: block,
@@ -56,18 +97,192 @@ impl From<evm_service::SimulateEvmTransactionOutput> for rpc::EvmSimulateTransac
         };
 
         Self {
-            execution: rpc::Execution {
-                chain_id,
-                block: block.into(),
-                status,
-                gas_used,
-                gas_limit,
-                fee,
-                burnt_fee,
-                output,
-                failure,
+            chain_id,
+            block: block.into(),
+            status,
+            gas_used,
+            gas_limit,
+            fee,
+            burnt_fee,
+            formatted_fee: None,
+            formatted_burnt_fee: None,
+            output,
+            failure,
+        }
+    }
+}
+
+impl From<evm_service::EvmCallFrame> for rpc::CallFrame {
+    fn from(frame: evm_service::EvmCallFrame) -> Self {
+        Self {
+            call_type: frame.kind.into(),
+            from: frame.from,
+            to: frame.to,
+            value: frame.value,
+            gas: frame.gas,
+            gas_used: frame.gas_used,
+            input: frame.input,
+            output: frame.output,
+            error: frame.error,
+            revert_reason: frame.revert_reason,
+            calls: frame.calls.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+fn map_log((log_index, log): (usize, evm_service::EvmSimulationLog)) -> rpc::Log {
+    rpc::Log {
+        address: log.address,
+        topics: log.topics,
+        data: log.data,
+        log_index: log_index as u64,
+        change_index: log.change_index as u64,
+    }
+}
+
+impl From<evm_service::EvmAccountDiff> for rpc::AccountStateDiff {
+    fn from(account: evm_service::EvmAccountDiff) -> Self {
+        Self {
+            address: account.address,
+            balance: rpc::ValueDiff {
+                before: account.balance.before,
+                after: account.balance.after,
+            },
+            nonce: rpc::ValueDiff {
+                before: U64::from(account.nonce.before),
+                after: U64::from(account.nonce.after),
+            },
+            code_hash: rpc::ValueDiff {
+                before: account.code_hash.before,
+                after: account.code_hash.after,
+            },
+            storage: account
+                .storage
+                .into_iter()
+                .map(|(slot, value)| {
+                    (
+                        B256::from(slot),
+                        rpc::ValueDiff {
+                            before: B256::from(value.before),
+                            after: B256::from(value.after),
+                        },
+                    )
+                })
+                .collect(),
+        }
+    }
+}
+
+impl From<evm_service::EvmCallKind> for rpc::CallType {
+    fn from(kind: evm_service::EvmCallKind) -> Self {
+        match kind {
+            evm_service::EvmCallKind::Call => Self::Call,
+            evm_service::EvmCallKind::CallCode => Self::CallCode,
+            evm_service::EvmCallKind::DelegateCall => Self::DelegateCall,
+            evm_service::EvmCallKind::StaticCall => Self::StaticCall,
+            evm_service::EvmCallKind::Create => Self::Create,
+            evm_service::EvmCallKind::Create2 => Self::Create2,
+        }
+    }
+}
+
+impl From<evm_service::SimulateEvmBundleOutput> for rpc::EvmSimulateBundleResponse {
+    fn from(output: evm_service::SimulateEvmBundleOutput) -> Self {
+        let (transactions, net_changes) = output.into_parts();
+
+        Self {
+            transactions: transactions.into_iter().map(Into::into).collect(),
+            net_changes: net_changes.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl From<evm_service::NetAssetChange> for rpc::NetChange {
+    fn from(change: evm_service::NetAssetChange) -> Self {
+        Self {
+            account: change.account,
+            asset: change.asset.into(),
+            raw_delta: change.raw_delta,
+            formatted_delta: None,
+        }
+    }
+}
+
+impl From<evm_service::BalanceChange> for rpc::BalanceChange {
+    fn from(change: evm_service::BalanceChange) -> Self {
+        Self {
+            account: change.account,
+            asset: change.asset.into(),
+            raw_delta: change.raw_delta,
+            raw_balance_before: change.raw_balance_before,
+            raw_balance_after: change.raw_balance_after,
+            formatted_delta: None,
+            formatted_balance_before: None,
+            formatted_balance_after: None,
+        }
+    }
+}
+
+impl From<evm_service::RiskWarning> for rpc::Warning {
+    fn from(warning: evm_service::RiskWarning) -> Self {
+        Self {
+            code: warning.code.into(),
+            severity: warning.severity.into(),
+            account: warning.account,
+            change_indexes: warning
+                .change_indexes
+                .into_iter()
+                .map(|index| index as u64)
+                .collect(),
+        }
+    }
+}
+
+impl From<evm_service::RiskCode> for rpc::RiskCode {
+    fn from(code: evm_service::RiskCode) -> Self {
+        match code {
+            evm_service::RiskCode::UnlimitedAllowanceToEoa => Self::UnlimitedAllowanceToEoa,
+            evm_service::RiskCode::ApprovalForAllToFreshContract => {
+                Self::ApprovalForAllToFreshContract
+            }
+            evm_service::RiskCode::NativeValueToCodelessTarget => Self::NativeValueToCodelessTarget,
+            evm_service::RiskCode::OwnershipTransfer => Self::OwnershipTransfer,
+            evm_service::RiskCode::WalletDrained => Self::WalletDrained,
+        }
+    }
+}
+
+impl From<evm_service::RiskSeverity> for rpc::RiskSeverity {
+    fn from(severity: evm_service::RiskSeverity) -> Self {
+        match severity {
+            evm_service::RiskSeverity::Low => Self::Low,
+            evm_service::RiskSeverity::Medium => Self::Medium,
+            evm_service::RiskSeverity::High => Self::High,
+        }
+    }
+}
+
+impl From<evm_service::ChangeAsset> for rpc::NetChangeAsset {
+    fn from(asset: evm_service::ChangeAsset) -> Self {
+        match asset {
+            evm_service::ChangeAsset::Native => Self::Native,
+            evm_service::ChangeAsset::Erc20 { contract_address } => {
+                Self::Erc20 { contract_address }
+            }
+            evm_service::ChangeAsset::Erc721 {
+                contract_address,
+                token_id,
+            } => Self::Erc721 {
+                contract_address,
+                token_id,
+            },
+            evm_service::ChangeAsset::Erc1155 {
+                contract_address,
+                token_id,
+            } => Self::Erc1155 {
+                contract_address,
+                token_id,
             },
-            changes: changes.into_iter().map(Into::into).collect(),
         }
     }
 }
@@ -131,6 +346,7 @@ impl From<evm_service::Change> for rpc::Change {
             } => Self::Transfer {
                 asset: rpc::TransferAsset::Native {
                     raw_amount,
+                    formatted_amount: None,
                     metadata: metadata.into(),
                 },
                 from,
@@ -146,6 +362,7 @@ impl From<evm_service::Change> for rpc::Change {
                 asset: rpc::TransferAsset::Erc20 {
                     contract_address,
                     raw_amount,
+                    formatted_amount: None,
                     metadata: metadata.into(),
                 },
                 from,
@@ -160,6 +377,7 @@ impl From<evm_service::Change> for rpc::Change {
                 asset: rpc::TokenMovementAsset::Erc20 {
                     contract_address,
                     raw_amount,
+                    formatted_amount: None,
                     metadata: metadata.into(),
                 },
                 to,
@@ -173,6 +391,7 @@ impl From<evm_service::Change> for rpc::Change {
                 asset: rpc::TokenMovementAsset::Erc20 {
                     contract_address,
                     raw_amount,
+                    formatted_amount: None,
                     metadata: metadata.into(),
                 },
                 from,
@@ -271,10 +490,70 @@ impl From<evm_service::Change> for rpc::Change {
                     contract_address,
                     raw_amount_before,
                     raw_amount_after,
+                    formatted_amount_before: None,
+                    formatted_amount_after: None,
+                    unlimited_before: None,
+                    unlimited_after: None,
+                    metadata: metadata.into(),
+                },
+                owner,
+                spender,
+            },
+            evm_service::Change::Erc20Permit {
+                contract_address,
+                owner,
+                spender,
+                raw_amount_before,
+                raw_amount_after,
+                nonce_before,
+                nonce_after,
+                metadata,
+            } => Self::Permit {
+                asset: rpc::AllowanceAsset::Erc20 {
+                    contract_address,
+                    raw_amount_before,
+                    raw_amount_after,
+                    formatted_amount_before: None,
+                    formatted_amount_after: None,
+                    unlimited_before: None,
+                    unlimited_after: None,
                     metadata: metadata.into(),
                 },
                 owner,
                 spender,
+                nonce_before,
+                nonce_after,
+            },
+            evm_service::Change::Permit2Allowance {
+                contract_address,
+                token,
+                owner,
+                spender,
+                raw_amount_before,
+                raw_amount_after,
+                expiration_before,
+                expiration_after,
+                nonce_before,
+                nonce_after,
+                metadata,
+            } => Self::Permit2Allowance {
+                asset: rpc::AllowanceAsset::Erc20 {
+                    contract_address: token,
+                    raw_amount_before,
+                    raw_amount_after,
+                    formatted_amount_before: None,
+                    formatted_amount_after: None,
+                    unlimited_before: None,
+                    unlimited_after: None,
+                    metadata: metadata.into(),
+                },
+                permit2_address: contract_address,
+                owner,
+                spender,
+                expiration_before: U64::from(expiration_before),
+                expiration_after: U64::from(expiration_after),
+                nonce_before: U64::from(nonce_before),
+                nonce_after: U64::from(nonce_after),
             },
             evm_service::Change::Erc721TokenApproval {
                 contract_address,
@@ -321,6 +600,36 @@ impl From<evm_service::Change> for rpc::Change {
                 approved_before,
                 approved_after,
             },
+            evm_service::Change::Delegation {
+                authority,
+                delegate_before,
+                delegate_after,
+            } => Self::Delegation {
+                authority,
+                delegate_before,
+                delegate_after,
+            },
+            evm_service::Change::ProxyUpgrade {
+                proxy,
+                slot,
+                address_before,
+                address_after,
+            } => Self::ProxyUpgrade {
+                proxy,
+                slot: slot.into(),
+                address_before,
+                address_after,
+            },
+        }
+    }
+}
+
+impl From<evm_service::ProxySlot> for rpc::ProxySlot {
+    fn from(slot: evm_service::ProxySlot) -> Self {
+        match slot {
+            evm_service::ProxySlot::Implementation => Self::Implementation,
+            evm_service::ProxySlot::Admin => Self::Admin,
+            evm_service::ProxySlot::Beacon => Self::Beacon,
         }
     }
 }
@@ -1,7 +1,9 @@
+use alloy::primitives::{B256, U64};
 use jsonrpsee::{core::RpcResult, proc_macros::rpc};
 
 use crate::interface::{
-    BlockRef, EvmSimulateTransactionResponse, SimulateTransactionOptions, Transaction,
+    BlockRef, CreateAccessListOptions, EvmCreateAccessListResponse, EvmSimulateBundleResponse,
+    EvmSimulateTransactionResponse, IncludeField, SimulateTransactionOptions, Transaction,
 };
 
 #[rpc(server)]
@@ -13,4 +15,28 @@ pub trait DryrunRpc {
         block: Option<BlockRef>,
         options: Option<SimulateTransactionOptions>,
     ) -> RpcResult<EvmSimulateTransactionResponse>;
+
+    #[method(name = "dryrun_evm_simulateBundle", param_kind = map)]
+    async fn dryrun_evm_simulate_bundle(
+        &self,
+        transactions: Vec<Transaction>,
+        block: Option<BlockRef>,
+        options: Option<SimulateTransactionOptions>,
+    ) -> RpcResult<EvmSimulateBundleResponse>;
+
+    #[method(name = "dryrun_evm_createAccessList", param_kind = map)]
+    async fn dryrun_evm_create_access_list(
+        &self,
+        transaction: Transaction,
+        block: Option<BlockRef>,
+        options: Option<CreateAccessListOptions>,
+    ) -> RpcResult<EvmCreateAccessListResponse>;
+
+    #[method(name = "dryrun_evm_replayTransaction", param_kind = map)]
+    async fn dryrun_evm_replay_transaction(
+        &self,
+        #[argument(rename = "chainId")] chain_id: U64,
+        #[argument(rename = "transactionHash")] transaction_hash: B256,
+        include: Option<Vec<IncludeField>>,
+    ) -> RpcResult<EvmSimulateTransactionResponse>;
 }
@@ -5,12 +5,18 @@ use tokio::task::JoinError;
 
 #[derive(Debug, Error)]
 pub enum SimulationServiceError {
+    #[error("chain {chain_id} is not configured")]
+    UnsupportedChain { chain_id: u64 },
+
     #[error("block resolution failed: {details}")]
     BlockResolution { details: String },
 
     #[error("transaction completion failed: {details}")]
     TransactionCompletion { details: String },
 
+    #[error("transaction resolution failed: {details}")]
+    TransactionResolution { details: String },
+
     #[error("simulation task set is closed")]
     TaskSetClosed,
 
@@ -42,13 +48,20 @@ impl SimulationServiceError {
     }
 
     pub fn is_not_supported(&self) -> bool {
-        matches!(self, Self::Simulation(error) if error.is_not_supported())
+        match self {
+            Self::UnsupportedChain { .. } => true,
+            Self::Simulation(error) => error.is_not_supported(),
+            _ => false,
+        }
     }
 
     pub fn kind_code(&self) -> Option<&'static str> {
         match self {
+            Self::UnsupportedChain { .. } => Some("unsupported_chain"),
             Self::BlockResolution { .. } => Some("block_resolution_error"),
-            Self::TransactionCompletion { .. } => Some("transaction_resolution_error"),
+            Self::TransactionCompletion { .. } | Self::TransactionResolution { .. } => {
+                Some("transaction_resolution_error")
+            }
             Self::TaskSetClosed => Some("task_set_closed"),
             Self::AttemptTask { .. } => Some("attempt_task_error"),
             Self::ExecutionTask { .. } => Some("execution_task_error"),
@@ -58,8 +71,10 @@ impl SimulationServiceError {
 
     pub fn details(&self) -> String {
         match self {
+            Self::UnsupportedChain { chain_id } => format!("chain {chain_id} is not configured"),
             Self::BlockResolution { details } => details.clone(),
             Self::TransactionCompletion { details } => details.clone(),
+            Self::TransactionResolution { details } => details.clone(),
             Self::TaskSetClosed => "simulation task set is closed".to_owned(),
             Self::AttemptTask { .. } => "simulation attempt task failed".to_owned(),
             Self::ExecutionTask { .. } => "EVM execution task failed".to_owned(),
@@ -84,6 +99,9 @@ impl From<EvmPreparationError> for SimulationServiceError {
             EvmPreparationError::TransactionCompletion { details } => {
                 Self::TransactionCompletion { details }
             }
+            EvmPreparationError::TransactionResolution { details } => {
+                Self::TransactionResolution { details }
+            }
         }
     }
 }
@@ -1,62 +1,227 @@
 mod error;
 
-use std::sync::Arc;
+use std::{collections::BTreeMap, sync::Arc};
 
+use alloy::primitives::B256;
 use evm_simulation::{EvmSimulationPreparer, EvmSimulator};
 use simulation_tasks::SimulationTaskSet;
 
 pub use error::SimulationServiceError;
-pub use evm_simulation::EvmBlockSelector;
 pub use evm_simulation::{
-    AccessListItem, Change, Erc20Metadata, Erc721CollectionMetadata,
+    AccessListItem, BalanceChange, Change, ChangeAsset, Erc20Metadata, Erc721CollectionMetadata,
+    EvmAccessListCreation as CreateEvmAccessListOutput, EvmAccountDiff, EvmAccountOverride,
+    EvmBlockOverrides, EvmBundleSimulation as SimulateEvmBundleOutput, EvmCallFrame, EvmCallKind,
     EvmExecutedDetails as ExecutedDetails, EvmExecution as SimulationExecution,
     EvmExecutionFailure as ExecutionFailure, EvmExecutionFailureCode,
     EvmExecutionOutcome as ExecutionOutcome, EvmSimulation as SimulateEvmTransactionOutput,
-    NativeMetadata, SimulatedBlock,
+    EvmSimulationIncludes, EvmSimulationLog, EvmStateOverrides, EvmStorageOverride, EvmValueDiff,
+    NativeMetadata, NetAssetChange, ProxySlot, RiskCode, RiskConfig, RiskRule, RiskSeverity,
+    RiskWarning, SimulatedBlock,
 };
+pub use evm_simulation::{EvmBlockSelector, config::EvmChainConfig};
 pub use simulation_transaction::TransactionRequest as EvmTransactionRequest;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct SimulateEvmTransactionInput {
     pub block: EvmBlockSelector,
     pub transaction: EvmTransactionRequest,
+    pub state_overrides: EvmStateOverrides,
+    pub block_overrides: EvmBlockOverrides,
+    pub includes: EvmSimulationIncludes,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SimulateEvmBundleInput {
+    pub chain_id: u64,
+    pub block: EvmBlockSelector,
+    pub transactions: Vec<EvmTransactionRequest>,
+    pub state_overrides: EvmStateOverrides,
+    pub block_overrides: EvmBlockOverrides,
+    pub includes: EvmSimulationIncludes,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CreateEvmAccessListInput {
+    pub block: EvmBlockSelector,
+    pub transaction: EvmTransactionRequest,
+    pub state_overrides: EvmStateOverrides,
+    pub block_overrides: EvmBlockOverrides,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReplayEvmTransactionInput {
+    pub chain_id: u64,
+    pub transaction_hash: B256,
+    pub includes: EvmSimulationIncludes,
 }
 
 #[derive(Debug, Clone)]
-pub struct SimulationService {
+pub struct EvmChainBackend {
     preparer: Arc<EvmSimulationPreparer>,
     simulator: Arc<EvmSimulator>,
+}
+
+impl EvmChainBackend {
+    pub fn new(preparer: Arc<EvmSimulationPreparer>, simulator: Arc<EvmSimulator>) -> Self {
+        Self {
+            preparer,
+            simulator,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SimulationService {
+    chains: BTreeMap<u64, EvmChainBackend>,
     simulation_tasks: SimulationTaskSet,
 }
 
 impl SimulationService {
+    /// Creates a service that routes each request to the backend of its
+    /// chain id, as reported by the backend simulator.
     pub fn new(
-        preparer: Arc<EvmSimulationPreparer>,
-        simulator: Arc<EvmSimulator>,
+        chains: impl IntoIterator<Item = EvmChainBackend>,
         simulation_tasks: SimulationTaskSet,
     ) -> Self {
         Self {
-            preparer,
-            simulator,
+            chains: chains
+                .into_iter()
+                .map(|backend| (backend.simulator.chain_id(), backend))
+                .collect(),
             simulation_tasks,
         }
     }
 
+    fn backend(&self, chain_id: u64) -> Result<&EvmChainBackend, SimulationServiceError> {
+        self.chains
+            .get(&chain_id)
+            .ok_or(SimulationServiceError::UnsupportedChain { chain_id })
+    }
+
+    /// Returns the native currency of the chain, which fees are paid in.
+    pub fn native_currency(
+        &self,
+        chain_id: u64,
+    ) -> Result<&NativeMetadata, SimulationServiceError> {
+        Ok(self.backend(chain_id)?.simulator.native_currency())
+    }
+
     pub async fn simulate_evm_transaction(
         &self,
         input: SimulateEvmTransactionInput,
     ) -> Result<SimulateEvmTransactionOutput, SimulationServiceError> {
-        let SimulateEvmTransactionInput { block, transaction } = input;
-        let preparer = Arc::clone(&self.preparer);
-        let simulator = Arc::clone(&self.simulator);
+        let SimulateEvmTransactionInput {
+            block,
+            transaction,
+            state_overrides,
+            block_overrides,
+            includes,
+        } = input;
+        let backend = self.backend(transaction.chain_id)?;
+        let preparer = Arc::clone(&backend.preparer);
+        let simulator = Arc::clone(&backend.simulator);
+
+        self.simulation_tasks
+            .run(move || async move {
+                let prepared = preparer
+                    .prepare_transaction(block, transaction, state_overrides, block_overrides)
+                    .await?;
+
+                let simulation =
+                    tokio::task::spawn_blocking(move || simulator.simulate(prepared, includes))
+                        .await
+                        .map_err(SimulationServiceError::execution_task)??;
+
+                Ok(simulation)
+            })
+            .await?
+    }
+
+    pub async fn simulate_evm_bundle(
+        &self,
+        input: SimulateEvmBundleInput,
+    ) -> Result<SimulateEvmBundleOutput, SimulationServiceError> {
+        let SimulateEvmBundleInput {
+            chain_id,
+            block,
+            transactions,
+            state_overrides,
+            block_overrides,
+            includes,
+        } = input;
+        let backend = self.backend(chain_id)?;
+        let preparer = Arc::clone(&backend.preparer);
+        let simulator = Arc::clone(&backend.simulator);
+
+        self.simulation_tasks
+            .run(move || async move {
+                let prepared = preparer
+                    .prepare_bundle(block, transactions, state_overrides, block_overrides)
+                    .await?;
+
+                let simulation = tokio::task::spawn_blocking(move || {
+                    simulator.simulate_bundle(prepared, includes)
+                })
+                .await
+                .map_err(SimulationServiceError::execution_task)??;
+
+                Ok(simulation)
+            })
+            .await?
+    }
+
+    pub async fn create_evm_access_list(
+        &self,
+        input: CreateEvmAccessListInput,
+    ) -> Result<CreateEvmAccessListOutput, SimulationServiceError> {
+        let CreateEvmAccessListInput {
+            block,
+            transaction,
+            state_overrides,
+            block_overrides,
+        } = input;
+        let backend = self.backend(transaction.chain_id)?;
+        let preparer = Arc::clone(&backend.preparer);
+        let simulator = Arc::clone(&backend.simulator);
+
+        self.simulation_tasks
+            .run(move || async move {
+                let prepared = preparer
+                    .prepare_transaction(block, transaction, state_overrides, block_overrides)
+                    .await?;
+
+                let creation =
+                    tokio::task::spawn_blocking(move || simulator.create_access_list(prepared))
+                        .await
+                        .map_err(SimulationServiceError::execution_task)??;
+
+                Ok(creation)
+            })
+            .await?
+    }
+
+    pub async fn replay_evm_transaction(
+        &self,
+        input: ReplayEvmTransactionInput,
+    ) -> Result<SimulateEvmTransactionOutput, SimulationServiceError> {
+        let ReplayEvmTransactionInput {
+            chain_id,
+            transaction_hash,
+            includes,
+        } = input;
+        let backend = self.backend(chain_id)?;
+        let preparer = Arc::clone(&backend.preparer);
+        let simulator = Arc::clone(&backend.simulator);
 
         self.simulation_tasks
             .run(move || async move {
-                let prepared = preparer.prepare_transaction(block, transaction).await?;
+                let prepared = preparer.prepare_replay(transaction_hash).await?;
 
-                let simulation = tokio::task::spawn_blocking(move || simulator.simulate(prepared))
-                    .await
-                    .map_err(SimulationServiceError::execution_task)??;
+                let simulation = tokio::task::spawn_blocking(move || {
+                    simulator.simulate_replay(prepared, includes)
+                })
+                .await
+                .map_err(SimulationServiceError::execution_task)??;
 
                 Ok(simulation)
             })
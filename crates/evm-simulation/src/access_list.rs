@@ -0,0 +1,103 @@
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+
+use alloy::primitives::{Address, B256};
+use simulation_transaction::{AccessListItem, Transaction, TransactionVariant};
+
+use crate::EvmExecution;
+
+// EIP-2930 charges for every listed address and storage key; EIP-2929 makes
+// the first access of an unlisted account or slot cold.
+const ACCESS_LIST_ADDRESS_COST: u64 = 2_400;
+const ACCESS_LIST_STORAGE_KEY_COST: u64 = 1_900;
+const COLD_ACCOUNT_ACCESS_SAVING: u64 = 2_600 - 100;
+const COLD_SLOAD_SAVING: u64 = 2_100 - 100;
+
+/// An EIP-2930 access list generated by executing the transaction, with the
+/// gas the transaction used without and with it at the same block.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EvmAccessListCreation {
+    /// Execution of the transaction carrying `access_list`.
+    pub execution: EvmExecution,
+    pub access_list: Vec<AccessListItem>,
+    pub gas_used_without_access_list: u64,
+    pub gas_used_with_access_list: u64,
+}
+
+pub(crate) type TouchedState = BTreeMap<Address, BTreeSet<B256>>;
+
+/// Lists the touched accounts whose entry costs less than the cold accesses
+/// it saves. Warm accounts are only worth listing for many storage keys.
+pub(crate) fn optimal_access_list(
+    touched: TouchedState,
+    warm: &HashSet<Address>,
+) -> Vec<AccessListItem> {
+    touched
+        .into_iter()
+        .filter(|(address, storage_keys)| saves_gas(warm.contains(address), storage_keys.len()))
+        .map(|(address, storage_keys)| AccessListItem {
+            address,
+            storage_keys: storage_keys.into_iter().collect(),
+        })
+        .collect()
+}
+
+fn saves_gas(warm: bool, storage_keys: usize) -> bool {
+    let storage_keys = storage_keys as u64;
+    let cost = ACCESS_LIST_ADDRESS_COST + storage_keys * ACCESS_LIST_STORAGE_KEY_COST;
+    let account_saving = if warm { 0 } else { COLD_ACCOUNT_ACCESS_SAVING };
+
+    account_saving + storage_keys * COLD_SLOAD_SAVING > cost
+}
+
+/// Replaces the access list of the transaction. Legacy transactions cannot
+/// carry one and become EIP-2930 transactions at the same gas price.
+pub(crate) fn with_access_list(
+    transaction: &Transaction,
+    access_list: Vec<AccessListItem>,
+) -> Transaction {
+    let variant = match transaction.variant.clone() {
+        TransactionVariant::Legacy { gas_price }
+        | TransactionVariant::AccessList { gas_price, .. } => TransactionVariant::AccessList {
+            gas_price,
+            access_list,
+        },
+        TransactionVariant::DynamicFee {
+            max_fee_per_gas,
+            max_priority_fee_per_gas,
+            ..
+        } => TransactionVariant::DynamicFee {
+            max_fee_per_gas,
+            max_priority_fee_per_gas,
+            access_list,
+        },
+        TransactionVariant::Blob {
+            max_fee_per_gas,
+            max_priority_fee_per_gas,
+            max_fee_per_blob_gas,
+            blob_versioned_hashes,
+            ..
+        } => TransactionVariant::Blob {
+            max_fee_per_gas,
+            max_priority_fee_per_gas,
+            max_fee_per_blob_gas,
+            access_list,
+            blob_versioned_hashes,
+        },
+        TransactionVariant::SetCode {
+            max_fee_per_gas,
+            max_priority_fee_per_gas,
+            authorization_list,
+            ..
+        } => TransactionVariant::SetCode {
+            max_fee_per_gas,
+            max_priority_fee_per_gas,
+            access_list,
+            authorization_list,
+        },
+    };
+
+    Transaction {
+        variant,
+        ..transaction.clone()
+    }
+}
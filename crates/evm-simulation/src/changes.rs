@@ -1,10 +1,14 @@
+mod delegation;
 mod error;
 mod native;
+mod proxy;
 mod standards;
 
 pub use error::EvmNativeChangeError;
 pub use native::analyze_native_changes;
 
+pub(crate) use delegation::analyze_delegation_changes;
+pub(crate) use proxy::analyze_proxy_changes;
 pub(crate) use standards::{
     collect_standard_candidates, load_standard_metadata, read_standard_state_values,
 };
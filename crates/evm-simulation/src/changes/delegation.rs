@@ -0,0 +1,79 @@
+use std::collections::BTreeMap;
+
+use alloy::{eips::eip7702::constants::EIP7702_DELEGATION_DESIGNATOR, primitives::Address};
+use contract_standards::Position;
+use revm::{Database, handler::EvmTr, state::Bytecode};
+use simulation_changes::{Change, PositionedChange};
+use simulation_transaction::{Transaction, TransactionVariant};
+
+use crate::{EvmExecutionObserver, EvmExecutionOutput, EvmSimulationError};
+
+const DELEGATION_CODE_LEN: usize = EIP7702_DELEGATION_DESIGNATOR.len() + Address::len_bytes();
+
+/// Reports authorities whose EIP-7702 delegation the transaction set, replaced
+/// or cleared. Authorizations apply before execution starts, so every change
+/// shares the first position and stays ahead of execution changes when sorted.
+pub(crate) fn analyze_delegation_changes(
+    output: &mut EvmExecutionOutput<EvmExecutionObserver>,
+    transaction: &Transaction,
+) -> Result<Vec<PositionedChange>, EvmSimulationError> {
+    if !matches!(transaction.variant, TransactionVariant::SetCode { .. }) {
+        return Ok(Vec::new());
+    }
+
+    let transition = output.transition().map_err(|_| {
+        EvmSimulationError::execution_error(
+            "transaction execution transition was unavailable during delegation analysis",
+        )
+    })?;
+    let code_updates = transition
+        .iter()
+        .filter(|(_, account)| account.info.code_hash != account.original_info.code_hash)
+        .map(|(address, account)| {
+            let original = (
+                account.original_info.code_hash,
+                account.original_info.code.clone(),
+            );
+            (*address, original, account.info.code.clone())
+        })
+        .collect::<Vec<_>>();
+
+    let database = &mut output.evm_mut().ctx_mut().journaled_state.database;
+    let mut delegations = BTreeMap::new();
+
+    for (authority, (original_code_hash, original_code), code) in code_updates {
+        let original_code = match original_code {
+            Some(code) => code,
+            None => database.code_by_hash(original_code_hash).map_err(|error| {
+                EvmSimulationError::state_access_error(format!(
+                    "failed to read original code of {authority}: {error}"
+                ))
+            })?,
+        };
+        let delegate_before = delegated_address(&original_code);
+        let delegate_after = code.as_ref().and_then(delegated_address);
+
+        // Code changes that involve no designator are contract deployments.
+        if delegate_before.is_some() || delegate_after.is_some() {
+            delegations.insert(
+                authority,
+                Change::Delegation {
+                    authority,
+                    delegate_before,
+                    delegate_after,
+                },
+            );
+        }
+    }
+
+    Ok(delegations
+        .into_values()
+        .map(|change| PositionedChange::new(Position::new(0, 0), change))
+        .collect())
+}
+
+fn delegated_address(code: &Bytecode) -> Option<Address> {
+    let code = code.original_byte_slice();
+    (code.len() == DELEGATION_CODE_LEN && code.starts_with(&EIP7702_DELEGATION_DESIGNATOR))
+        .then(|| Address::from_slice(&code[EIP7702_DELEGATION_DESIGNATOR.len()..]))
+}
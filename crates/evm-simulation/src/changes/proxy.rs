@@ -0,0 +1,76 @@
+use std::collections::BTreeMap;
+
+use alloy::primitives::Address;
+use contract_standards::{Position, ProxySlot, proxy_slot_address};
+use simulation_changes::{Change, PositionedChange};
+
+use crate::{
+    EvmExecutionObservation, EvmExecutionObserver, EvmExecutionOutput, EvmSimulationError,
+};
+
+/// Reports EIP-1967 implementation, admin and beacon slots that the
+/// transaction rewrote. A change takes the position of the last matching
+/// `Upgraded`, `AdminChanged` or `BeaconUpgraded` log of the proxy, or sorts
+/// after every execution change when the proxy emitted none.
+pub(crate) fn analyze_proxy_changes(
+    output: &EvmExecutionOutput<EvmExecutionObserver>,
+    observations: &[EvmExecutionObservation],
+) -> Result<Vec<PositionedChange>, EvmSimulationError> {
+    let transition = output.transition().map_err(|_| {
+        EvmSimulationError::execution_error(
+            "transaction execution transition was unavailable during proxy analysis",
+        )
+    })?;
+
+    let mut upgrades = BTreeMap::new();
+    for (proxy, account) in transition {
+        if account.is_selfdestructed() {
+            continue;
+        }
+
+        for slot in ProxySlot::ALL {
+            let Some(value) = account.storage.get(&slot.storage_key()) else {
+                continue;
+            };
+            let address_before = proxy_slot_address(value.original_value);
+            let address_after = proxy_slot_address(value.present_value);
+            if address_before == address_after {
+                continue;
+            }
+
+            let position = event_position(observations, *proxy, slot)
+                .unwrap_or(Position::new(observations.len(), 0));
+            upgrades.insert(
+                (*proxy, slot),
+                PositionedChange::new(
+                    position,
+                    Change::ProxyUpgrade {
+                        proxy: *proxy,
+                        slot,
+                        address_before,
+                        address_after,
+                    },
+                ),
+            );
+        }
+    }
+
+    Ok(upgrades.into_values().collect())
+}
+
+fn event_position(
+    observations: &[EvmExecutionObservation],
+    proxy: Address,
+    slot: ProxySlot,
+) -> Option<Position> {
+    observations
+        .iter()
+        .rposition(|observation| {
+            matches!(
+                observation,
+                EvmExecutionObservation::Log { address, topics, .. }
+                    if *address == proxy && topics.first() == Some(&slot.event_topic())
+            )
+        })
+        .map(|index| Position::new(index, 0))
+}
@@ -1,11 +1,12 @@
 use alloy::sol_types::SolCall;
 use alloy_primitives::{Address, B256, FixedBytes};
 use contract_standards::{
-    CollectionStandards, ERC165_INTERFACE_ID, ERC721_INTERFACE_ID, ERC1155_INTERFACE_ID,
-    Erc20AllowanceCall, Erc20BalanceCall, Erc20TotalSupplyCall, Erc721GetApprovedCall,
-    Erc721OwnerCall, Erc721TokenKey, Erc721TokenState, Erc1155BalanceCall,
-    INVALID_ERC165_INTERFACE_ID, OperatorApprovalCall, StandardStateValues, StateRequirements,
-    SupportsInterfaceCall,
+    BeaconImplementationCall, CollectionStandards, ERC165_INTERFACE_ID, ERC721_INTERFACE_ID,
+    ERC1155_INTERFACE_ID, Erc20AllowanceCall, Erc20BalanceCall, Erc20PermitNonceCall,
+    Erc20TotalSupplyCall, Erc721GetApprovedCall, Erc721OwnerCall, Erc721TokenKey, Erc721TokenState,
+    Erc1155BalanceCall, INVALID_ERC165_INTERFACE_ID, OperatorApprovalCall, Permit2AllowanceCall,
+    ProxyImplementation, ProxySlot, StandardStateValues, StateRequirements, SupportsInterfaceCall,
+    proxy_slot_address,
 };
 use revm::{Database, context_interface::result::EVMError, handler::EvmTr};
 
@@ -44,6 +45,13 @@ where
         values
             .contract_code_hashes
             .insert(contract, read_contract_code_hash(evm, contract)?);
+        if let Some(implementation) =
+            read_proxy_implementation(evm, transaction, chain_id, contract)?
+        {
+            values
+                .proxy_implementations
+                .insert(contract, implementation);
+        }
     }
 
     for &collection in &requirements.collection_standards {
@@ -86,6 +94,32 @@ where
         values.erc20_allowances.insert(key, allowance);
     }
 
+    for &key in &requirements.erc20_permit_nonces {
+        let nonce = read_required_value(
+            evm,
+            transaction,
+            chain_id,
+            key.token,
+            Erc20PermitNonceCall { owner: key.owner },
+        )?;
+        values.erc20_permit_nonces.insert(key, nonce);
+    }
+
+    for &key in &requirements.permit2_allowances {
+        let allowance = read_required_value(
+            evm,
+            transaction,
+            chain_id,
+            key.permit2,
+            Permit2AllowanceCall {
+                user: key.owner,
+                token: key.token,
+                spender: key.spender,
+            },
+        )?;
+        values.permit2_allowances.insert(key, allowance.into());
+    }
+
     for &key in &requirements.erc721_tokens {
         values.erc721_tokens.insert(
             key,
@@ -186,6 +220,27 @@ where
     })
 }
 
+/// Like [`read_required_value`], but a call that reverts, halts or returns
+/// undecodable data yields `None` instead of failing the analysis.
+fn read_optional_value<DB, INSP, C>(
+    evm: &mut MainnetEvmWithDb<DB, INSP>,
+    transaction: &EvmTransaction,
+    chain_id: u64,
+    target: Address,
+    call: C,
+) -> Result<Option<C::Return>, EvmSimulationError>
+where
+    DB: Database,
+    C: SolCall,
+{
+    match execute_token_state_call(evm, transaction, chain_id, target, call)? {
+        ReadCallOutcome::Success(output) => {
+            Ok(C::abi_decode_returns_validate(output.as_ref()).ok())
+        }
+        ReadCallOutcome::Revert(_) | ReadCallOutcome::Halt(_) => Ok(None),
+    }
+}
+
 fn read_interface_support<DB, INSP>(
     evm: &mut MainnetEvmWithDb<DB, INSP>,
     transaction: &EvmTransaction,
@@ -299,6 +354,64 @@ where
     Ok(code_hash)
 }
 
+/// Resolves the implementation behind an EIP-1967 proxy, directly or through
+/// its beacon. Contracts with neither slot set are not proxies, and neither
+/// are contracts whose beacon does not answer `implementation()`.
+fn read_proxy_implementation<DB, INSP>(
+    evm: &mut MainnetEvmWithDb<DB, INSP>,
+    transaction: &EvmTransaction,
+    chain_id: u64,
+    contract: Address,
+) -> Result<Option<ProxyImplementation>, EvmSimulationError>
+where
+    DB: Database,
+{
+    let address = match read_proxy_slot(evm, contract, ProxySlot::Implementation)? {
+        Some(implementation) => implementation,
+        None => {
+            let Some(beacon) = read_proxy_slot(evm, contract, ProxySlot::Beacon)? else {
+                return Ok(None);
+            };
+            let Some(implementation) = read_optional_value(
+                evm,
+                transaction,
+                chain_id,
+                beacon,
+                BeaconImplementationCall {},
+            )?
+            else {
+                return Ok(None);
+            };
+            implementation
+        }
+    };
+
+    Ok(Some(ProxyImplementation {
+        address,
+        code_hash: read_contract_code_hash(evm, address)?,
+    }))
+}
+
+fn read_proxy_slot<DB, INSP>(
+    evm: &mut MainnetEvmWithDb<DB, INSP>,
+    contract: Address,
+    slot: ProxySlot,
+) -> Result<Option<Address>, EvmSimulationError>
+where
+    DB: Database,
+{
+    let database = &mut evm.ctx_mut().journaled_state.database;
+    let value = database
+        .storage(contract, slot.storage_key())
+        .map_err(|error| {
+            EvmSimulationError::state_access_error(format!(
+                "failed to read proxy slot of token contract {contract}: {error}",
+            ))
+        })?;
+
+    Ok(proxy_slot_address(value))
+}
+
 fn read_erc721_token_state<DB, INSP>(
     evm: &mut MainnetEvmWithDb<DB, INSP>,
     transaction: &EvmTransaction,
@@ -0,0 +1,144 @@
+use alloy::eips::{eip1559::BaseFeeParams, eip7840::BlobParams};
+use alloy_chains::{Chain, NamedChain};
+pub use alloy_hardforks::{EthereumHardfork, ForkCondition};
+
+use crate::NativeMetadata;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EvmChainConfig {
+    pub chain_id: u64,
+    pub hardforks: Vec<(EthereumHardfork, ForkCondition)>,
+    pub native_currency: NativeMetadata,
+    /// Seconds between blocks, used to time the synthetic pending block.
+    pub block_interval_secs: u64,
+    pub base_fee_params: BaseFeeParams,
+}
+
+// Post-merge Ethereum networks produce a block every slot.
+const ETHEREUM_BLOCK_INTERVAL_SECS: u64 = 12;
+
+impl EvmChainConfig {
+    pub fn mainnet() -> Self {
+        Self {
+            chain_id: Chain::mainnet().id(),
+            hardforks: EthereumHardfork::mainnet().to_vec(),
+            native_currency: native_currency("Ether", "ETH"),
+            block_interval_secs: ETHEREUM_BLOCK_INTERVAL_SECS,
+            base_fee_params: BaseFeeParams::ethereum(),
+        }
+    }
+
+    pub fn sepolia() -> Self {
+        Self {
+            chain_id: Chain::sepolia().id(),
+            hardforks: EthereumHardfork::sepolia().to_vec(),
+            native_currency: native_currency("Sepolia Ether", "ETH"),
+            block_interval_secs: ETHEREUM_BLOCK_INTERVAL_SECS,
+            base_fee_params: BaseFeeParams::ethereum(),
+        }
+    }
+
+    pub fn holesky() -> Self {
+        Self {
+            chain_id: Chain::holesky().id(),
+            hardforks: EthereumHardfork::holesky().to_vec(),
+            native_currency: native_currency("Holesky Ether", "ETH"),
+            block_interval_secs: ETHEREUM_BLOCK_INTERVAL_SECS,
+            base_fee_params: BaseFeeParams::ethereum(),
+        }
+    }
+
+    /// Returns the built-in configuration for chains with a known hardfork
+    /// schedule.
+    pub fn known(chain_id: u64) -> Option<Self> {
+        match NamedChain::try_from(chain_id).ok()? {
+            NamedChain::Mainnet => Some(Self::mainnet()),
+            NamedChain::Sepolia => Some(Self::sepolia()),
+            NamedChain::Holesky => Some(Self::holesky()),
+            _ => None,
+        }
+    }
+
+    /// Builds a configuration for a chain without a built-in schedule. The
+    /// hardforks may be given in any order. Block timing and base fee
+    /// parameters start as Ethereum's.
+    pub fn custom(
+        chain_id: u64,
+        mut hardforks: Vec<(EthereumHardfork, ForkCondition)>,
+        native_currency: NativeMetadata,
+    ) -> Self {
+        hardforks.sort_by_key(|(hardfork, _)| *hardfork);
+
+        Self {
+            chain_id,
+            hardforks,
+            native_currency,
+            block_interval_secs: ETHEREUM_BLOCK_INTERVAL_SECS,
+            base_fee_params: BaseFeeParams::ethereum(),
+        }
+    }
+
+    pub(crate) fn active_hardfork(&self, block_number: u64, timestamp: u64) -> EthereumHardfork {
+        self.hardforks
+            .iter()
+            .rev()
+            .find_map(|(hardfork, condition)| {
+                condition
+                    .active_at_timestamp_or_number(timestamp, block_number)
+                    .then_some(*hardfork)
+            })
+            .unwrap_or(EthereumHardfork::Frontier)
+    }
+
+    /// Returns the blob fee parameters of the block, or `None` before
+    /// Cancun. Blob parameter only forks after BPO2 have no parameters yet
+    /// and keep those of BPO2.
+    pub(crate) fn blob_params(&self, block_number: u64, timestamp: u64) -> Option<BlobParams> {
+        match self.active_hardfork(block_number, timestamp) {
+            hardfork if hardfork >= EthereumHardfork::Bpo2 => Some(BlobParams::bpo2()),
+            hardfork if hardfork >= EthereumHardfork::Bpo1 => Some(BlobParams::bpo1()),
+            hardfork if hardfork >= EthereumHardfork::Osaka => Some(BlobParams::osaka()),
+            hardfork if hardfork >= EthereumHardfork::Prague => Some(BlobParams::prague()),
+            hardfork if hardfork >= EthereumHardfork::Cancun => Some(BlobParams::cancun()),
+            _ => None,
+        }
+    }
+}
+
+fn native_currency(name: &str, symbol: &str) -> NativeMetadata {
+    NativeMetadata {
+        name: Some(name.to_string()),
+        symbol: Some(symbol.to_string()),
+        decimals: Some(18),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Any post-merge mainnet block number; the forks below are timestamp based.
+    const BLOCK_NUMBER: u64 = 23_000_000;
+
+    fn mainnet_blob_params(hardfork: EthereumHardfork) -> Option<BlobParams> {
+        let timestamp = hardfork
+            .mainnet_activation_timestamp()
+            .expect("timestamp activated fork");
+        EvmChainConfig::mainnet().blob_params(BLOCK_NUMBER, timestamp)
+    }
+
+    #[test]
+    fn blob_parameter_only_forks_raise_the_blob_limits() {
+        let osaka = mainnet_blob_params(EthereumHardfork::Osaka).unwrap();
+        let bpo1 = mainnet_blob_params(EthereumHardfork::Bpo1).unwrap();
+        let bpo2 = mainnet_blob_params(EthereumHardfork::Bpo2).unwrap();
+
+        assert_eq!(osaka, BlobParams::osaka());
+        assert_eq!(bpo1, BlobParams::bpo1());
+        assert_eq!(bpo2, BlobParams::bpo2());
+        assert_eq!(
+            [osaka, bpo1, bpo2].map(|params| params.max_blob_count),
+            [9, 15, 21]
+        );
+    }
+}
@@ -0,0 +1,105 @@
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+
+use alloy::{
+    consensus::Header,
+    primitives::{Address, B256},
+};
+use revm::{
+    Inspector,
+    bytecode::opcode,
+    interpreter::{Interpreter, interpreter_types::Jumps},
+    precompile::{PrecompileSpecId, Precompiles},
+    primitives::hardfork::SpecId,
+};
+use simulation_transaction::{Transaction, TransactionVariant};
+
+use super::{EvmExecutionError, chain_spec::resolve_execution_spec_id};
+use crate::config::EvmChainConfig;
+
+/// Collects every account and storage slot a transaction accesses,
+/// including accesses of frames that later revert.
+#[derive(Debug, Default)]
+pub struct EvmAccessListInspector {
+    touched: BTreeMap<Address, BTreeSet<B256>>,
+}
+
+impl EvmAccessListInspector {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn into_touched(self) -> BTreeMap<Address, BTreeSet<B256>> {
+        self.touched
+    }
+
+    fn touch_account(&mut self, word: B256) {
+        self.touched.entry(Address::from_word(word)).or_default();
+    }
+}
+
+impl<CTX> Inspector<CTX> for EvmAccessListInspector {
+    fn step(&mut self, interpreter: &mut Interpreter, _context: &mut CTX) {
+        match interpreter.bytecode.opcode() {
+            opcode::SLOAD | opcode::SSTORE => {
+                if let Ok(slot) = interpreter.stack.peek(0) {
+                    self.touched
+                        .entry(interpreter.input.target_address)
+                        .or_default()
+                        .insert(B256::from(slot));
+                }
+            }
+            opcode::BALANCE
+            | opcode::EXTCODESIZE
+            | opcode::EXTCODECOPY
+            | opcode::EXTCODEHASH
+            | opcode::SELFDESTRUCT => {
+                if let Ok(address) = interpreter.stack.peek(0) {
+                    self.touch_account(B256::from(address));
+                }
+            }
+            opcode::CALL | opcode::CALLCODE | opcode::DELEGATECALL | opcode::STATICCALL => {
+                if let Ok(address) = interpreter.stack.peek(1) {
+                    self.touch_account(B256::from(address));
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Accounts that are warm before the first instruction runs, so an access
+/// list entry for them only pays off through its storage keys: the sender,
+/// the recipient or created contract, EIP-7702 authorities, precompiles and,
+/// from Shanghai, the coinbase.
+pub(crate) fn warm_addresses(
+    chain: &EvmChainConfig,
+    header: &Header,
+    transaction: &Transaction,
+) -> Result<HashSet<Address>, EvmExecutionError> {
+    let spec_id = resolve_execution_spec_id(chain, header.number, header.timestamp)?;
+    let mut warm = Precompiles::new(PrecompileSpecId::from_spec_id(spec_id))
+        .addresses()
+        .copied()
+        .collect::<HashSet<_>>();
+    warm.insert(transaction.from);
+    warm.insert(
+        transaction
+            .to
+            .unwrap_or_else(|| transaction.from.create(transaction.nonce)),
+    );
+    if spec_id.is_enabled_in(SpecId::SHANGHAI) {
+        warm.insert(header.beneficiary);
+    }
+    if let TransactionVariant::SetCode {
+        authorization_list, ..
+    } = &transaction.variant
+    {
+        warm.extend(
+            authorization_list
+                .iter()
+                .filter_map(|authorization| authorization.recover_authority().ok()),
+        );
+    }
+
+    Ok(warm)
+}
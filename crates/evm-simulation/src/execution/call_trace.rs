@@ -0,0 +1,128 @@
+use alloy::primitives::{Address, Bytes, U256};
+use revm::{
+    context_interface::result::HaltReason,
+    interpreter::{CallScheme, CreateScheme, InstructionResult, SuccessOrHalt},
+};
+
+use crate::outcome::decode_revert_reason;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EvmCallKind {
+    Call,
+    CallCode,
+    DelegateCall,
+    StaticCall,
+    Create,
+    Create2,
+}
+
+impl EvmCallKind {
+    pub(super) fn from_call_scheme(scheme: CallScheme) -> Self {
+        match scheme {
+            CallScheme::Call => Self::Call,
+            CallScheme::CallCode => Self::CallCode,
+            CallScheme::DelegateCall => Self::DelegateCall,
+            CallScheme::StaticCall => Self::StaticCall,
+        }
+    }
+
+    pub(super) fn from_create_scheme(scheme: CreateScheme) -> Self {
+        match scheme {
+            CreateScheme::Create2 { .. } => Self::Create2,
+            _ => Self::Create,
+        }
+    }
+}
+
+/// One call frame of an executed transaction. Frames of a reverted parent are
+/// kept so the trace shows where execution went wrong.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EvmCallFrame {
+    pub kind: EvmCallKind,
+    pub from: Address,
+    /// The called account, or the created one once a CREATE frame succeeds.
+    pub to: Option<Address>,
+    pub value: U256,
+    pub input: Bytes,
+    pub output: Bytes,
+    pub gas: u64,
+    pub gas_used: u64,
+    pub error: Option<String>,
+    pub revert_reason: Option<String>,
+    pub calls: Vec<EvmCallFrame>,
+}
+
+impl EvmCallFrame {
+    pub(super) fn new(
+        kind: EvmCallKind,
+        from: Address,
+        to: Option<Address>,
+        value: U256,
+        input: Bytes,
+        gas: u64,
+    ) -> Self {
+        Self {
+            kind,
+            from,
+            to,
+            value,
+            input,
+            output: Bytes::new(),
+            gas,
+            gas_used: 0,
+            error: None,
+            revert_reason: None,
+            calls: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub(super) struct CallTracer {
+    stack: Vec<EvmCallFrame>,
+    root: Option<EvmCallFrame>,
+}
+
+impl CallTracer {
+    pub(super) fn enter(&mut self, frame: EvmCallFrame) {
+        self.stack.push(frame);
+    }
+
+    pub(super) fn exit(
+        &mut self,
+        result: InstructionResult,
+        output: &Bytes,
+        gas_used: u64,
+        created_address: Option<Address>,
+    ) {
+        let Some(mut frame) = self.stack.pop() else {
+            return;
+        };
+
+        frame.output = output.clone();
+        frame.gas_used = gas_used;
+        if created_address.is_some() {
+            frame.to = created_address;
+        }
+        match SuccessOrHalt::<HaltReason>::from(result) {
+            SuccessOrHalt::Success(_) => {}
+            SuccessOrHalt::Revert => {
+                frame.error = Some("execution reverted".to_string());
+                frame.revert_reason = decode_revert_reason(output);
+            }
+            SuccessOrHalt::Halt(reason) => frame.error = Some(reason.to_string()),
+            SuccessOrHalt::FatalExternalError | SuccessOrHalt::Internal(_) => {
+                frame.error = Some(format!("{result:?}"));
+            }
+        }
+
+        match self.stack.last_mut() {
+            Some(parent) => parent.calls.push(frame),
+            None => self.root = Some(frame),
+        }
+    }
+
+    pub(super) fn root(&self) -> Option<&EvmCallFrame> {
+        self.root.as_ref()
+    }
+}
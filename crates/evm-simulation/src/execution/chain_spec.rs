@@ -1,29 +1,15 @@
-use alloy_chains::Chain;
 use alloy_hardforks::EthereumHardfork;
 use revm::primitives::hardfork::SpecId;
 
 use super::EvmExecutionError;
+use crate::config::EvmChainConfig;
 
 pub(super) fn resolve_execution_spec_id(
-    chain_id: u64,
+    chain: &EvmChainConfig,
     block_number: u64,
     timestamp: u64,
 ) -> Result<SpecId, EvmExecutionError> {
-    if chain_id != Chain::mainnet().id() {
-        return Err(EvmExecutionError::UnsupportedChain(chain_id));
-    }
-
-    let hardfork = EthereumHardfork::mainnet()
-        .iter()
-        .rev()
-        .find_map(|(hardfork, condition)| {
-            condition
-                .active_at_timestamp_or_number(timestamp, block_number)
-                .then_some(*hardfork)
-        })
-        .unwrap_or(EthereumHardfork::Frontier);
-
-    map_hardfork_to_spec_id(hardfork)
+    map_hardfork_to_spec_id(chain.active_hardfork(block_number, timestamp))
 }
 
 fn map_hardfork_to_spec_id(hardfork: EthereumHardfork) -> Result<SpecId, EvmExecutionError> {
@@ -1,9 +1,11 @@
 use alloy::consensus::{BlockHeader, Header};
-use alloy::primitives::U256;
+use alloy::eips::eip7840::BlobParams;
+use alloy::primitives::{Address, B256, U256};
 use revm::{
     context::{BlockEnv, CfgEnv, TxEnv},
     context_interface::{
         block::BlobExcessGasAndPrice,
+        either::Either,
         transaction::{
             AccessList as RevmAccessList, AccessListItem as RevmAccessListItem, TransactionType,
         },
@@ -13,6 +15,91 @@ use revm::{
 use simulation_transaction::{AccessListItem, Transaction, TransactionVariant};
 
 use super::EvmExecutionError;
+use crate::config::EvmChainConfig;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EvmBlockOverrides {
+    pub number: Option<u64>,
+    pub timestamp: Option<u64>,
+    pub base_fee_per_gas: Option<u64>,
+    pub coinbase: Option<Address>,
+    pub gas_limit: Option<u64>,
+    pub prevrandao: Option<B256>,
+    pub excess_blob_gas: Option<u64>,
+}
+
+impl EvmBlockOverrides {
+    pub fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+
+    /// Returns overrides that turn `parent` into a synthetic header for the
+    /// block that follows it, with the EIP-1559 and EIP-4844 fee updates
+    /// applied. Coinbase, gas limit and prevrandao stay those of the parent.
+    pub fn next_block(parent: &Header, chain: &EvmChainConfig) -> Self {
+        let number = parent.number.saturating_add(1);
+        let timestamp = parent.timestamp.saturating_add(chain.block_interval_secs);
+
+        Self {
+            number: Some(number),
+            timestamp: Some(timestamp),
+            base_fee_per_gas: parent.next_block_base_fee(chain.base_fee_params),
+            excess_blob_gas: chain
+                .blob_params(number, timestamp)
+                .and_then(|blob_params| parent.next_block_excess_blob_gas(blob_params)),
+            ..Self::default()
+        }
+    }
+
+    /// Combines two sets of overrides, keeping the fields set in `self`.
+    pub fn or(self, fallback: Self) -> Self {
+        Self {
+            number: self.number.or(fallback.number),
+            timestamp: self.timestamp.or(fallback.timestamp),
+            base_fee_per_gas: self.base_fee_per_gas.or(fallback.base_fee_per_gas),
+            coinbase: self.coinbase.or(fallback.coinbase),
+            gas_limit: self.gas_limit.or(fallback.gas_limit),
+            prevrandao: self.prevrandao.or(fallback.prevrandao),
+            excess_blob_gas: self.excess_blob_gas.or(fallback.excess_blob_gas),
+        }
+    }
+
+    /// Returns the header the transaction executes against. The anchored
+    /// header still identifies the state the execution reads from.
+    pub fn apply(&self, header: &Header) -> Header {
+        let mut header = header.clone();
+
+        if let Some(number) = self.number {
+            header.number = number;
+        }
+
+        if let Some(timestamp) = self.timestamp {
+            header.timestamp = timestamp;
+        }
+
+        if let Some(base_fee_per_gas) = self.base_fee_per_gas {
+            header.base_fee_per_gas = Some(base_fee_per_gas);
+        }
+
+        if let Some(coinbase) = self.coinbase {
+            header.beneficiary = coinbase;
+        }
+
+        if let Some(gas_limit) = self.gas_limit {
+            header.gas_limit = gas_limit;
+        }
+
+        if let Some(prevrandao) = self.prevrandao {
+            header.mix_hash = prevrandao;
+        }
+
+        if let Some(excess_blob_gas) = self.excess_blob_gas {
+            header.excess_blob_gas = Some(excess_blob_gas);
+        }
+
+        header
+    }
+}
 
 pub(super) fn create_cfg_env(chain_id: u64, spec_id: SpecId) -> CfgEnv {
     CfgEnv::new_with_spec(spec_id).with_chain_id(chain_id)
@@ -21,6 +108,7 @@ pub(super) fn create_cfg_env(chain_id: u64, spec_id: SpecId) -> CfgEnv {
 pub(super) fn create_block_env(
     header: &Header,
     spec_id: SpecId,
+    blob_params: Option<BlobParams>,
 ) -> Result<BlockEnv, EvmExecutionError> {
     let basefee = if spec_id.is_enabled_in(SpecId::LONDON) {
         header.base_fee_per_gas().ok_or_else(|| {
@@ -42,16 +130,18 @@ pub(super) fn create_block_env(
         None
     };
 
-    let blob_excess_gas_and_price = if spec_id.is_enabled_in(SpecId::CANCUN) {
+    // The blob base fee follows the update fraction of the chain's blob
+    // schedule, which blob parameter only forks change without a new spec.
+    let blob_excess_gas_and_price = if let Some(blob_params) = blob_params {
         let excess_blob_gas = header.excess_blob_gas().ok_or_else(|| {
             EvmExecutionError::BlockContext(format!(
                 "rpc block header is missing excess blob gas for spec {spec_id:?}"
             ))
         })?;
 
-        Some(BlobExcessGasAndPrice::new_with_spec(
+        Some(BlobExcessGasAndPrice::new(
             excess_blob_gas,
-            spec_id,
+            blob_params.update_fraction as u64,
         ))
     } else {
         None
@@ -100,6 +190,42 @@ pub(super) fn create_tx_env(transaction: &Transaction) -> TxEnv {
             Some(*max_priority_fee_per_gas),
             map_access_list(access_list),
         ),
+        TransactionVariant::Blob {
+            max_fee_per_gas,
+            max_priority_fee_per_gas,
+            max_fee_per_blob_gas,
+            access_list,
+            blob_versioned_hashes,
+        } => TxEnv {
+            blob_hashes: blob_versioned_hashes.clone(),
+            max_fee_per_blob_gas: *max_fee_per_blob_gas,
+            ..base_tx_env(
+                transaction,
+                TransactionType::Eip4844,
+                *max_fee_per_gas,
+                Some(*max_priority_fee_per_gas),
+                map_access_list(access_list),
+            )
+        },
+        TransactionVariant::SetCode {
+            max_fee_per_gas,
+            max_priority_fee_per_gas,
+            access_list,
+            authorization_list,
+        } => TxEnv {
+            authorization_list: authorization_list
+                .iter()
+                .cloned()
+                .map(Either::Left)
+                .collect(),
+            ..base_tx_env(
+                transaction,
+                TransactionType::Eip7702,
+                *max_fee_per_gas,
+                Some(*max_priority_fee_per_gas),
+                map_access_list(access_list),
+            )
+        },
     }
 }
 
@@ -17,6 +17,7 @@ impl EvmFeeSettlement {
         result: &ExecutionResult<HaltReason>,
         effective_gas_price: u128,
         base_fee_per_gas: u64,
+        blob_fee: U256,
     ) -> Result<Self, EvmExecutionError> {
         let gas = result.gas();
         let gas_limit = U256::from(gas.limit());
@@ -24,14 +25,18 @@ impl EvmFeeSettlement {
         let effective_gas_price = U256::from(effective_gas_price);
         let base_fee_per_gas = U256::from(base_fee_per_gas);
 
+        // The blob fee is charged up front, never refunded and fully burnt.
         let gas_precharge = gas_limit
             .checked_mul(effective_gas_price)
+            .and_then(|value| value.checked_add(blob_fee))
             .ok_or(EvmExecutionError::FeeSettlement)?;
         let fee = gas_used
             .checked_mul(effective_gas_price)
+            .and_then(|value| value.checked_add(blob_fee))
             .ok_or(EvmExecutionError::FeeSettlement)?;
         let burnt_fee = gas_used
             .checked_mul(base_fee_per_gas)
+            .and_then(|value| value.checked_add(blob_fee))
             .ok_or(EvmExecutionError::FeeSettlement)?;
         let caller_refund = gas_precharge
             .checked_sub(fee)
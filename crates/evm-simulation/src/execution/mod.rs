@@ -1,27 +1,37 @@
+mod access_list;
+mod call_trace;
 mod chain_spec;
 mod env;
 mod fee_settlement;
 mod observation;
+mod snapshot;
 mod state;
+mod state_cache;
+mod upstream;
 
 use alloy::{
     consensus::{BlockHeader, Header, Sealed},
-    primitives::Address,
+    eips::{eip2935::HISTORY_STORAGE_ADDRESS, eip4788::BEACON_ROOTS_ADDRESS},
+    primitives::{Address, B256, Bytes, U256},
 };
 use revm::{
     Context, ExecuteCommitEvm, InspectEvm, MainBuilder, MainContext, MainnetEvm as RevmMainnetEvm,
     context::{BlockEnv, CfgEnv, TxEnv},
     context_interface::{
+        Block,
         result::{EVMError, ExecutionResult, HaltReason, InvalidTransaction},
         transaction::Transaction,
     },
-    handler::EvmTr,
+    handler::{EvmTr, SystemCallCommitEvm},
     interpreter::interpreter::EthInterpreter,
+    primitives::hardfork::SpecId,
     state::EvmState,
 };
 use simulation_transaction::Transaction as SimulationTransaction;
 use thiserror::Error;
 
+use crate::config::EvmChainConfig;
+
 use self::{
     chain_spec::resolve_execution_spec_id,
     env::{create_block_env, create_cfg_env, create_tx_env},
@@ -33,13 +43,14 @@ pub(crate) type MainnetEvmWithDb<DB, INSP = ()> =
     RevmMainnetEvm<Context<BlockEnv, TxEnv, CfgEnv, DB>, INSP>;
 type MainnetEvmWithDatabase<DB, INSP = ()> = MainnetEvmWithDb<DB, INSP>;
 
+pub use access_list::EvmAccessListInspector;
+pub(crate) use access_list::warm_addresses;
+pub use call_trace::{EvmCallFrame, EvmCallKind};
+pub use env::EvmBlockOverrides;
 pub use observation::{EvmExecutionObservation, EvmExecutionObserver};
 
 #[derive(Debug, Error)]
 pub enum EvmExecutionError {
-    #[error("only Ethereum mainnet is supported now, got chain_id={0}")]
-    UnsupportedChain(u64),
-
     #[error("hardfork {0} is not mapped to revm::SpecId yet")]
     UnsupportedHardfork(String),
 
@@ -63,6 +74,9 @@ pub enum EvmExecutionError {
 
     #[error("transaction transition is only applicable to a successful execution")]
     TransitionNotApplicable,
+
+    #[error("transaction transition must be applied before executing the next transaction")]
+    TransitionPending,
 }
 
 #[derive(Debug)]
@@ -119,12 +133,40 @@ impl<INSP> EvmExecutionOutput<INSP> {
         self.evm.commit(transition);
         Ok(())
     }
+
+    /// Commits the transition of any executed transaction, including reverted
+    /// and halted ones, as block replay must keep their fee and nonce effects.
+    pub fn commit_transition(&mut self) -> Result<(), EvmExecutionError> {
+        let transition = self
+            .transition
+            .take()
+            .ok_or(EvmExecutionError::TransitionAlreadyApplied)?;
+        self.evm.commit(transition);
+        Ok(())
+    }
+
+    /// Reuses the committed state for the next transaction of a bundle.
+    pub fn into_executor(
+        mut self,
+        inspector: INSP,
+    ) -> Result<EvmTransactionExecutor<INSP>, EvmExecutionError> {
+        if self.transition.is_some() {
+            return Err(EvmExecutionError::TransitionPending);
+        }
+
+        self.evm.inspector = inspector;
+        Ok(EvmTransactionExecutor { evm: self.evm })
+    }
 }
 
 impl EvmExecutionOutput<EvmExecutionObserver> {
     pub fn observations(&self) -> Vec<EvmExecutionObservation> {
         self.evm.inspector.observations()
     }
+
+    pub fn call_trace(&self) -> Option<EvmCallFrame> {
+        self.evm.inspector.call_trace()
+    }
 }
 
 #[derive(Debug)]
@@ -136,7 +178,8 @@ impl<INSP> EvmTransactionExecutor<INSP> {
     pub fn new(
         state_source: EvmStateSource,
         block: Sealed<Header>,
-        chain_id: u64,
+        block_overrides: &EvmBlockOverrides,
+        chain: &EvmChainConfig,
         inspector: INSP,
     ) -> Result<Self, EvmExecutionError> {
         let block_anchor = EvmBlockAnchor::new(block.number(), block.hash());
@@ -150,9 +193,47 @@ impl<INSP> EvmTransactionExecutor<INSP> {
             )));
         }
 
-        let spec_id = resolve_execution_spec_id(chain_id, block.number(), block.timestamp())?;
-        let cfg_env = create_cfg_env(chain_id, spec_id);
-        let block_env = create_block_env(block.inner(), spec_id)?;
+        let header = block_overrides.apply(block.inner());
+        Self::with_header(state_source, &header, chain, inspector)
+    }
+
+    /// Executes in the environment of a mined block on top of its parent
+    /// state, as needed to replay the transactions of that block.
+    pub fn new_for_mined_block(
+        state_source: EvmStateSource,
+        block: &Sealed<Header>,
+        chain: &EvmChainConfig,
+        inspector: INSP,
+    ) -> Result<Self, EvmExecutionError> {
+        let anchor = state_source.anchor();
+        if anchor.hash() != block.parent_hash
+            || anchor.number().checked_add(1) != Some(block.number)
+        {
+            return Err(EvmExecutionError::BlockContext(format!(
+                "state source is anchored at block {} ({}) which is not the parent of block {} ({})",
+                anchor.number(),
+                anchor.hash(),
+                block.number,
+                block.hash(),
+            )));
+        }
+
+        Self::with_header(state_source, block.inner(), chain, inspector)
+    }
+
+    fn with_header(
+        state_source: EvmStateSource,
+        header: &Header,
+        chain: &EvmChainConfig,
+        inspector: INSP,
+    ) -> Result<Self, EvmExecutionError> {
+        let spec_id = resolve_execution_spec_id(chain, header.number(), header.timestamp())?;
+        let cfg_env = create_cfg_env(chain.chain_id, spec_id);
+        let block_env = create_block_env(
+            header,
+            spec_id,
+            chain.blob_params(header.number(), header.timestamp()),
+        )?;
         let evm = Context::mainnet()
             .with_db(state_source.into_database())
             .modify_cfg_chained(|cfg| *cfg = cfg_env)
@@ -162,6 +243,55 @@ impl<INSP> EvmTransactionExecutor<INSP> {
         Ok(Self { evm })
     }
 
+    /// Continues on the same state with a different inspector.
+    pub fn with_inspector<I>(self, inspector: I) -> EvmTransactionExecutor<I> {
+        EvmTransactionExecutor {
+            evm: self.evm.ctx.build_mainnet_with_inspector(inspector),
+        }
+    }
+
+    /// Applies the EIP-4788 beacon root and EIP-2935 block hash system calls
+    /// that a block runs before its first transaction.
+    pub fn apply_pre_block_system_calls(
+        &mut self,
+        block: &Header,
+    ) -> Result<(), EvmExecutionError> {
+        let spec_id = self.evm.ctx().cfg.spec;
+
+        if spec_id.is_enabled_in(SpecId::CANCUN)
+            && let Some(parent_beacon_block_root) = block.parent_beacon_block_root
+        {
+            self.apply_system_call(BEACON_ROOTS_ADDRESS, parent_beacon_block_root)?;
+        }
+
+        if spec_id.is_enabled_in(SpecId::PRAGUE) {
+            self.apply_system_call(HISTORY_STORAGE_ADDRESS, block.parent_hash)?;
+        }
+
+        Ok(())
+    }
+
+    fn apply_system_call(
+        &mut self,
+        contract: Address,
+        data: B256,
+    ) -> Result<(), EvmExecutionError> {
+        let result = self
+            .evm
+            .system_call_commit(contract, Bytes::copy_from_slice(data.as_slice()))
+            .map_err(|error| {
+                EvmExecutionError::Execution(format!("system call to {contract} failed: {error}"))
+            })?;
+
+        if !result.is_success() {
+            return Err(EvmExecutionError::Execution(format!(
+                "system call to {contract} did not succeed: {result:?}"
+            )));
+        }
+
+        Ok(())
+    }
+
     pub fn execute(
         mut self,
         transaction: &SimulationTransaction,
@@ -172,6 +302,8 @@ impl<INSP> EvmTransactionExecutor<INSP> {
         let tx_env = create_tx_env(transaction);
         let effective_gas_price = tx_env.effective_gas_price(self.evm.ctx().block.basefee as u128);
         let base_fee_per_gas = self.evm.ctx().block.basefee;
+        let blob_fee = U256::from(tx_env.total_blob_gas())
+            * U256::from(self.evm.ctx().block.blob_gasprice().unwrap_or_default());
         let result_and_state = match self.evm.inspect_tx(tx_env) {
             Ok(result_and_state) => result_and_state,
             Err(EVMError::Transaction(error)) => {
@@ -198,6 +330,7 @@ impl<INSP> EvmTransactionExecutor<INSP> {
             &result_and_state.result,
             effective_gas_price,
             base_fee_per_gas,
+            blob_fee,
         )?;
 
         Ok(EvmExecutionOutput {
@@ -210,4 +343,10 @@ impl<INSP> EvmTransactionExecutor<INSP> {
 }
 
 pub use fee_settlement::EvmFeeSettlement;
-pub use state::{EvmBlockAnchor, EvmStateSource};
+pub(crate) use snapshot::EvmSnapshotRecorder;
+pub use snapshot::{EvmSnapshotAccount, EvmSnapshotStore, EvmStateSnapshot};
+pub use state::{
+    EvmAccountOverride, EvmBlockAnchor, EvmStateOverrides, EvmStateSource, EvmStorageOverride,
+};
+pub use state_cache::EvmStateCache;
+pub use upstream::EvmUpstreamError;
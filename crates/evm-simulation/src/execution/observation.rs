@@ -4,11 +4,13 @@ use revm::{
     context::ContextTr,
     context_interface::LocalContextTr,
     interpreter::{
-        CallInput, CallInputs, CallOutcome, CreateInputs, CreateOutcome, InstructionResult,
-        InterpreterTypes,
+        CallInput, CallInputs, CallOutcome, CallScheme, CreateInputs, CreateOutcome,
+        InstructionResult, InterpreterTypes,
     },
 };
 
+use super::call_trace::{CallTracer, EvmCallFrame, EvmCallKind};
+
 // transferFrom(address,address,uint256) is a 4-byte selector plus three ABI words.
 const CALL_INPUT_PREFIX_LIMIT: usize = 100;
 
@@ -147,6 +149,7 @@ impl ObservationJournal {
 #[derive(Debug, Default)]
 pub struct EvmExecutionObserver {
     journal: ObservationJournal,
+    call_tracer: Option<CallTracer>,
 }
 
 impl EvmExecutionObserver {
@@ -154,6 +157,22 @@ impl EvmExecutionObserver {
         Self::default()
     }
 
+    /// Also records the full call tree, including input and output data of
+    /// every frame.
+    pub fn with_call_trace() -> Self {
+        Self {
+            journal: ObservationJournal::default(),
+            call_tracer: Some(CallTracer::default()),
+        }
+    }
+
+    pub fn call_trace(&self) -> Option<EvmCallFrame> {
+        self.call_tracer
+            .as_ref()
+            .and_then(CallTracer::root)
+            .cloned()
+    }
+
     pub fn observations(&self) -> Vec<EvmExecutionObservation> {
         self.journal
             .entries
@@ -182,17 +201,38 @@ where
 
     fn call(&mut self, context: &mut CTX, inputs: &mut CallInputs) -> Option<CallOutcome> {
         self.journal.push_call_frame(observed_call(context, inputs));
+        if let Some(call_tracer) = &mut self.call_tracer {
+            call_tracer.enter(traced_call(context, inputs));
+        }
         None
     }
 
     fn call_end(&mut self, _context: &mut CTX, _inputs: &CallInputs, outcome: &mut CallOutcome) {
         self.journal
             .pop_frame(is_success(outcome.instruction_result()), None);
+        if let Some(call_tracer) = &mut self.call_tracer {
+            call_tracer.exit(
+                *outcome.instruction_result(),
+                &outcome.result.output,
+                outcome.result.gas.spent(),
+                None,
+            );
+        }
     }
 
     fn create(&mut self, _context: &mut CTX, inputs: &mut CreateInputs) -> Option<CreateOutcome> {
         self.journal
             .push_create_frame(inputs.caller(), inputs.value());
+        if let Some(call_tracer) = &mut self.call_tracer {
+            call_tracer.enter(EvmCallFrame::new(
+                EvmCallKind::from_create_scheme(inputs.scheme()),
+                inputs.caller(),
+                None,
+                inputs.value(),
+                inputs.init_code().clone(),
+                inputs.gas_limit(),
+            ));
+        }
         None
     }
 
@@ -204,6 +244,14 @@ where
     ) {
         self.journal
             .pop_frame(is_success(outcome.instruction_result()), outcome.address);
+        if let Some(call_tracer) = &mut self.call_tracer {
+            call_tracer.exit(
+                *outcome.instruction_result(),
+                &outcome.result.output,
+                outcome.result.gas.spent(),
+                outcome.address,
+            );
+        }
     }
 
     fn selfdestruct(&mut self, contract: Address, target: Address, value: U256) {
@@ -237,32 +285,55 @@ where
     })
 }
 
+fn traced_call<CTX>(context: &CTX, inputs: &CallInputs) -> EvmCallFrame
+where
+    CTX: ContextTr,
+{
+    // DELEGATECALL and CALLCODE run the callee code in the caller's account.
+    let to = match inputs.scheme {
+        CallScheme::DelegateCall | CallScheme::CallCode => inputs.bytecode_address,
+        CallScheme::Call | CallScheme::StaticCall => inputs.target_address,
+    };
+
+    EvmCallFrame::new(
+        EvmCallKind::from_call_scheme(inputs.scheme),
+        inputs.caller,
+        Some(to),
+        inputs.transfer_value().unwrap_or_default(),
+        copy_call_input(context, &inputs.input, inputs.input.len()),
+        inputs.gas_limit,
+    )
+}
+
 fn call_input_prefix<CTX>(context: &CTX, input: &CallInput) -> Bytes
 where
     CTX: ContextTr,
 {
-    let prefix_len = input.len().min(CALL_INPUT_PREFIX_LIMIT);
+    copy_call_input(context, input, CALL_INPUT_PREFIX_LIMIT)
+}
+
+fn copy_call_input<CTX>(context: &CTX, input: &CallInput, limit: usize) -> Bytes
+where
+    CTX: ContextTr,
+{
+    let copy_len = input.len().min(limit);
 
     match input {
-        CallInput::Bytes(bytes) => copy_input_prefix(bytes),
+        CallInput::Bytes(bytes) => Bytes::copy_from_slice(&bytes[..copy_len]),
         // Internal CALL input points into Revm shared memory and must be copied
         // before the child frame can overwrite that buffer.
         CallInput::SharedBuffer(range) => {
-            let prefix = context
+            let copied = context
                 .local()
-                .shared_memory_buffer_slice(range.start..range.start.saturating_add(prefix_len))
-                .map(|bytes| copy_input_prefix(&bytes))
+                .shared_memory_buffer_slice(range.start..range.start.saturating_add(copy_len))
+                .map(|bytes| Bytes::copy_from_slice(&bytes))
                 .unwrap_or_default();
-            debug_assert_eq!(prefix.len(), prefix_len);
-            prefix
+            debug_assert_eq!(copied.len(), copy_len);
+            copied
         }
     }
 }
 
-fn copy_input_prefix(input: &[u8]) -> Bytes {
-    Bytes::copy_from_slice(&input[..input.len().min(CALL_INPUT_PREFIX_LIMIT)])
-}
-
 fn is_success(result: &InstructionResult) -> bool {
     result.is_ok()
 }
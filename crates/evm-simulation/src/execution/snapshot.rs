@@ -0,0 +1,235 @@
+use std::{
+    collections::BTreeMap,
+    io,
+    path::PathBuf,
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+};
+
+use alloy::{
+    consensus::{Header, Sealed},
+    primitives::{Address, B256, Bytes, U256},
+};
+use revm::state::{AccountInfo, Bytecode};
+use serde::{Deserialize, Serialize};
+use simulation_state_cache::{SnapshotMode, SnapshotStore};
+
+/// Every upstream read made at one block, enough to execute against that
+/// block again without a provider.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EvmStateSnapshot {
+    pub chain_id: u64,
+    pub header: Header,
+    pub block_hash: B256,
+    /// `None` records an account that did not exist at the block.
+    pub accounts: BTreeMap<Address, Option<EvmSnapshotAccount>>,
+    pub storage: BTreeMap<Address, BTreeMap<U256, U256>>,
+    pub block_hashes: BTreeMap<u64, B256>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EvmSnapshotAccount {
+    pub balance: U256,
+    pub nonce: u64,
+    pub code_hash: B256,
+    pub code: Bytes,
+}
+
+impl EvmStateSnapshot {
+    pub fn new(chain_id: u64, block: &Sealed<Header>) -> Self {
+        Self {
+            chain_id,
+            header: block.inner().clone(),
+            block_hash: block.hash(),
+            accounts: BTreeMap::new(),
+            storage: BTreeMap::new(),
+            block_hashes: BTreeMap::new(),
+        }
+    }
+
+    pub fn block(&self) -> Sealed<Header> {
+        Sealed::new_unchecked(self.header.clone(), self.block_hash)
+    }
+
+    pub(super) fn account(&self, address: Address) -> Option<Option<AccountInfo>> {
+        self.accounts.get(&address).map(|account| {
+            account.as_ref().map(|account| {
+                AccountInfo::new(
+                    account.balance,
+                    account.nonce,
+                    account.code_hash,
+                    Bytecode::new_raw(account.code.clone()),
+                )
+            })
+        })
+    }
+
+    pub(super) fn storage_value(&self, address: Address, index: U256) -> Option<U256> {
+        self.storage.get(&address)?.get(&index).copied()
+    }
+
+    pub(super) fn block_hash_at(&self, number: u64) -> Option<B256> {
+        self.block_hashes.get(&number).copied()
+    }
+
+    fn merge(&mut self, other: Self) {
+        self.accounts.extend(other.accounts);
+        for (address, slots) in other.storage {
+            self.storage.entry(address).or_default().extend(slots);
+        }
+        self.block_hashes.extend(other.block_hashes);
+    }
+}
+
+/// Collects the upstream reads of one simulation.
+#[derive(Debug, Clone)]
+pub(crate) struct EvmSnapshotRecorder {
+    snapshot: Arc<Mutex<EvmStateSnapshot>>,
+}
+
+impl EvmSnapshotRecorder {
+    pub(crate) fn new(chain_id: u64, block: &Sealed<Header>) -> Self {
+        Self {
+            snapshot: Arc::new(Mutex::new(EvmStateSnapshot::new(chain_id, block))),
+        }
+    }
+
+    pub(super) fn record_account(&self, address: Address, account: Option<&AccountInfo>) {
+        let account = account.map(|account| EvmSnapshotAccount {
+            balance: account.balance,
+            nonce: account.nonce,
+            code_hash: account.code_hash,
+            code: account
+                .code
+                .as_ref()
+                .map(Bytecode::original_bytes)
+                .unwrap_or_default(),
+        });
+        self.lock().accounts.insert(address, account);
+    }
+
+    pub(super) fn record_storage(&self, address: Address, index: U256, value: U256) {
+        self.lock()
+            .storage
+            .entry(address)
+            .or_default()
+            .insert(index, value);
+    }
+
+    pub(super) fn record_block_hash(&self, number: u64, hash: B256) {
+        self.lock().block_hashes.insert(number, hash);
+    }
+
+    pub(crate) fn snapshot(&self) -> EvmStateSnapshot {
+        self.lock().clone()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, EvmStateSnapshot> {
+        self.snapshot.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// Snapshots on disk, one file per chain and block. Recording into a block
+/// that already has a snapshot adds the new reads to it; replaying executes
+/// against the recorded reads instead of the provider.
+#[derive(Debug, Clone)]
+pub struct EvmSnapshotStore {
+    store: SnapshotStore,
+    mode: SnapshotMode,
+}
+
+impl EvmSnapshotStore {
+    pub fn new(directory: impl Into<PathBuf>, mode: SnapshotMode) -> Self {
+        Self {
+            store: SnapshotStore::new(directory),
+            mode,
+        }
+    }
+
+    pub fn mode(&self) -> SnapshotMode {
+        self.mode
+    }
+
+    pub fn load(&self, chain_id: u64, block_hash: B256) -> io::Result<Option<EvmStateSnapshot>> {
+        self.store.load(&snapshot_key(chain_id, block_hash))
+    }
+
+    pub fn save(&self, snapshot: EvmStateSnapshot) -> io::Result<()> {
+        let key = snapshot_key(snapshot.chain_id, snapshot.block_hash);
+        self.store.merge(&key, snapshot, EvmStateSnapshot::merge)
+    }
+}
+
+fn snapshot_key(chain_id: u64, block_hash: B256) -> String {
+    format!("evm-{chain_id}-{block_hash}")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, process};
+
+    use alloy::primitives::{address, b256};
+    use revm::database::DatabaseAsyncRef;
+
+    use super::{super::upstream::EvmUpstreamDatabase, *};
+
+    const CHAIN_ID: u64 = 1;
+
+    #[test]
+    fn replays_the_recorded_reads_of_a_block() {
+        let directory = std::env::temp_dir().join(format!("evm-snapshot-{}", process::id()));
+        let store = EvmSnapshotStore::new(&directory, SnapshotMode::Record);
+        let block = Sealed::new(Header {
+            number: 100,
+            ..Header::default()
+        });
+        let holder = address!("0x1111111111111111111111111111111111111111");
+        let missing = address!("0x2222222222222222222222222222222222222222");
+        let account = AccountInfo::new(
+            U256::from(5),
+            3,
+            B256::ZERO,
+            Bytecode::new_raw(Bytes::new()),
+        );
+        let parent_hash =
+            b256!("0x3333333333333333333333333333333333333333333333333333333333333333");
+
+        let recorder = EvmSnapshotRecorder::new(CHAIN_ID, &block);
+        recorder.record_account(holder, Some(&account));
+        recorder.record_account(missing, None);
+        recorder.record_storage(holder, U256::from(1), U256::from(7));
+        recorder.record_block_hash(99, parent_hash);
+        store.save(recorder.snapshot()).unwrap();
+
+        let snapshot = store.load(CHAIN_ID, block.hash()).unwrap().unwrap();
+        assert_eq!(snapshot.block(), block);
+        let database = EvmUpstreamDatabase::snapshot(Arc::new(snapshot));
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap();
+        runtime.block_on(async {
+            let replayed = database.basic_async_ref(holder).await.unwrap().unwrap();
+            assert_eq!((replayed.balance, replayed.nonce), (U256::from(5), 3));
+            assert_eq!(database.basic_async_ref(missing).await.unwrap(), None);
+            assert_eq!(
+                database
+                    .storage_async_ref(holder, U256::from(1))
+                    .await
+                    .unwrap(),
+                U256::from(7)
+            );
+            assert_eq!(
+                database.block_hash_async_ref(99).await.unwrap(),
+                parent_hash
+            );
+            assert!(
+                database
+                    .storage_async_ref(holder, U256::from(2))
+                    .await
+                    .is_err()
+            );
+        });
+        fs::remove_dir_all(directory).unwrap();
+    }
+}
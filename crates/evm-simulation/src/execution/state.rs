@@ -1,8 +1,26 @@
-use alloy::{eips::BlockId, network::Ethereum, primitives::B256, providers::RootProvider};
-use revm::database::{AlloyDB, CacheDB, WrapDatabaseAsync};
+use std::{collections::BTreeMap, sync::Arc};
+
+use alloy::{
+    eips::BlockId,
+    primitives::{Address, B256, Bytes, U256},
+    providers::RootProvider,
+};
+use revm::{
+    Database,
+    database::{AlloyDB, CacheDB, WrapDatabaseAsync},
+    primitives::HashMap,
+    state::Bytecode,
+};
 use tokio::runtime::Handle;
 
-pub type MainnetEvmDatabase = CacheDB<WrapDatabaseAsync<AlloyDB<Ethereum, RootProvider>>>;
+use super::{
+    EvmExecutionError,
+    snapshot::{EvmSnapshotRecorder, EvmStateSnapshot},
+    state_cache::EvmStateCache,
+    upstream::EvmUpstreamDatabase,
+};
+
+pub type MainnetEvmDatabase = CacheDB<WrapDatabaseAsync<EvmUpstreamDatabase>>;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct EvmBlockAnchor {
@@ -24,6 +42,24 @@ impl EvmBlockAnchor {
     }
 }
 
+pub type EvmStateOverrides = BTreeMap<Address, EvmAccountOverride>;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EvmAccountOverride {
+    pub balance: Option<U256>,
+    pub nonce: Option<u64>,
+    pub code: Option<Bytes>,
+    pub storage: Option<EvmStorageOverride>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EvmStorageOverride {
+    /// Replaces the whole account storage; unlisted slots read as zero.
+    Replace(BTreeMap<B256, B256>),
+    /// Patches the listed slots on top of the anchored storage.
+    Patch(BTreeMap<B256, B256>),
+}
+
 #[derive(Debug)]
 pub struct EvmStateSource {
     database: MainnetEvmDatabase,
@@ -31,10 +67,47 @@ pub struct EvmStateSource {
 }
 
 impl EvmStateSource {
-    pub fn new(provider: RootProvider, runtime_handle: Handle, anchor: EvmBlockAnchor) -> Self {
+    pub fn new(
+        provider: RootProvider,
+        runtime_handle: Handle,
+        anchor: EvmBlockAnchor,
+        state_cache: EvmStateCache,
+    ) -> Self {
+        Self::new_recorded(provider, runtime_handle, anchor, state_cache, None)
+    }
+
+    /// Records every upstream read into `recorder` when one is given.
+    pub(crate) fn new_recorded(
+        provider: RootProvider,
+        runtime_handle: Handle,
+        anchor: EvmBlockAnchor,
+        state_cache: EvmStateCache,
+        recorder: Option<EvmSnapshotRecorder>,
+    ) -> Self {
         let block_id = BlockId::Hash(anchor.hash().into());
         let alloy_db = AlloyDB::new(provider, block_id);
-        let database = WrapDatabaseAsync::with_handle(alloy_db, runtime_handle);
+        let mut upstream = EvmUpstreamDatabase::provider(alloy_db, state_cache, anchor.hash());
+        if let Some(recorder) = recorder {
+            upstream.set_recorder(recorder);
+        }
+
+        Self::from_upstream(upstream, runtime_handle, anchor)
+    }
+
+    /// Reads only from `snapshot`; a read it did not record fails.
+    pub fn from_snapshot(snapshot: Arc<EvmStateSnapshot>, runtime_handle: Handle) -> Self {
+        let anchor = EvmBlockAnchor::new(snapshot.header.number, snapshot.block_hash);
+        let upstream = EvmUpstreamDatabase::snapshot(snapshot);
+
+        Self::from_upstream(upstream, runtime_handle, anchor)
+    }
+
+    fn from_upstream(
+        upstream: EvmUpstreamDatabase,
+        runtime_handle: Handle,
+        anchor: EvmBlockAnchor,
+    ) -> Self {
+        let database = WrapDatabaseAsync::with_handle(upstream, runtime_handle);
 
         Self {
             database: CacheDB::new(database),
@@ -42,6 +115,65 @@ impl EvmStateSource {
         }
     }
 
+    pub fn apply_state_overrides(
+        &mut self,
+        overrides: &EvmStateOverrides,
+    ) -> Result<(), EvmExecutionError> {
+        for (&address, account_override) in overrides {
+            self.apply_account_override(address, account_override)?;
+        }
+
+        Ok(())
+    }
+
+    fn apply_account_override(
+        &mut self,
+        address: Address,
+        account_override: &EvmAccountOverride,
+    ) -> Result<(), EvmExecutionError> {
+        let mut info = self
+            .database
+            .basic(address)
+            .map_err(|error| {
+                EvmExecutionError::StateAccess(format!(
+                    "failed to load overridden account {address}: {error}"
+                ))
+            })?
+            .unwrap_or_default();
+
+        if let Some(balance) = account_override.balance {
+            info.balance = balance;
+        }
+
+        if let Some(nonce) = account_override.nonce {
+            info.nonce = nonce;
+        }
+
+        if let Some(code) = &account_override.code {
+            info.set_code(Bytecode::new_raw(code.clone()));
+        }
+
+        self.database.insert_account_info(address, info);
+
+        let storage_result = match &account_override.storage {
+            None => Ok(()),
+            Some(EvmStorageOverride::Replace(slots)) => self
+                .database
+                .replace_account_storage(address, storage_slots(slots).collect::<HashMap<_, _>>()),
+            Some(EvmStorageOverride::Patch(slots)) => {
+                storage_slots(slots).try_for_each(|(slot, value)| {
+                    self.database.insert_account_storage(address, slot, value)
+                })
+            }
+        };
+
+        storage_result.map_err(|error| {
+            EvmExecutionError::StateAccess(format!(
+                "failed to override storage of account {address}: {error}"
+            ))
+        })
+    }
+
     pub(super) fn anchor(&self) -> EvmBlockAnchor {
         self.anchor
     }
@@ -50,3 +182,9 @@ impl EvmStateSource {
         self.database
     }
 }
+
+fn storage_slots(slots: &BTreeMap<B256, B256>) -> impl Iterator<Item = (U256, U256)> + '_ {
+    slots
+        .iter()
+        .map(|(slot, value)| (U256::from_be_bytes(slot.0), U256::from_be_bytes(value.0)))
+}
@@ -0,0 +1,41 @@
+use alloy::primitives::{Address, B256};
+use revm::{
+    primitives::{StorageKey, StorageValue},
+    state::AccountInfo,
+};
+use simulation_state_cache::{SharedStateCache, StateCacheLimits};
+
+/// Upstream account, storage and block hash reads shared by every simulation
+/// of a chain. Reads are keyed by the hash of the block they were made at;
+/// state overrides live in the per-request `CacheDB` above this layer.
+#[derive(Debug, Clone)]
+pub struct EvmStateCache {
+    pub(crate) accounts: SharedStateCache<(B256, Address), Option<AccountInfo>>,
+    pub(crate) storage: SharedStateCache<(B256, Address, StorageKey), StorageValue>,
+    pub(crate) block_hashes: SharedStateCache<(B256, u64), B256>,
+}
+
+impl EvmStateCache {
+    /// Bounds each kind of read to `limits`. Accounts carry their runtime
+    /// code inline, which is what the byte budget is spent on.
+    pub fn new(limits: StateCacheLimits) -> Self {
+        Self {
+            accounts: SharedStateCache::new(limits, account_code_len),
+            storage: SharedStateCache::new(limits, |_| 0),
+            block_hashes: SharedStateCache::new(limits, |_| 0),
+        }
+    }
+}
+
+impl Default for EvmStateCache {
+    fn default() -> Self {
+        Self::new(StateCacheLimits::default())
+    }
+}
+
+fn account_code_len(account: &Option<AccountInfo>) -> usize {
+    account
+        .as_ref()
+        .and_then(|account| account.code.as_ref())
+        .map_or(0, |code| code.len())
+}
@@ -0,0 +1,197 @@
+use std::sync::Arc;
+
+use alloy::{
+    network::Ethereum,
+    primitives::{Address, B256},
+    providers::RootProvider,
+};
+use revm::{
+    database::{AlloyDB, DBErrorMarker, DBTransportError, DatabaseAsyncRef},
+    primitives::{StorageKey, StorageValue},
+    state::{AccountInfo, Bytecode},
+};
+use thiserror::Error;
+
+use super::{
+    snapshot::{EvmSnapshotRecorder, EvmStateSnapshot},
+    state_cache::EvmStateCache,
+};
+
+#[derive(Debug, Error)]
+pub enum EvmUpstreamError {
+    #[error(transparent)]
+    Transport(#[from] DBTransportError),
+
+    #[error("state snapshot of block {block_hash} did not record {read}")]
+    SnapshotMiss { block_hash: B256, read: String },
+}
+
+impl DBErrorMarker for EvmUpstreamError {}
+
+#[derive(Debug)]
+enum UpstreamSource {
+    Provider {
+        alloy_db: AlloyDB<Ethereum, RootProvider>,
+        state_cache: EvmStateCache,
+    },
+    Snapshot(Arc<EvmStateSnapshot>),
+}
+
+/// The anchored state below the per-request `CacheDB`: provider reads
+/// through the shared cache, or the reads of a recorded snapshot.
+#[derive(Debug)]
+pub struct EvmUpstreamDatabase {
+    source: UpstreamSource,
+    block_hash: B256,
+    recorder: Option<EvmSnapshotRecorder>,
+}
+
+impl EvmUpstreamDatabase {
+    pub(super) fn provider(
+        alloy_db: AlloyDB<Ethereum, RootProvider>,
+        state_cache: EvmStateCache,
+        block_hash: B256,
+    ) -> Self {
+        Self {
+            source: UpstreamSource::Provider {
+                alloy_db,
+                state_cache,
+            },
+            block_hash,
+            recorder: None,
+        }
+    }
+
+    pub(super) fn snapshot(snapshot: Arc<EvmStateSnapshot>) -> Self {
+        Self {
+            block_hash: snapshot.block_hash,
+            source: UpstreamSource::Snapshot(snapshot),
+            recorder: None,
+        }
+    }
+
+    pub(super) fn set_recorder(&mut self, recorder: EvmSnapshotRecorder) {
+        self.recorder = Some(recorder);
+    }
+
+    fn snapshot_miss(&self, read: String) -> EvmUpstreamError {
+        EvmUpstreamError::SnapshotMiss {
+            block_hash: self.block_hash,
+            read,
+        }
+    }
+
+    async fn read_account(
+        &self,
+        address: Address,
+    ) -> Result<Option<AccountInfo>, EvmUpstreamError> {
+        match &self.source {
+            UpstreamSource::Provider {
+                alloy_db,
+                state_cache,
+            } => {
+                let key = (self.block_hash, address);
+                if let Some(account) = state_cache.accounts.get(&key) {
+                    return Ok(account);
+                }
+
+                let account = alloy_db.basic_async_ref(address).await?;
+                state_cache.accounts.insert(key, account.clone());
+                Ok(account)
+            }
+            UpstreamSource::Snapshot(snapshot) => snapshot
+                .account(address)
+                .ok_or_else(|| self.snapshot_miss(format!("account {address}"))),
+        }
+    }
+
+    async fn read_storage(
+        &self,
+        address: Address,
+        index: StorageKey,
+    ) -> Result<StorageValue, EvmUpstreamError> {
+        match &self.source {
+            UpstreamSource::Provider {
+                alloy_db,
+                state_cache,
+            } => {
+                let key = (self.block_hash, address, index);
+                if let Some(value) = state_cache.storage.get(&key) {
+                    return Ok(value);
+                }
+
+                let value = alloy_db.storage_async_ref(address, index).await?;
+                state_cache.storage.insert(key, value);
+                Ok(value)
+            }
+            UpstreamSource::Snapshot(snapshot) => snapshot
+                .storage_value(address, index)
+                .ok_or_else(|| self.snapshot_miss(format!("slot {index} of {address}"))),
+        }
+    }
+
+    async fn read_block_hash(&self, number: u64) -> Result<B256, EvmUpstreamError> {
+        match &self.source {
+            UpstreamSource::Provider {
+                alloy_db,
+                state_cache,
+            } => {
+                let key = (self.block_hash, number);
+                if let Some(hash) = state_cache.block_hashes.get(&key) {
+                    return Ok(hash);
+                }
+
+                let hash = alloy_db.block_hash_async_ref(number).await?;
+                state_cache.block_hashes.insert(key, hash);
+                Ok(hash)
+            }
+            UpstreamSource::Snapshot(snapshot) => snapshot
+                .block_hash_at(number)
+                .ok_or_else(|| self.snapshot_miss(format!("hash of block {number}"))),
+        }
+    }
+}
+
+impl DatabaseAsyncRef for EvmUpstreamDatabase {
+    type Error = EvmUpstreamError;
+
+    async fn basic_async_ref(&self, address: Address) -> Result<Option<AccountInfo>, Self::Error> {
+        let account = self.read_account(address).await?;
+        if let Some(recorder) = &self.recorder {
+            recorder.record_account(address, account.as_ref());
+        }
+        Ok(account)
+    }
+
+    async fn code_by_hash_async_ref(&self, code_hash: B256) -> Result<Bytecode, Self::Error> {
+        // Account reads already carry their code.
+        match &self.source {
+            UpstreamSource::Provider { alloy_db, .. } => {
+                Ok(alloy_db.code_by_hash_async_ref(code_hash).await?)
+            }
+            UpstreamSource::Snapshot(_) => {
+                Err(self.snapshot_miss(format!("code with hash {code_hash}")))
+            }
+        }
+    }
+
+    async fn storage_async_ref(
+        &self,
+        address: Address,
+        index: StorageKey,
+    ) -> Result<StorageValue, Self::Error> {
+        let value = self.read_storage(address, index).await?;
+        if let Some(recorder) = &self.recorder {
+            recorder.record_storage(address, index, value);
+        }
+        Ok(value)
+    }
+
+    async fn block_hash_async_ref(&self, number: u64) -> Result<B256, Self::Error> {
+        let hash = self.read_block_hash(number).await?;
+        if let Some(recorder) = &self.recorder {
+            recorder.record_block_hash(number, hash);
+        }
+        Ok(hash)
+    }
+}
@@ -1,54 +1,84 @@
+use std::{collections::BTreeMap, slice};
+
 use alloy::{
     consensus::{BlockHeader, Header, Sealed},
     eips::{BlockId, BlockNumberOrTag},
-    primitives::{Address, B256, Bytes, TxKind, U256},
+    primitives::{Address, B256, Bytes, TxKind, U256, map::B256HashMap},
     providers::{Provider, RootProvider, layers::BlockIdProvider},
     rpc::types::{
-        AccessList as RpcAccessList, TransactionInput, TransactionRequest as RpcTransactionRequest,
+        AccessList as RpcAccessList, BlockOverrides as RpcBlockOverrides, TransactionInput,
+        TransactionRequest as RpcTransactionRequest,
+        state::{AccountOverride as RpcAccountOverride, StateOverride as RpcStateOverride},
     },
 };
+pub use simulation_state_cache::{SnapshotMode, StateCacheLimits};
 pub use simulation_transaction::{AccessListItem, TransactionRequest as EvmTransactionRequest};
 use simulation_transaction::{
     Transaction, TransactionRequest, TransactionVariant, TransactionVariantRequest,
 };
 use thiserror::Error;
 
+use crate::config::EvmChainConfig;
+
+mod access_list;
 mod changes;
+pub mod config;
 mod error;
 mod execution;
+mod logs;
 mod outcome;
+mod prefetch;
+mod replay;
+mod risk;
 mod simulation;
 mod simulator;
+mod state_diff;
 
+pub use access_list::EvmAccessListCreation;
 pub use changes::{EvmNativeChangeError, analyze_native_changes};
 pub use error::{EvmSimulationError, EvmSimulationInternalKind};
 pub use execution::{
-    EvmBlockAnchor, EvmExecutionError, EvmExecutionObservation, EvmExecutionObserver,
-    EvmExecutionOutput, EvmFeeSettlement, EvmStateSource, EvmTransactionExecutor, MainnetEvm,
-    MainnetEvmDatabase,
+    EvmAccessListInspector, EvmAccountOverride, EvmBlockAnchor, EvmBlockOverrides, EvmCallFrame,
+    EvmCallKind, EvmExecutionError, EvmExecutionObservation, EvmExecutionObserver,
+    EvmExecutionOutput, EvmFeeSettlement, EvmSnapshotAccount, EvmSnapshotStore, EvmStateCache,
+    EvmStateOverrides, EvmStateSnapshot, EvmStateSource, EvmStorageOverride,
+    EvmTransactionExecutor, EvmUpstreamError, MainnetEvm, MainnetEvmDatabase,
 };
+pub use logs::EvmSimulationLog;
+pub use prefetch::EvmPrefetch;
+pub use replay::{EvmReplayedReceipt, EvmReplayedTransaction, PreparedEvmReplay};
 pub use simulation::{
-    EvmExecutedDetails, EvmExecution, EvmExecutionFailure, EvmExecutionFailureCode,
-    EvmExecutionOutcome, EvmSimulation, SimulatedBlock,
+    EvmBundleSimulation, EvmExecutedDetails, EvmExecution, EvmExecutionFailure,
+    EvmExecutionFailureCode, EvmExecutionOutcome, EvmSimulation, EvmSimulationIncludes,
+    SimulatedBlock,
+};
+pub use simulation_changes::{
+    BalanceChange, Change, ChangeAsset, Erc20Metadata, Erc721CollectionMetadata, NativeMetadata,
+    NetAssetChange, ProxySlot, RiskCode, RiskConfig, RiskRule, RiskSeverity, RiskWarning,
 };
-pub use simulation_changes::{Change, Erc20Metadata, Erc721CollectionMetadata, NativeMetadata};
 pub use simulator::EvmSimulator;
+pub use state_diff::{EvmAccountDiff, EvmValueDiff};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum EvmBlockSelector {
     Latest,
+    /// The block after the latest one, executed on top of the latest state.
+    Pending,
     Safe,
     Finalized,
     Number(u64),
+    Hash(B256),
 }
 
 impl EvmBlockSelector {
-    fn block_number_or_tag(self) -> BlockNumberOrTag {
+    fn block_id(self) -> BlockId {
         match self {
-            Self::Latest => BlockNumberOrTag::Latest,
-            Self::Safe => BlockNumberOrTag::Safe,
-            Self::Finalized => BlockNumberOrTag::Finalized,
-            Self::Number(number) => BlockNumberOrTag::Number(number),
+            // Pending execution anchors its state at the latest block.
+            Self::Latest | Self::Pending => BlockNumberOrTag::Latest.into(),
+            Self::Safe => BlockNumberOrTag::Safe.into(),
+            Self::Finalized => BlockNumberOrTag::Finalized.into(),
+            Self::Number(number) => BlockNumberOrTag::Number(number).into(),
+            Self::Hash(hash) => BlockId::Hash(hash.into()),
         }
     }
 }
@@ -60,6 +90,9 @@ pub enum EvmPreparationError {
 
     #[error("transaction completion failed: {details}")]
     TransactionCompletion { details: String },
+
+    #[error("transaction resolution failed: {details}")]
+    TransactionResolution { details: String },
 }
 
 impl EvmPreparationError {
@@ -74,27 +107,153 @@ impl EvmPreparationError {
             details: details.into(),
         }
     }
+
+    fn transaction_resolution(details: impl Into<String>) -> Self {
+        Self::TransactionResolution {
+            details: details.into(),
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
 pub struct EvmSimulationPreparer {
     provider: RootProvider,
+    chain: EvmChainConfig,
+    state_cache: Option<EvmStateCache>,
+    prefetch: EvmPrefetch,
+    snapshot_store: Option<EvmSnapshotStore>,
 }
 
 impl EvmSimulationPreparer {
-    pub fn new(provider: RootProvider) -> Self {
-        Self { provider }
+    pub fn new(provider: RootProvider, chain: EvmChainConfig) -> Self {
+        Self {
+            provider,
+            chain,
+            state_cache: None,
+            prefetch: EvmPrefetch::default(),
+            snapshot_store: None,
+        }
+    }
+
+    /// Prefetches into `state_cache`, which must be the cache the simulator
+    /// reads through. Without one the preparer does not prefetch.
+    pub fn with_state_cache(mut self, state_cache: EvmStateCache) -> Self {
+        self.state_cache = Some(state_cache);
+        self
+    }
+
+    pub fn with_prefetch(mut self, prefetch: EvmPrefetch) -> Self {
+        self.prefetch = prefetch;
+        self
+    }
+
+    /// When `store` replays, resolves blocks from its recorded snapshots, so
+    /// the block must be selected by hash. Recording stores are ignored here.
+    pub fn with_snapshot_store(mut self, store: EvmSnapshotStore) -> Self {
+        self.snapshot_store = Some(store).filter(|store| store.mode() == SnapshotMode::Replay);
+        self
+    }
+
+    /// Resolves the block whose state the execution reads. A pending
+    /// selector executes in a synthetic next block, under any caller
+    /// overrides.
+    async fn resolve_execution_block(
+        &self,
+        selector: EvmBlockSelector,
+        block_overrides: EvmBlockOverrides,
+    ) -> Result<(Sealed<Header>, EvmBlockOverrides), EvmPreparationError> {
+        let block = match &self.snapshot_store {
+            Some(store) => resolve_snapshot_block(store, self.chain.chain_id, selector)?,
+            None => resolve_block(&self.provider, selector).await?,
+        };
+        let block_overrides = match selector {
+            EvmBlockSelector::Pending => {
+                block_overrides.or(EvmBlockOverrides::next_block(block.inner(), &self.chain))
+            }
+            _ => block_overrides,
+        };
+
+        Ok((block, block_overrides))
     }
 
     pub async fn prepare_transaction(
         &self,
         block: EvmBlockSelector,
         transaction: TransactionRequest,
+        state_overrides: EvmStateOverrides,
+        block_overrides: EvmBlockOverrides,
     ) -> Result<PreparedEvmInput, EvmPreparationError> {
-        let block = resolve_block(&self.provider, block).await?;
-        let transaction = complete_transaction(transaction, &self.provider, &block).await?;
+        let (block, block_overrides) = self.resolve_execution_block(block, block_overrides).await?;
+        let transaction = complete_transaction(
+            transaction,
+            &self.provider,
+            &block,
+            &state_overrides,
+            &block_overrides,
+        )
+        .await?;
+        self.prefetch(
+            &block,
+            slice::from_ref(&transaction),
+            &state_overrides,
+            &block_overrides,
+        )
+        .await;
 
-        Ok(PreparedEvmInput { block, transaction })
+        Ok(PreparedEvmInput {
+            block,
+            transaction,
+            state_overrides,
+            block_overrides,
+        })
+    }
+
+    /// Completes an ordered bundle against the selected block. Later
+    /// transactions depend on the effects of earlier ones, so only the first
+    /// transaction may rely on provider gas estimation, and nonces of repeated
+    /// senders continue from the previous bundle transaction.
+    pub async fn prepare_bundle(
+        &self,
+        block: EvmBlockSelector,
+        transactions: Vec<TransactionRequest>,
+        state_overrides: EvmStateOverrides,
+        block_overrides: EvmBlockOverrides,
+    ) -> Result<PreparedEvmBundle, EvmPreparationError> {
+        let (block, block_overrides) = self.resolve_execution_block(block, block_overrides).await?;
+        let mut next_nonces = BTreeMap::<Address, u64>::new();
+        let mut completed = Vec::with_capacity(transactions.len());
+
+        for (index, mut transaction) in transactions.into_iter().enumerate() {
+            if index > 0 && transaction.gas_limit.is_none() {
+                return Err(EvmPreparationError::transaction_completion(format!(
+                    "bundle transaction {index} must set a gas limit"
+                )));
+            }
+
+            if transaction.nonce.is_none() {
+                transaction.nonce = next_nonces.get(&transaction.from).copied();
+            }
+
+            let transaction = complete_transaction(
+                transaction,
+                &self.provider,
+                &block,
+                &state_overrides,
+                &block_overrides,
+            )
+            .await?;
+            next_nonces.insert(transaction.from, transaction.nonce.saturating_add(1));
+            completed.push(transaction);
+        }
+        self.prefetch(&block, &completed, &state_overrides, &block_overrides)
+            .await;
+
+        Ok(PreparedEvmBundle {
+            block,
+            transactions: completed,
+            state_overrides,
+            block_overrides,
+        })
     }
 }
 
@@ -102,11 +261,51 @@ impl EvmSimulationPreparer {
 pub struct PreparedEvmInput {
     block: Sealed<Header>,
     transaction: Transaction,
+    state_overrides: EvmStateOverrides,
+    block_overrides: EvmBlockOverrides,
 }
 
 impl PreparedEvmInput {
-    pub fn into_parts(self) -> (Sealed<Header>, Transaction) {
-        (self.block, self.transaction)
+    pub fn into_parts(
+        self,
+    ) -> (
+        Sealed<Header>,
+        Transaction,
+        EvmStateOverrides,
+        EvmBlockOverrides,
+    ) {
+        (
+            self.block,
+            self.transaction,
+            self.state_overrides,
+            self.block_overrides,
+        )
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PreparedEvmBundle {
+    block: Sealed<Header>,
+    transactions: Vec<Transaction>,
+    state_overrides: EvmStateOverrides,
+    block_overrides: EvmBlockOverrides,
+}
+
+impl PreparedEvmBundle {
+    pub fn into_parts(
+        self,
+    ) -> (
+        Sealed<Header>,
+        Vec<Transaction>,
+        EvmStateOverrides,
+        EvmBlockOverrides,
+    ) {
+        (
+            self.block,
+            self.transactions,
+            self.state_overrides,
+            self.block_overrides,
+        )
     }
 }
 
@@ -115,7 +314,7 @@ async fn resolve_block(
     selector: EvmBlockSelector,
 ) -> Result<Sealed<Header>, EvmPreparationError> {
     let block = provider
-        .get_block_by_number(selector.block_number_or_tag())
+        .get_block(selector.block_id())
         .await
         .map_err(|_| {
             EvmPreparationError::block_resolution("provider request failed while resolving block")
@@ -125,10 +324,43 @@ async fn resolve_block(
         })?;
 
     let provider_hash = block.hash();
+    if let EvmBlockSelector::Hash(requested_hash) = selector
+        && provider_hash != requested_hash
+    {
+        return Err(EvmPreparationError::block_resolution(
+            "provider block hash did not match the requested block hash",
+        ));
+    }
+
     let header = block.into_consensus_header();
     seal_and_validate_block(header, provider_hash)
 }
 
+fn resolve_snapshot_block(
+    store: &EvmSnapshotStore,
+    chain_id: u64,
+    selector: EvmBlockSelector,
+) -> Result<Sealed<Header>, EvmPreparationError> {
+    let EvmBlockSelector::Hash(block_hash) = selector else {
+        return Err(EvmPreparationError::block_resolution(
+            "replaying state snapshots requires a block hash",
+        ));
+    };
+
+    let snapshot = store
+        .load(chain_id, block_hash)
+        .map_err(|error| {
+            EvmPreparationError::block_resolution(format!("failed to load state snapshot: {error}"))
+        })?
+        .ok_or_else(|| {
+            EvmPreparationError::block_resolution(format!(
+                "no state snapshot was recorded at block {block_hash}"
+            ))
+        })?;
+
+    Ok(snapshot.block())
+}
+
 fn seal_and_validate_block(
     header: Header,
     provider_hash: B256,
@@ -148,6 +380,8 @@ async fn complete_transaction(
     request: TransactionRequest,
     provider: &RootProvider,
     block: &Sealed<Header>,
+    state_overrides: &EvmStateOverrides,
+    block_overrides: &EvmBlockOverrides,
 ) -> Result<Transaction, EvmPreparationError> {
     let TransactionRequest {
         from,
@@ -161,7 +395,10 @@ async fn complete_transaction(
     } = request;
     let block_id = BlockId::Hash(block.hash().into());
     let anchored_provider = BlockIdProvider::new(provider.clone(), block_id);
-    let nonce = match nonce {
+    let overridden_nonce = state_overrides
+        .get(&from)
+        .and_then(|account_override| account_override.nonce);
+    let nonce = match nonce.or(overridden_nonce) {
         Some(nonce) => nonce,
         None => anchored_provider
             .get_transaction_count(from)
@@ -173,13 +410,13 @@ async fn complete_transaction(
                 ))
             })?,
     };
-    let variant = complete_transaction_variant(provider, block, variant).await?;
+    let variant = complete_transaction_variant(provider, block, block_overrides, variant).await?;
     let value = value.unwrap_or(U256::ZERO);
     let data = data.unwrap_or_default();
     let gas_limit = match gas_limit {
         Some(gas_limit) => gas_limit,
-        None => anchored_provider
-            .estimate_gas(estimation_request(
+        None => {
+            let estimation = anchored_provider.estimate_gas(estimation_request(
                 from,
                 to,
                 nonce,
@@ -187,14 +424,25 @@ async fn complete_transaction(
                 data.clone(),
                 chain_id,
                 &variant,
-            ))
-            .await
-            .map_err(|error| {
+            ));
+            let estimation = if state_overrides.is_empty() {
+                estimation
+            } else {
+                estimation.overrides(rpc_state_override(state_overrides))
+            };
+            let estimation = if block_overrides.is_empty() {
+                estimation
+            } else {
+                estimation.with_block_overrides(rpc_block_overrides(block_overrides))
+            };
+
+            estimation.await.map_err(|error| {
                 EvmPreparationError::transaction_completion(format!(
                     "failed to estimate gas at block {}: {error}",
                     block.number()
                 ))
-            })?,
+            })?
+        }
     };
 
     Ok(Transaction {
@@ -212,6 +460,7 @@ async fn complete_transaction(
 async fn complete_transaction_variant(
     provider: &RootProvider,
     block: &Sealed<Header>,
+    block_overrides: &EvmBlockOverrides,
     variant: TransactionVariantRequest,
 ) -> Result<TransactionVariant, EvmPreparationError> {
     match variant {
@@ -230,31 +479,118 @@ async fn complete_transaction_variant(
             max_priority_fee_per_gas,
             access_list,
         } => {
-            let max_priority_fee_per_gas = match max_priority_fee_per_gas {
-                Some(value) => value,
-                None => provider
-                    .get_max_priority_fee_per_gas()
-                    .await
-                    .map_err(|error| {
-                        EvmPreparationError::transaction_completion(format!(
-                            "failed to fetch max priority fee per gas: {error}"
-                        ))
-                    })?,
-            };
-            let max_fee_per_gas = match max_fee_per_gas {
+            let (max_fee_per_gas, max_priority_fee_per_gas) = complete_dynamic_fees(
+                provider,
+                block,
+                block_overrides,
+                max_fee_per_gas,
+                max_priority_fee_per_gas,
+            )
+            .await?;
+
+            Ok(TransactionVariant::DynamicFee {
+                max_fee_per_gas,
+                max_priority_fee_per_gas,
+                access_list,
+            })
+        }
+        TransactionVariantRequest::Blob {
+            max_fee_per_gas,
+            max_priority_fee_per_gas,
+            max_fee_per_blob_gas,
+            access_list,
+            blob_versioned_hashes,
+        } => {
+            let (max_fee_per_gas, max_priority_fee_per_gas) = complete_dynamic_fees(
+                provider,
+                block,
+                block_overrides,
+                max_fee_per_gas,
+                max_priority_fee_per_gas,
+            )
+            .await?;
+            let max_fee_per_blob_gas = match max_fee_per_blob_gas {
                 Some(value) => value,
-                None => suggested_dynamic_fee_cap(block.inner(), max_priority_fee_per_gas)?,
+                None => suggested_blob_fee_cap(provider).await?,
             };
 
-            Ok(TransactionVariant::DynamicFee {
+            Ok(TransactionVariant::Blob {
                 max_fee_per_gas,
                 max_priority_fee_per_gas,
+                max_fee_per_blob_gas,
                 access_list,
+                blob_versioned_hashes,
+            })
+        }
+        TransactionVariantRequest::SetCode {
+            max_fee_per_gas,
+            max_priority_fee_per_gas,
+            access_list,
+            authorization_list,
+        } => {
+            let (max_fee_per_gas, max_priority_fee_per_gas) = complete_dynamic_fees(
+                provider,
+                block,
+                block_overrides,
+                max_fee_per_gas,
+                max_priority_fee_per_gas,
+            )
+            .await?;
+
+            Ok(TransactionVariant::SetCode {
+                max_fee_per_gas,
+                max_priority_fee_per_gas,
+                access_list,
+                authorization_list,
             })
         }
     }
 }
 
+async fn complete_dynamic_fees(
+    provider: &RootProvider,
+    block: &Sealed<Header>,
+    block_overrides: &EvmBlockOverrides,
+    max_fee_per_gas: Option<u128>,
+    max_priority_fee_per_gas: Option<u128>,
+) -> Result<(u128, u128), EvmPreparationError> {
+    let max_priority_fee_per_gas = match max_priority_fee_per_gas {
+        Some(value) => value,
+        None => provider
+            .get_max_priority_fee_per_gas()
+            .await
+            .map_err(|error| {
+                EvmPreparationError::transaction_completion(format!(
+                    "failed to fetch max priority fee per gas: {error}"
+                ))
+            })?,
+    };
+    let max_fee_per_gas = match max_fee_per_gas {
+        Some(value) => value,
+        None => suggested_dynamic_fee_cap(
+            &block_overrides.apply(block.inner()),
+            max_priority_fee_per_gas,
+        )?,
+    };
+
+    Ok((max_fee_per_gas, max_priority_fee_per_gas))
+}
+
+async fn suggested_blob_fee_cap(provider: &RootProvider) -> Result<u128, EvmPreparationError> {
+    let blob_base_fee = provider.get_blob_base_fee().await.map_err(|error| {
+        EvmPreparationError::transaction_completion(format!(
+            "failed to fetch blob base fee: {error}"
+        ))
+    })?;
+
+    blob_base_fee.checked_mul(2).ok_or_else(|| {
+        EvmPreparationError::transaction_completion(
+            "calculated blob fee exceeds the simulator maximum \
+             340282366920938463463374607431768211455",
+        )
+    })
+}
+
 async fn suggested_gas_price(
     provider: &RootProvider,
     gas_price: Option<u128>,
@@ -333,6 +669,32 @@ fn estimation_request(
             request.max_priority_fee_per_gas = Some(*max_priority_fee_per_gas);
             request.access_list = Some(rpc_access_list(access_list));
         }
+        TransactionVariant::Blob {
+            max_fee_per_gas,
+            max_priority_fee_per_gas,
+            max_fee_per_blob_gas,
+            access_list,
+            blob_versioned_hashes,
+        } => {
+            request.transaction_type = Some(3);
+            request.max_fee_per_gas = Some(*max_fee_per_gas);
+            request.max_priority_fee_per_gas = Some(*max_priority_fee_per_gas);
+            request.max_fee_per_blob_gas = Some(*max_fee_per_blob_gas);
+            request.access_list = Some(rpc_access_list(access_list));
+            request.blob_versioned_hashes = Some(blob_versioned_hashes.clone());
+        }
+        TransactionVariant::SetCode {
+            max_fee_per_gas,
+            max_priority_fee_per_gas,
+            access_list,
+            authorization_list,
+        } => {
+            request.transaction_type = Some(4);
+            request.max_fee_per_gas = Some(*max_fee_per_gas);
+            request.max_priority_fee_per_gas = Some(*max_priority_fee_per_gas);
+            request.access_list = Some(rpc_access_list(access_list));
+            request.authorization_list = Some(authorization_list.clone());
+        }
     }
 
     request
@@ -341,3 +703,44 @@ fn estimation_request(
 fn rpc_access_list(items: &[AccessListItem]) -> RpcAccessList {
     RpcAccessList(items.to_vec())
 }
+
+fn rpc_state_override(overrides: &EvmStateOverrides) -> RpcStateOverride {
+    overrides
+        .iter()
+        .map(|(&address, account_override)| {
+            let (state, state_diff) = match &account_override.storage {
+                None => (None, None),
+                Some(EvmStorageOverride::Replace(slots)) => (Some(rpc_storage_slots(slots)), None),
+                Some(EvmStorageOverride::Patch(slots)) => (None, Some(rpc_storage_slots(slots))),
+            };
+
+            (
+                address,
+                RpcAccountOverride {
+                    balance: account_override.balance,
+                    nonce: account_override.nonce,
+                    code: account_override.code.clone(),
+                    state,
+                    state_diff,
+                    ..Default::default()
+                },
+            )
+        })
+        .collect()
+}
+
+fn rpc_block_overrides(overrides: &EvmBlockOverrides) -> RpcBlockOverrides {
+    RpcBlockOverrides {
+        number: overrides.number.map(U256::from),
+        time: overrides.timestamp,
+        base_fee: overrides.base_fee_per_gas.map(U256::from),
+        coinbase: overrides.coinbase,
+        gas_limit: overrides.gas_limit,
+        random: overrides.prevrandao,
+        ..Default::default()
+    }
+}
+
+fn rpc_storage_slots(slots: &BTreeMap<B256, B256>) -> B256HashMap<B256> {
+    slots.iter().map(|(slot, value)| (*slot, *value)).collect()
+}
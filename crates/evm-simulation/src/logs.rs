@@ -0,0 +1,45 @@
+use alloy::primitives::{Address, B256, Bytes};
+use contract_standards::Position;
+use simulation_changes::PositionedChange;
+
+use crate::EvmExecutionObservation;
+
+/// A log that survived execution, in emission order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EvmSimulationLog {
+    pub address: Address,
+    pub topics: Vec<B256>,
+    pub data: Bytes,
+    /// Number of `changes` entries that happened before this log. Changes
+    /// decoded from the log itself start at this index.
+    pub change_index: usize,
+}
+
+pub(crate) fn collect_logs(
+    observations: &[EvmExecutionObservation],
+    sorted_changes: &[PositionedChange],
+) -> Vec<EvmSimulationLog> {
+    observations
+        .iter()
+        .enumerate()
+        .filter_map(|(index, observation)| match observation {
+            EvmExecutionObservation::Log {
+                address,
+                topics,
+                data,
+            } => {
+                let position = Position::new(index, 0);
+                Some(EvmSimulationLog {
+                    address: *address,
+                    topics: topics.clone(),
+                    data: data.clone(),
+                    change_index: sorted_changes
+                        .partition_point(|positioned| positioned.position() < position),
+                })
+            }
+            EvmExecutionObservation::Call { .. }
+            | EvmExecutionObservation::CreateTransfer { .. }
+            | EvmExecutionObservation::SelfDestruct { .. } => None,
+        })
+        .collect()
+}
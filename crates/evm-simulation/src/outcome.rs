@@ -201,7 +201,7 @@ fn build_revert_failure(output: &Bytes) -> EvmExecutionFailure {
     }
 }
 
-fn decode_revert_reason(output: &Bytes) -> Option<String> {
+pub(crate) fn decode_revert_reason(output: &Bytes) -> Option<String> {
     Revert::abi_decode(output.as_ref())
         .map(|revert| revert.reason().to_string())
         .or_else(|_| {
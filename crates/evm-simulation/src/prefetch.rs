@@ -0,0 +1,309 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use alloy::{
+    consensus::{Header, Sealed},
+    eips::BlockId,
+    primitives::{Address, B256, Bytes, U64, U256},
+    rpc::types::{BlockOverrides as RpcBlockOverrides, state::StateOverride as RpcStateOverride},
+    transports::TransportResult,
+};
+use revm::state::{AccountInfo, Bytecode};
+use serde::{Deserialize, Serialize};
+use simulation_transaction::{Transaction, TransactionVariant};
+
+use crate::{
+    EvmBlockOverrides, EvmSimulationPreparer, EvmStateCache, EvmStateOverrides, estimation_request,
+    rpc_block_overrides, rpc_state_override,
+};
+
+// Upstreams commonly cap JSON-RPC batches at 100 calls.
+const PREFETCH_BATCH_CALLS: usize = 100;
+// `eth_getBalance`, `eth_getTransactionCount` and `eth_getCode`.
+const CALLS_PER_ACCOUNT: usize = 3;
+
+/// How the preparer warms the shared state cache before execution, so that
+/// execution does not fetch each account and slot in its own round trip.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum EvmPrefetch {
+    /// Execution loads every account and slot on first use.
+    Disabled,
+    /// Loads the senders, recipients, coinbase and access list entries.
+    #[default]
+    AccessList,
+    /// Also loads the accounts and slots a `debug_traceCall` prestate trace
+    /// touches. An upstream without the debug namespace falls back to the
+    /// access list.
+    PrestateTrace,
+}
+
+type PrestateTrace = BTreeMap<Address, PrestateAccount>;
+
+#[derive(Debug, Deserialize)]
+struct PrestateAccount {
+    #[serde(default)]
+    storage: BTreeMap<B256, B256>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PrestateTraceOptions {
+    tracer: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    state_overrides: Option<RpcStateOverride>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    block_overrides: Option<RpcBlockOverrides>,
+}
+
+#[derive(Debug, Default)]
+struct PrefetchKeys {
+    accounts: BTreeSet<Address>,
+    storage: BTreeSet<(Address, U256)>,
+}
+
+impl PrefetchKeys {
+    fn new<'a>(coinbase: Address, transactions: impl IntoIterator<Item = &'a Transaction>) -> Self {
+        let mut keys = Self::default();
+        keys.accounts.insert(coinbase);
+        for transaction in transactions {
+            keys.add_transaction(transaction);
+        }
+
+        keys
+    }
+
+    fn add_transaction(&mut self, transaction: &Transaction) {
+        self.accounts.insert(transaction.from);
+        self.accounts.extend(transaction.to);
+
+        let (access_list, authorization_list) = match &transaction.variant {
+            TransactionVariant::Legacy { .. } => return,
+            TransactionVariant::AccessList { access_list, .. }
+            | TransactionVariant::DynamicFee { access_list, .. }
+            | TransactionVariant::Blob { access_list, .. } => (access_list, &[][..]),
+            TransactionVariant::SetCode {
+                access_list,
+                authorization_list,
+                ..
+            } => (access_list, &authorization_list[..]),
+        };
+        for item in access_list {
+            self.accounts.insert(item.address);
+            self.storage.extend(
+                item.storage_keys
+                    .iter()
+                    .map(|key| (item.address, U256::from_be_bytes(key.0))),
+            );
+        }
+        self.accounts.extend(
+            authorization_list
+                .iter()
+                .map(|authorization| authorization.inner().address),
+        );
+    }
+
+    fn add_prestate(&mut self, prestate: PrestateTrace) {
+        for (address, account) in prestate {
+            self.accounts.insert(address);
+            self.storage.extend(
+                account
+                    .storage
+                    .into_keys()
+                    .map(|slot| (address, U256::from_be_bytes(slot.0))),
+            );
+        }
+    }
+}
+
+impl EvmSimulationPreparer {
+    /// Loads the state the transactions are likely to read at `block` into
+    /// the shared state cache. Prefetching is best effort: reads it could not
+    /// load are left to execution.
+    pub(crate) async fn prefetch(
+        &self,
+        block: &Sealed<Header>,
+        transactions: &[Transaction],
+        state_overrides: &EvmStateOverrides,
+        block_overrides: &EvmBlockOverrides,
+    ) {
+        let Some(state_cache) = self.prefetch_cache() else {
+            return;
+        };
+
+        let coinbase = block_overrides.coinbase.unwrap_or(block.beneficiary);
+        let mut keys = PrefetchKeys::new(coinbase, transactions);
+        if self.prefetch == EvmPrefetch::PrestateTrace
+            && let Ok(prestates) = self
+                .trace_prestates(block, transactions, state_overrides, block_overrides)
+                .await
+        {
+            for prestate in prestates {
+                keys.add_prestate(prestate);
+            }
+        }
+
+        self.load_keys(state_cache, block.hash(), keys).await;
+    }
+
+    /// Prefetches a replay at the parent state. A prestate trace against the
+    /// parent would miss the effects of the preceding transactions, so only
+    /// the transactions themselves are used.
+    pub(crate) async fn prefetch_replay(
+        &self,
+        parent: &Sealed<Header>,
+        block: &Sealed<Header>,
+        transactions: impl IntoIterator<Item = &Transaction>,
+    ) {
+        let Some(state_cache) = self.prefetch_cache() else {
+            return;
+        };
+
+        let keys = PrefetchKeys::new(block.beneficiary, transactions);
+        self.load_keys(state_cache, parent.hash(), keys).await;
+    }
+
+    fn prefetch_cache(&self) -> Option<&EvmStateCache> {
+        self.state_cache
+            .as_ref()
+            .filter(|_| self.prefetch != EvmPrefetch::Disabled)
+    }
+
+    // Each transaction is traced on its own against the anchored state, so
+    // later bundle transactions may touch more than their trace reports.
+    async fn trace_prestates(
+        &self,
+        block: &Sealed<Header>,
+        transactions: &[Transaction],
+        state_overrides: &EvmStateOverrides,
+        block_overrides: &EvmBlockOverrides,
+    ) -> TransportResult<Vec<PrestateTrace>> {
+        let block_id = BlockId::Hash(block.hash().into());
+        let options = PrestateTraceOptions {
+            tracer: "prestateTracer",
+            state_overrides: (!state_overrides.is_empty())
+                .then(|| rpc_state_override(state_overrides)),
+            block_overrides: (!block_overrides.is_empty())
+                .then(|| rpc_block_overrides(block_overrides)),
+        };
+        let mut batch = self.provider.client().new_batch();
+        let waiters = transactions
+            .iter()
+            .map(|transaction| {
+                let mut request = estimation_request(
+                    transaction.from,
+                    transaction.to,
+                    transaction.nonce,
+                    transaction.value,
+                    transaction.data.clone(),
+                    transaction.chain_id,
+                    &transaction.variant,
+                );
+                request.gas = Some(transaction.gas_limit);
+                batch.add_call::<_, PrestateTrace>(
+                    "debug_traceCall",
+                    &(request, block_id, options.clone()),
+                )
+            })
+            .collect::<TransportResult<Vec<_>>>()?;
+        batch.send().await?;
+
+        let mut prestates = Vec::with_capacity(waiters.len());
+        for waiter in waiters {
+            prestates.push(waiter.await?);
+        }
+
+        Ok(prestates)
+    }
+
+    async fn load_keys(&self, state_cache: &EvmStateCache, block_hash: B256, keys: PrefetchKeys) {
+        let accounts = keys
+            .accounts
+            .into_iter()
+            .filter(|&address| !state_cache.accounts.contains(&(block_hash, address)))
+            .collect::<Vec<_>>();
+        let storage = keys
+            .storage
+            .into_iter()
+            .filter(|&(address, index)| {
+                !state_cache.storage.contains(&(block_hash, address, index))
+            })
+            .collect::<Vec<_>>();
+
+        for addresses in accounts.chunks(PREFETCH_BATCH_CALLS / CALLS_PER_ACCOUNT) {
+            if self
+                .load_accounts(state_cache, block_hash, addresses)
+                .await
+                .is_err()
+            {
+                return;
+            }
+        }
+        for slots in storage.chunks(PREFETCH_BATCH_CALLS) {
+            if self
+                .load_storage(state_cache, block_hash, slots)
+                .await
+                .is_err()
+            {
+                return;
+            }
+        }
+    }
+
+    // Mirrors `AlloyDB`, which reports every account as existing.
+    async fn load_accounts(
+        &self,
+        state_cache: &EvmStateCache,
+        block_hash: B256,
+        addresses: &[Address],
+    ) -> TransportResult<()> {
+        let block_id = BlockId::Hash(block_hash.into());
+        let mut batch = self.provider.client().new_batch();
+        let mut waiters = Vec::with_capacity(addresses.len());
+        for &address in addresses {
+            waiters.push((
+                address,
+                batch.add_call::<_, U256>("eth_getBalance", &(address, block_id))?,
+                batch.add_call::<_, U64>("eth_getTransactionCount", &(address, block_id))?,
+                batch.add_call::<_, Bytes>("eth_getCode", &(address, block_id))?,
+            ));
+        }
+        batch.send().await?;
+
+        for (address, balance, nonce, code) in waiters {
+            let code = Bytecode::new_raw(code.await?);
+            let account =
+                AccountInfo::new(balance.await?, nonce.await?.to(), code.hash_slow(), code);
+            state_cache
+                .accounts
+                .insert((block_hash, address), Some(account));
+        }
+
+        Ok(())
+    }
+
+    async fn load_storage(
+        &self,
+        state_cache: &EvmStateCache,
+        block_hash: B256,
+        slots: &[(Address, U256)],
+    ) -> TransportResult<()> {
+        let block_id = BlockId::Hash(block_hash.into());
+        let mut batch = self.provider.client().new_batch();
+        let waiters = slots
+            .iter()
+            .map(|&(address, index)| {
+                batch
+                    .add_call::<_, U256>("eth_getStorageAt", &(address, index, block_id))
+                    .map(|waiter| (address, index, waiter))
+            })
+            .collect::<TransportResult<Vec<_>>>()?;
+        batch.send().await?;
+
+        for (address, index, value) in waiters {
+            state_cache
+                .storage
+                .insert((block_hash, address, index), value.await?);
+        }
+
+        Ok(())
+    }
+}
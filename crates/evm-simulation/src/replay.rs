@@ -0,0 +1,230 @@
+use alloy::{
+    consensus::{Header, Sealed, Transaction as _, TxEnvelope},
+    network::ReceiptResponse,
+    primitives::B256,
+    providers::Provider,
+    rpc::types::{Block, BlockTransactions, Transaction as RpcTransaction},
+};
+use simulation_transaction::{Transaction, TransactionVariant};
+
+use crate::{
+    EvmBlockSelector, EvmPreparationError, EvmSimulationPreparer, resolve_block,
+    seal_and_validate_block,
+};
+
+impl EvmSimulationPreparer {
+    /// Loads a mined transaction together with the transactions that precede
+    /// it in its block and their receipts, anchored at the parent block state.
+    pub async fn prepare_replay(
+        &self,
+        transaction_hash: B256,
+    ) -> Result<PreparedEvmReplay, EvmPreparationError> {
+        let target = self
+            .provider
+            .get_transaction_by_hash(transaction_hash)
+            .await
+            .map_err(|error| {
+                EvmPreparationError::transaction_resolution(format!(
+                    "failed to fetch transaction {transaction_hash}: {error}"
+                ))
+            })?
+            .ok_or_else(|| {
+                EvmPreparationError::transaction_resolution(format!(
+                    "provider did not return transaction {transaction_hash}"
+                ))
+            })?;
+        let (Some(block_hash), Some(index)) = (target.block_hash, target.transaction_index) else {
+            return Err(EvmPreparationError::transaction_resolution(format!(
+                "transaction {transaction_hash} has not been mined"
+            )));
+        };
+
+        let Block {
+            header,
+            transactions,
+            ..
+        } = self
+            .provider
+            .get_block_by_hash(block_hash)
+            .full()
+            .await
+            .map_err(|_| {
+                EvmPreparationError::block_resolution(
+                    "provider request failed while resolving the replay block",
+                )
+            })?
+            .ok_or_else(|| {
+                EvmPreparationError::block_resolution(
+                    "provider did not return the block of the replayed transaction",
+                )
+            })?;
+        let BlockTransactions::Full(transactions) = transactions else {
+            return Err(EvmPreparationError::block_resolution(
+                "provider did not return full transactions for the replay block",
+            ));
+        };
+        let block = seal_and_validate_block(header.inner, header.hash)?;
+        let parent =
+            resolve_block(&self.provider, EvmBlockSelector::Hash(block.parent_hash)).await?;
+
+        let index = usize::try_from(index).map_err(|_| {
+            EvmPreparationError::transaction_resolution(format!(
+                "transaction index {index} does not fit into usize"
+            ))
+        })?;
+        let Some(mined) = transactions.get(index) else {
+            return Err(EvmPreparationError::transaction_resolution(format!(
+                "block {} does not contain transaction index {index}",
+                block.number
+            )));
+        };
+        if *mined.inner.inner().tx_hash() != transaction_hash {
+            return Err(EvmPreparationError::transaction_resolution(format!(
+                "block {} holds a different transaction at index {index}",
+                block.number
+            )));
+        }
+
+        let receipts = self
+            .provider
+            .get_block_receipts(block_hash.into())
+            .await
+            .map_err(|_| {
+                EvmPreparationError::block_resolution(
+                    "provider request failed while resolving the replay block receipts",
+                )
+            })?
+            .ok_or_else(|| {
+                EvmPreparationError::block_resolution(
+                    "provider did not return the receipts of the replay block",
+                )
+            })?;
+        if receipts.len() < index {
+            return Err(EvmPreparationError::block_resolution(format!(
+                "provider returned {} receipts for the {index} transactions before the target",
+                receipts.len()
+            )));
+        }
+
+        // Pre-EIP-155 legacy transactions carry no chain id of their own.
+        let preceding = transactions[..index]
+            .iter()
+            .zip(&receipts)
+            .map(|(transaction, receipt)| {
+                let transaction_hash = *transaction.inner.inner().tx_hash();
+                if receipt.transaction_hash() != transaction_hash {
+                    return Err(EvmPreparationError::block_resolution(format!(
+                        "provider returned a receipt for a different transaction than {transaction_hash}"
+                    )));
+                }
+
+                Ok(EvmReplayedTransaction {
+                    transaction: mined_transaction(transaction, self.chain.chain_id),
+                    receipt: EvmReplayedReceipt {
+                        transaction_hash,
+                        succeeded: receipt.status(),
+                        gas_used: receipt.gas_used(),
+                    },
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let target = mined_transaction(mined, self.chain.chain_id);
+        self.prefetch_replay(
+            &parent,
+            &block,
+            preceding
+                .iter()
+                .map(|replayed| &replayed.transaction)
+                .chain([&target]),
+        )
+        .await;
+
+        Ok(PreparedEvmReplay {
+            parent,
+            block,
+            preceding,
+            target,
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PreparedEvmReplay {
+    parent: Sealed<Header>,
+    block: Sealed<Header>,
+    preceding: Vec<EvmReplayedTransaction>,
+    target: Transaction,
+}
+
+/// A transaction mined before the replayed one, with the receipt its
+/// re-execution must reproduce.
+#[derive(Debug, Clone)]
+pub struct EvmReplayedTransaction {
+    pub transaction: Transaction,
+    pub receipt: EvmReplayedReceipt,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EvmReplayedReceipt {
+    pub transaction_hash: B256,
+    pub succeeded: bool,
+    pub gas_used: u64,
+}
+
+impl PreparedEvmReplay {
+    pub fn into_parts(
+        self,
+    ) -> (
+        Sealed<Header>,
+        Sealed<Header>,
+        Vec<EvmReplayedTransaction>,
+        Transaction,
+    ) {
+        (self.parent, self.block, self.preceding, self.target)
+    }
+}
+
+fn mined_transaction(transaction: &RpcTransaction, fallback_chain_id: u64) -> Transaction {
+    let envelope = transaction.inner.inner();
+    let variant = match envelope {
+        TxEnvelope::Legacy(signed) => TransactionVariant::Legacy {
+            gas_price: signed.tx().gas_price,
+        },
+        TxEnvelope::Eip2930(signed) => TransactionVariant::AccessList {
+            gas_price: signed.tx().gas_price,
+            access_list: signed.tx().access_list.0.clone(),
+        },
+        TxEnvelope::Eip1559(signed) => TransactionVariant::DynamicFee {
+            max_fee_per_gas: signed.tx().max_fee_per_gas,
+            max_priority_fee_per_gas: signed.tx().max_priority_fee_per_gas,
+            access_list: signed.tx().access_list.0.clone(),
+        },
+        TxEnvelope::Eip4844(signed) => {
+            let tx = signed.tx().tx();
+            TransactionVariant::Blob {
+                max_fee_per_gas: tx.max_fee_per_gas,
+                max_priority_fee_per_gas: tx.max_priority_fee_per_gas,
+                max_fee_per_blob_gas: tx.max_fee_per_blob_gas,
+                access_list: tx.access_list.0.clone(),
+                blob_versioned_hashes: tx.blob_versioned_hashes.clone(),
+            }
+        }
+        TxEnvelope::Eip7702(signed) => TransactionVariant::SetCode {
+            max_fee_per_gas: signed.tx().max_fee_per_gas,
+            max_priority_fee_per_gas: signed.tx().max_priority_fee_per_gas,
+            access_list: signed.tx().access_list.0.clone(),
+            authorization_list: signed.tx().authorization_list.clone(),
+        },
+    };
+
+    Transaction {
+        chain_id: envelope.chain_id().unwrap_or(fallback_chain_id),
+        from: transaction.inner.signer(),
+        to: envelope.to(),
+        nonce: envelope.nonce(),
+        gas_limit: envelope.gas_limit(),
+        value: envelope.value(),
+        data: envelope.input().clone(),
+        variant,
+    }
+}
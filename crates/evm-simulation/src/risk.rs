@@ -0,0 +1,135 @@
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+use alloy::{
+    primitives::{Address, B256, KECCAK256_EMPTY},
+    providers::{Provider, RootProvider},
+};
+use revm::{Database, handler::EvmTr, state::EvmState};
+use simulation_changes::{OwnershipTransfer, RiskAccountKind};
+use tokio::runtime::Handle;
+
+use crate::{
+    EvmExecutionObservation, EvmExecutionObserver, EvmExecutionOutput, EvmSimulationError,
+};
+
+/// Accounts that had no code before the transaction and have code after it.
+pub(crate) fn created_contracts(state: &EvmState) -> HashSet<Address> {
+    state
+        .iter()
+        .filter(|(_, account)| {
+            is_empty_code_hash(account.original_info.code_hash)
+                && !is_empty_code_hash(account.info.code_hash)
+                && !account.is_selfdestructed()
+        })
+        .map(|(address, _)| *address)
+        .collect()
+}
+
+pub(crate) fn ownership_transfers(
+    observations: &[EvmExecutionObservation],
+) -> Vec<OwnershipTransfer> {
+    observations
+        .iter()
+        .filter_map(|observation| {
+            let EvmExecutionObservation::Log {
+                address,
+                topics,
+                data,
+            } = observation
+            else {
+                return None;
+            };
+            OwnershipTransfer::from_log(*address, topics, data)
+        })
+        .collect()
+}
+
+/// Tells whether a contract was deployed after `block_number`, by reading its
+/// code there from the provider.
+#[derive(Debug, Clone)]
+pub(crate) struct FreshContractProbe {
+    provider: RootProvider,
+    runtime_handle: Handle,
+    block_number: u64,
+}
+
+impl FreshContractProbe {
+    pub(crate) fn new(provider: RootProvider, runtime_handle: Handle, block_number: u64) -> Self {
+        Self {
+            provider,
+            runtime_handle,
+            block_number,
+        }
+    }
+
+    fn deployed_since(&self, account: Address) -> Result<bool, EvmSimulationError> {
+        let code = self
+            .runtime_handle
+            .block_on(
+                self.provider
+                    .get_code_at(account)
+                    .number(self.block_number)
+                    .into_future(),
+            )
+            .map_err(|error| {
+                EvmSimulationError::state_access_error(format!(
+                    "failed to read code of {account} at block {} during risk analysis: {error}",
+                    self.block_number
+                ))
+            })?;
+
+        Ok(code.is_empty())
+    }
+}
+
+/// Reads whether each account holds code once the transaction's transition
+/// has been applied. Contracts among `operators` that the transaction did not
+/// create are checked with `fresh_probe`; without one they are not fresh.
+pub(crate) fn read_account_kinds(
+    output: &mut EvmExecutionOutput<EvmExecutionObserver>,
+    accounts: &[Address],
+    created: &HashSet<Address>,
+    operators: &BTreeSet<Address>,
+    fresh_probe: Option<&FreshContractProbe>,
+) -> Result<HashMap<Address, RiskAccountKind>, EvmSimulationError> {
+    let database = &mut output.evm_mut().ctx_mut().journaled_state.database;
+    let mut kinds = HashMap::with_capacity(accounts.len());
+
+    for &account in accounts {
+        let info = database.basic(account).map_err(|error| {
+            EvmSimulationError::state_access_error(format!(
+                "failed to read account {account} during risk analysis: {error}"
+            ))
+        })?;
+        let code = match info {
+            Some(info) if !is_empty_code_hash(info.code_hash) => match info.code {
+                Some(code) => Some(code),
+                None => Some(database.code_by_hash(info.code_hash).map_err(|error| {
+                    EvmSimulationError::state_access_error(format!(
+                        "failed to read code of {account} during risk analysis: {error}"
+                    ))
+                })?),
+            },
+            Some(_) | None => None,
+        };
+
+        let kind = match code {
+            Some(code) if !code.is_empty() && !code.is_eip7702() => {
+                let fresh = match fresh_probe {
+                    _ if created.contains(&account) => true,
+                    Some(probe) if operators.contains(&account) => probe.deployed_since(account)?,
+                    _ => false,
+                };
+                RiskAccountKind::Contract { fresh }
+            }
+            Some(_) | None => RiskAccountKind::Eoa,
+        };
+        kinds.insert(account, kind);
+    }
+
+    Ok(kinds)
+}
+
+fn is_empty_code_hash(code_hash: B256) -> bool {
+    code_hash == KECCAK256_EMPTY || code_hash == B256::ZERO
+}
@@ -1,4 +1,7 @@
-use crate::Change;
+use crate::{
+    BalanceChange, Change, EvmAccountDiff, EvmCallFrame, EvmSimulationLog, NetAssetChange,
+    RiskWarning,
+};
 use alloy_primitives::{B256, Bytes, U256};
 use simulation_execution::ExecutionOutcome;
 
@@ -83,15 +86,63 @@ pub struct EvmExecution {
 
 pub type EvmExecutionOutcome = ExecutionOutcome<EvmExecutedDetails, EvmExecutionFailure>;
 
+/// Optional outputs requested on top of the execution and its changes.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct EvmSimulationIncludes {
+    pub call_trace: bool,
+    pub state_diff: bool,
+    pub logs: bool,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct EvmSimulation {
     pub execution: EvmExecution,
     pub changes: Vec<Change>,
+    /// Net asset movements of `changes` grouped by account and asset.
+    pub balance_changes: Vec<BalanceChange>,
+    /// Risk rules that matched `changes`.
+    pub warnings: Vec<RiskWarning>,
+    pub call_trace: Option<EvmCallFrame>,
+    pub state_diff: Option<Vec<EvmAccountDiff>>,
+    pub logs: Option<Vec<EvmSimulationLog>>,
 }
 
 impl EvmSimulation {
     pub fn new(execution: EvmExecution, changes: Vec<Change>) -> Self {
-        Self { execution, changes }
+        Self {
+            execution,
+            changes,
+            balance_changes: Vec::new(),
+            warnings: Vec::new(),
+            call_trace: None,
+            state_diff: None,
+            logs: None,
+        }
+    }
+
+    pub fn with_balance_changes(mut self, balance_changes: Vec<BalanceChange>) -> Self {
+        self.balance_changes = balance_changes;
+        self
+    }
+
+    pub fn with_warnings(mut self, warnings: Vec<RiskWarning>) -> Self {
+        self.warnings = warnings;
+        self
+    }
+
+    pub fn with_call_trace(mut self, call_trace: Option<EvmCallFrame>) -> Self {
+        self.call_trace = call_trace;
+        self
+    }
+
+    pub fn with_state_diff(mut self, state_diff: Option<Vec<EvmAccountDiff>>) -> Self {
+        self.state_diff = state_diff;
+        self
+    }
+
+    pub fn with_logs(mut self, logs: Option<Vec<EvmSimulationLog>>) -> Self {
+        self.logs = logs;
+        self
     }
 
     pub fn execution(&self) -> &EvmExecution {
@@ -102,7 +153,48 @@ impl EvmSimulation {
         &self.changes
     }
 
+    pub fn balance_changes(&self) -> &[BalanceChange] {
+        &self.balance_changes
+    }
+
+    pub fn warnings(&self) -> &[RiskWarning] {
+        &self.warnings
+    }
+
+    pub fn call_trace(&self) -> Option<&EvmCallFrame> {
+        self.call_trace.as_ref()
+    }
+
+    pub fn state_diff(&self) -> Option<&[EvmAccountDiff]> {
+        self.state_diff.as_deref()
+    }
+
+    pub fn logs(&self) -> Option<&[EvmSimulationLog]> {
+        self.logs.as_deref()
+    }
+
     pub fn into_parts(self) -> (EvmExecution, Vec<Change>) {
         (self.execution, self.changes)
     }
 }
+
+/// Results of an ordered bundle. Execution stops after the first transaction
+/// that does not succeed, so `transactions` may be shorter than the bundle.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EvmBundleSimulation {
+    pub transactions: Vec<EvmSimulation>,
+    pub net_changes: Vec<NetAssetChange>,
+}
+
+impl EvmBundleSimulation {
+    pub fn new(transactions: Vec<EvmSimulation>, net_changes: Vec<NetAssetChange>) -> Self {
+        Self {
+            transactions,
+            net_changes,
+        }
+    }
+
+    pub fn into_parts(self) -> (Vec<EvmSimulation>, Vec<NetAssetChange>) {
+        (self.transactions, self.net_changes)
+    }
+}
@@ -1,79 +1,429 @@
+use std::sync::Arc;
+
 use alloy::{
     consensus::{BlockHeader, Header, Sealed},
     providers::RootProvider,
 };
-use alloy_chains::Chain;
 use contract_standards::{MetadataRequests, state_requirements, verify};
+use revm::context_interface::result::{ExecutionResult, HaltReason};
 use simulation_changes::{
-    ChangeMetadata, PositionedChange, into_enriched_changes, sort_changes_by_position,
+    ChangeMetadata, PositionedChange, RiskInput, approved_operators, assess_risks, balance_changes,
+    into_enriched_changes, net_asset_changes, risk_accounts, sort_changes_by_position,
 };
 use simulation_transaction::Transaction;
 use tokio::runtime::Handle;
 
 use crate::{
-    EvmBlockAnchor, EvmExecutionError, EvmExecutionObserver, EvmNativeChangeError, EvmSimulation,
-    EvmSimulationError, EvmStateSource, EvmTransactionExecutor, PreparedEvmInput,
+    EvmAccessListCreation, EvmAccessListInspector, EvmBlockAnchor, EvmBlockOverrides,
+    EvmBundleSimulation, EvmExecution, EvmExecutionError, EvmExecutionObserver,
+    EvmNativeChangeError, EvmReplayedReceipt, EvmSimulation, EvmSimulationError,
+    EvmSimulationIncludes, EvmSnapshotStore, EvmStateCache, EvmStateOverrides, EvmStateSource,
+    EvmTransactionExecutor, NativeMetadata, PreparedEvmBundle, PreparedEvmInput, PreparedEvmReplay,
+    RiskConfig, SnapshotMode,
+    access_list::{TouchedState, optimal_access_list, with_access_list},
     changes::{
-        analyze_native_changes, collect_standard_candidates, load_standard_metadata,
-        read_standard_state_values,
+        analyze_delegation_changes, analyze_native_changes, analyze_proxy_changes,
+        collect_standard_candidates, load_standard_metadata, read_standard_state_values,
     },
+    config::EvmChainConfig,
+    execution::{EvmSnapshotRecorder, warm_addresses},
+    logs::collect_logs,
     outcome::{build_execution, build_not_executed},
+    risk::{FreshContractProbe, created_contracts, ownership_transfers, read_account_kinds},
+    state_diff::build_state_diff,
 };
 
+// Attaching an access list changes gas costs, which can change the accessed
+// state of gas-dependent code; generation stops once the list is stable.
+const MAX_ACCESS_LIST_EXECUTIONS: usize = 4;
+
 #[derive(Debug, Clone)]
 pub struct EvmSimulator {
     provider: RootProvider,
     runtime_handle: Handle,
-    chain_id: u64,
+    chain: EvmChainConfig,
+    risk: RiskConfig,
+    state_cache: EvmStateCache,
+    snapshot_store: Option<EvmSnapshotStore>,
 }
 
 impl EvmSimulator {
-    pub fn new(provider: RootProvider, runtime_handle: Handle) -> Self {
+    pub fn new(provider: RootProvider, runtime_handle: Handle, chain: EvmChainConfig) -> Self {
         Self {
             provider,
             runtime_handle,
-            chain_id: Chain::mainnet().id(),
+            chain,
+            risk: RiskConfig::default(),
+            state_cache: EvmStateCache::default(),
+            snapshot_store: None,
+        }
+    }
+
+    /// Shares upstream state reads with every simulator holding the same cache.
+    pub fn with_state_cache(mut self, state_cache: EvmStateCache) -> Self {
+        self.state_cache = state_cache;
+        self
+    }
+
+    pub fn with_risk_config(mut self, risk: RiskConfig) -> Self {
+        self.risk = risk;
+        self
+    }
+
+    /// Records the upstream reads of every successful simulation into `store`
+    /// or, when it replays, executes against its recorded snapshots instead
+    /// of the provider. Reads a snapshot did not record fail the simulation.
+    pub fn with_snapshot_store(mut self, store: EvmSnapshotStore) -> Self {
+        self.snapshot_store = Some(store);
+        self
+    }
+
+    pub fn chain_id(&self) -> u64 {
+        self.chain.chain_id
+    }
+
+    pub fn native_currency(&self) -> &NativeMetadata {
+        &self.chain.native_currency
+    }
+
+    pub fn simulate(
+        &self,
+        input: PreparedEvmInput,
+        includes: EvmSimulationIncludes,
+    ) -> Result<EvmSimulation, EvmSimulationError> {
+        let (block, transaction, state_overrides, block_overrides) = input.into_parts();
+        let recorder = self.snapshot_recorder(&block);
+        let executor = self.build_executor(
+            self.state_source(&block, recorder.clone())?,
+            &block,
+            &state_overrides,
+            &block_overrides,
+            new_observer(includes),
+        )?;
+        let (simulation, _) = simulate_step(
+            executor,
+            &self.chain,
+            &self.risk,
+            self.fresh_contract_probe(&block).as_ref(),
+            &block,
+            &transaction,
+            includes,
+        )?;
+        self.save_snapshot(recorder)?;
+
+        Ok(simulation)
+    }
+
+    pub fn simulate_bundle(
+        &self,
+        input: PreparedEvmBundle,
+        includes: EvmSimulationIncludes,
+    ) -> Result<EvmBundleSimulation, EvmSimulationError> {
+        let (block, transactions, state_overrides, block_overrides) = input.into_parts();
+        let recorder = self.snapshot_recorder(&block);
+        let mut executor = Some(self.build_executor(
+            self.state_source(&block, recorder.clone())?,
+            &block,
+            &state_overrides,
+            &block_overrides,
+            new_observer(includes),
+        )?);
+        let fresh_probe = self.fresh_contract_probe(&block);
+        let mut simulations = Vec::with_capacity(transactions.len());
+
+        for transaction in &transactions {
+            let Some(current) = executor.take() else {
+                break;
+            };
+            let (simulation, next) = simulate_step(
+                current,
+                &self.chain,
+                &self.risk,
+                fresh_probe.as_ref(),
+                &block,
+                transaction,
+                includes,
+            )?;
+            simulations.push(simulation);
+            executor = next;
         }
+
+        self.save_snapshot(recorder)?;
+
+        let net_changes = net_asset_changes(simulations.iter().flat_map(EvmSimulation::changes));
+        Ok(EvmBundleSimulation::new(simulations, net_changes))
+    }
+
+    /// Re-executes the transactions mined before the target in its block, after
+    /// the block's pre-block system calls, and then simulates the target. The
+    /// preceding transactions run without inspection or analysis.
+    pub fn simulate_replay(
+        &self,
+        input: PreparedEvmReplay,
+        includes: EvmSimulationIncludes,
+    ) -> Result<EvmSimulation, EvmSimulationError> {
+        let (parent, block, preceding, target) = input.into_parts();
+        // The replayed block executes on its parent's state, so the snapshot
+        // is recorded at the parent.
+        let recorder = self.snapshot_recorder(&parent);
+        let mut executor = EvmTransactionExecutor::new_for_mined_block(
+            self.state_source(&parent, recorder.clone())?,
+            &block,
+            &self.chain,
+            (),
+        )
+        .map_err(map_execution_error)?;
+        executor
+            .apply_pre_block_system_calls(block.inner())
+            .map_err(map_execution_error)?;
+
+        for replayed in &preceding {
+            let mut output = match executor.execute(&replayed.transaction) {
+                Ok(output) => output,
+                Err(EvmExecutionError::NotExecuted(error)) => {
+                    return Err(EvmSimulationError::execution_error(format!(
+                        "replay diverged: transaction {} was not executed: {error}",
+                        replayed.receipt.transaction_hash
+                    )));
+                }
+                Err(error) => return Err(map_execution_error(error)),
+            };
+            verify_replayed_result(output.result(), replayed.receipt)?;
+            output.commit_transition().map_err(map_execution_error)?;
+            executor = output.into_executor(()).map_err(map_execution_error)?;
+        }
+
+        let (simulation, _) = simulate_step(
+            executor.with_inspector(new_observer(includes)),
+            &self.chain,
+            &self.risk,
+            self.fresh_contract_probe(&block).as_ref(),
+            &block,
+            &target,
+            includes,
+        )?;
+        self.save_snapshot(recorder)?;
+
+        Ok(simulation)
     }
 
-    pub fn simulate(&self, input: PreparedEvmInput) -> Result<EvmSimulation, EvmSimulationError> {
-        let (block, transaction) = input.into_parts();
-        simulate_prepared(
-            &self.provider,
-            &self.runtime_handle,
-            self.chain_id,
+    /// Generates an access list for the transaction by executing it without
+    /// one and then with the generated list until the list no longer changes.
+    pub fn create_access_list(
+        &self,
+        input: PreparedEvmInput,
+    ) -> Result<EvmAccessListCreation, EvmSimulationError> {
+        let (block, transaction, state_overrides, block_overrides) = input.into_parts();
+        let warm = warm_addresses(
+            &self.chain,
+            &block_overrides.apply(block.inner()),
+            &transaction,
+        )
+        .map_err(map_execution_error)?;
+        let recorder = self.snapshot_recorder(&block);
+        let execute = |access_list: Vec<_>| {
+            self.execute_for_access_list(
+                &block,
+                &with_access_list(&transaction, access_list),
+                &state_overrides,
+                &block_overrides,
+                recorder.clone(),
+            )
+        };
+
+        let (_, gas_used_without_access_list, touched) = execute(Vec::new())?;
+        let mut access_list = optimal_access_list(touched, &warm);
+        let mut executions = 1;
+        let (execution, gas_used_with_access_list) = loop {
+            let (execution, gas_used, touched) = execute(access_list.clone())?;
+            executions += 1;
+            let next = optimal_access_list(touched, &warm);
+            if next == access_list || executions == MAX_ACCESS_LIST_EXECUTIONS {
+                break (execution, gas_used);
+            }
+            access_list = next;
+        };
+        self.save_snapshot(recorder)?;
+
+        Ok(EvmAccessListCreation {
+            execution,
+            access_list,
+            gas_used_without_access_list,
+            gas_used_with_access_list,
+        })
+    }
+
+    fn execute_for_access_list(
+        &self,
+        block: &Sealed<Header>,
+        transaction: &Transaction,
+        state_overrides: &EvmStateOverrides,
+        block_overrides: &EvmBlockOverrides,
+        recorder: Option<EvmSnapshotRecorder>,
+    ) -> Result<AccessListExecution, EvmSimulationError> {
+        let executor = self.build_executor(
+            self.state_source(block, recorder)?,
             block,
-            transaction,
+            state_overrides,
+            block_overrides,
+            EvmAccessListInspector::new(),
+        )?;
+        let mut output = match executor.execute(transaction) {
+            Ok(output) => output,
+            Err(EvmExecutionError::NotExecuted(error)) => {
+                let execution = build_not_executed(self.chain.chain_id, block, transaction, error);
+                return Ok((execution, 0, TouchedState::new()));
+            }
+            Err(error) => return Err(map_execution_error(error)),
+        };
+        let touched = output.take_inspector().into_touched();
+        let gas_used = output.result().gas_used();
+        let execution = build_execution(
+            output.result().clone(),
+            self.chain.chain_id,
+            block,
+            output.fee_settlement(),
+        );
+
+        Ok((execution, gas_used, touched))
+    }
+
+    /// The state at `anchor`: the recorded snapshot when replaying, the
+    /// provider otherwise.
+    fn state_source(
+        &self,
+        anchor: &Sealed<Header>,
+        recorder: Option<EvmSnapshotRecorder>,
+    ) -> Result<EvmStateSource, EvmSimulationError> {
+        let Some(store) = self.snapshot_store_in(SnapshotMode::Replay) else {
+            return Ok(EvmStateSource::new_recorded(
+                self.provider.clone(),
+                self.runtime_handle.clone(),
+                EvmBlockAnchor::new(anchor.number(), anchor.hash()),
+                self.state_cache.clone(),
+                recorder,
+            ));
+        };
+
+        let snapshot = store
+            .load(self.chain.chain_id, anchor.hash())
+            .map_err(|error| {
+                EvmSimulationError::internal(format!("failed to load state snapshot: {error}"))
+            })?
+            .ok_or_else(|| {
+                EvmSimulationError::block_context_error(format!(
+                    "no state snapshot was recorded at block {}",
+                    anchor.hash()
+                ))
+            })?;
+
+        Ok(EvmStateSource::from_snapshot(
+            Arc::new(snapshot),
+            self.runtime_handle.clone(),
+        ))
+    }
+
+    fn snapshot_recorder(&self, anchor: &Sealed<Header>) -> Option<EvmSnapshotRecorder> {
+        self.snapshot_store_in(SnapshotMode::Record)
+            .map(|_| EvmSnapshotRecorder::new(self.chain.chain_id, anchor))
+    }
+
+    /// Replayed snapshots cannot read older blocks, so only contracts the
+    /// transaction created count as fresh then.
+    fn fresh_contract_probe(&self, block: &Sealed<Header>) -> Option<FreshContractProbe> {
+        if !self.risk.approval_for_all_to_fresh_contract.enabled
+            || self.snapshot_store_in(SnapshotMode::Replay).is_some()
+        {
+            return None;
+        }
+
+        Some(FreshContractProbe::new(
+            self.provider.clone(),
+            self.runtime_handle.clone(),
+            block
+                .number()
+                .saturating_sub(self.risk.fresh_contract_max_age_blocks),
+        ))
+    }
+
+    fn snapshot_store_in(&self, mode: SnapshotMode) -> Option<&EvmSnapshotStore> {
+        self.snapshot_store
+            .as_ref()
+            .filter(|store| store.mode() == mode)
+    }
+
+    fn save_snapshot(
+        &self,
+        recorder: Option<EvmSnapshotRecorder>,
+    ) -> Result<(), EvmSimulationError> {
+        let (Some(store), Some(recorder)) = (&self.snapshot_store, recorder) else {
+            return Ok(());
+        };
+
+        store.save(recorder.snapshot()).map_err(|error| {
+            EvmSimulationError::internal(format!("failed to save state snapshot: {error}"))
+        })
+    }
+
+    fn build_executor<INSP>(
+        &self,
+        mut state_source: EvmStateSource,
+        block: &Sealed<Header>,
+        state_overrides: &EvmStateOverrides,
+        block_overrides: &EvmBlockOverrides,
+        inspector: INSP,
+    ) -> Result<EvmTransactionExecutor<INSP>, EvmSimulationError> {
+        state_source
+            .apply_state_overrides(state_overrides)
+            .map_err(map_execution_error)?;
+
+        EvmTransactionExecutor::new(
+            state_source,
+            block.clone(),
+            block_overrides,
+            &self.chain,
+            inspector,
         )
+        .map_err(map_execution_error)
     }
 }
 
-fn simulate_prepared(
-    provider: &RootProvider,
-    runtime_handle: &Handle,
-    chain_id: u64,
-    block: Sealed<Header>,
-    transaction: Transaction,
-) -> Result<EvmSimulation, EvmSimulationError> {
-    let state_source = EvmStateSource::new(
-        provider.clone(),
-        runtime_handle.clone(),
-        EvmBlockAnchor::new(block.number(), block.hash()),
-    );
-    let executor = EvmTransactionExecutor::new(
-        state_source,
-        block.clone(),
-        chain_id,
-        EvmExecutionObserver::new(),
-    )
-    .map_err(map_execution_error)?;
-
-    let mut output = match executor.execute(&transaction) {
+fn new_observer(includes: EvmSimulationIncludes) -> EvmExecutionObserver {
+    if includes.call_trace {
+        EvmExecutionObserver::with_call_trace()
+    } else {
+        EvmExecutionObserver::new()
+    }
+}
+
+// The execution, its gas used and the state it accessed.
+type AccessListExecution = (EvmExecution, u64, TouchedState);
+
+type EvmStepOutput = (
+    EvmSimulation,
+    Option<EvmTransactionExecutor<EvmExecutionObserver>>,
+);
+
+// Hands back an executor for the following transaction only when this one
+// succeeded and its state transition was committed.
+fn simulate_step(
+    executor: EvmTransactionExecutor<EvmExecutionObserver>,
+    chain: &EvmChainConfig,
+    risk: &RiskConfig,
+    fresh_probe: Option<&FreshContractProbe>,
+    block: &Sealed<Header>,
+    transaction: &Transaction,
+    includes: EvmSimulationIncludes,
+) -> Result<EvmStepOutput, EvmSimulationError> {
+    let mut output = match executor.execute(transaction) {
         Ok(output) => output,
         Err(EvmExecutionError::NotExecuted(error)) => {
-            return Ok(EvmSimulation::new(
-                build_not_executed(chain_id, &block, &transaction, error),
-                Vec::new(),
+            return Ok((
+                EvmSimulation::new(
+                    build_not_executed(chain.chain_id, block, transaction, error),
+                    Vec::new(),
+                ),
+                None,
             ));
         }
         Err(error) => return Err(map_execution_error(error)),
@@ -81,52 +431,141 @@ fn simulate_prepared(
 
     let execution = build_execution(
         output.result().clone(),
-        chain_id,
-        &block,
+        chain.chain_id,
+        block,
         output.fee_settlement(),
     );
+    let call_trace = output.call_trace();
+    let state_diff = if includes.state_diff {
+        Some(build_state_diff(
+            output.transition().map_err(map_execution_error)?,
+        ))
+    } else {
+        None
+    };
+    // EIP-7702 authorizations apply even when the call fails, so delegations
+    // are analyzed before the outcome is checked.
+    let mut positioned_changes = analyze_delegation_changes(&mut output, transaction)?;
     if !output.result().is_success() {
-        return Ok(EvmSimulation::new(execution, Vec::new()));
+        // A failed transaction reverts every log it emitted; only its
+        // delegation changes remain.
+        let changes = positioned_changes
+            .into_iter()
+            .map(|positioned| positioned.change)
+            .collect();
+        return Ok((
+            EvmSimulation::new(execution, changes)
+                .with_call_trace(call_trace)
+                .with_state_diff(state_diff)
+                .with_logs(includes.logs.then(Vec::new)),
+            None,
+        ));
     }
 
-    let candidates = collect_standard_candidates(&output.observations())?;
+    let observations = output.observations();
+    let candidates = collect_standard_candidates(&observations)?;
     let requirements = state_requirements(&candidates);
-    let mut positioned_changes =
-        analyze_native_changes(&output).map_err(map_native_change_error)?;
+    positioned_changes.extend(analyze_native_changes(&output).map_err(map_native_change_error)?);
+    positioned_changes.extend(analyze_proxy_changes(&output, &observations)?);
+    let created_contracts = created_contracts(output.transition().map_err(map_execution_error)?);
 
     let before_token_state =
-        read_standard_state_values(output.evm_mut(), &transaction, chain_id, &requirements)?;
+        read_standard_state_values(output.evm_mut(), transaction, chain.chain_id, &requirements)?;
 
     output.apply_transition().map_err(map_execution_error)?;
 
     let after_token_state =
-        read_standard_state_values(output.evm_mut(), &transaction, chain_id, &requirements)?;
+        read_standard_state_values(output.evm_mut(), transaction, chain.chain_id, &requirements)?;
     let standard_changes = verify(&candidates, &before_token_state, &after_token_state)?;
     let metadata_requests = MetadataRequests::from_changes(&standard_changes);
     positioned_changes.extend(standard_changes.into_iter().map(PositionedChange::from));
 
+    sort_changes_by_position(&mut positioned_changes);
+    let logs = includes
+        .logs
+        .then(|| collect_logs(&observations, &positioned_changes));
+    let ownership_transfers = ownership_transfers(&observations);
+
     let changes = if positioned_changes.is_empty() {
         Vec::new()
     } else {
-        sort_changes_by_position(&mut positioned_changes);
-        let standard_metadata =
-            load_standard_metadata(output.evm_mut(), &transaction, chain_id, metadata_requests)?;
-        let metadata = ChangeMetadata::new(native_metadata(chain_id), standard_metadata);
+        let standard_metadata = load_standard_metadata(
+            output.evm_mut(),
+            transaction,
+            chain.chain_id,
+            metadata_requests,
+        )?;
+        let metadata = ChangeMetadata::new(chain.native_currency.clone(), standard_metadata);
         into_enriched_changes(positioned_changes, &metadata)
     };
+    let balance_changes = balance_changes(&changes, &before_token_state, &after_token_state);
 
-    Ok(EvmSimulation::new(execution, changes))
+    let called_target = transaction.to.filter(|_| !transaction.data.is_empty());
+    let accounts = read_account_kinds(
+        &mut output,
+        &risk_accounts(&changes, called_target),
+        &created_contracts,
+        &approved_operators(&changes),
+        fresh_probe,
+    )?;
+    let warnings = assess_risks(
+        risk,
+        &RiskInput {
+            sender: transaction.from,
+            called_target,
+            changes: &changes,
+            balance_changes: &balance_changes,
+            ownership_transfers: &ownership_transfers,
+            accounts: &accounts,
+        },
+    );
+
+    let executor = output
+        .into_executor(new_observer(includes))
+        .map_err(map_execution_error)?;
+
+    Ok((
+        EvmSimulation::new(execution, changes)
+            .with_balance_changes(balance_changes)
+            .with_warnings(warnings)
+            .with_call_trace(call_trace)
+            .with_state_diff(state_diff)
+            .with_logs(logs),
+        Some(executor),
+    ))
 }
 
-fn native_metadata(chain_id: u64) -> crate::NativeMetadata {
-    match chain_id {
-        1 => crate::NativeMetadata {
-            name: Some("Ether".to_string()),
-            symbol: Some("ETH".to_string()),
-            decimals: Some(18),
-        },
-        _ => crate::NativeMetadata::default(),
+/// Fails when a re-executed transaction did not end as its receipt records.
+fn verify_replayed_result(
+    result: &ExecutionResult<HaltReason>,
+    receipt: EvmReplayedReceipt,
+) -> Result<(), EvmSimulationError> {
+    let EvmReplayedReceipt {
+        transaction_hash,
+        succeeded,
+        gas_used,
+    } = receipt;
+    let diverged = |detail: String| {
+        EvmSimulationError::execution_error(format!(
+            "replay diverged: transaction {transaction_hash} {detail}"
+        ))
+    };
+    if result.is_success() != succeeded {
+        let outcome_name = |succeeded: bool| if succeeded { "succeeded" } else { "failed" };
+        return Err(diverged(format!(
+            "{} but its receipt reports it {}",
+            outcome_name(result.is_success()),
+            outcome_name(succeeded)
+        )));
     }
+    if result.gas_used() != gas_used {
+        return Err(diverged(format!(
+            "used {} gas but its receipt reports {gas_used}",
+            result.gas_used()
+        )));
+    }
+
+    Ok(())
 }
 
 fn map_native_change_error(error: EvmNativeChangeError) -> EvmSimulationError {
@@ -142,9 +581,6 @@ fn map_native_change_error(error: EvmNativeChangeError) -> EvmSimulationError {
 
 fn map_execution_error(error: EvmExecutionError) -> EvmSimulationError {
     match error {
-        EvmExecutionError::UnsupportedChain(chain_id) => EvmSimulationError::not_supported(
-            format!("only Ethereum mainnet is supported now, got chain_id={chain_id}"),
-        ),
         EvmExecutionError::UnsupportedHardfork(hardfork) => EvmSimulationError::not_ready(format!(
             "hardfork {hardfork} is not mapped to revm::SpecId yet"
         )),
@@ -162,6 +598,9 @@ fn map_execution_error(error: EvmExecutionError) -> EvmSimulationError {
         EvmExecutionError::TransitionNotApplicable => EvmSimulationError::execution_error(
             "transaction transition is only applicable to a successful execution",
         ),
+        EvmExecutionError::TransitionPending => EvmSimulationError::execution_error(
+            "transaction transition must be applied before executing the next transaction",
+        ),
         EvmExecutionError::NotExecuted(_) => EvmSimulationError::internal(
             "invalid transaction was unexpectedly treated as an outer error",
         ),
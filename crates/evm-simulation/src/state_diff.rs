@@ -0,0 +1,84 @@
+use std::collections::BTreeMap;
+
+use alloy::primitives::{Address, B256, KECCAK256_EMPTY, U256};
+use revm::state::EvmState;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EvmValueDiff<T> {
+    pub before: T,
+    pub after: T,
+}
+
+/// Account fields and storage slots changed by a transaction. Balance, nonce
+/// and code hash are always reported so the account state can be read off
+/// the diff alone.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EvmAccountDiff {
+    pub address: Address,
+    pub balance: EvmValueDiff<U256>,
+    pub nonce: EvmValueDiff<u64>,
+    pub code_hash: EvmValueDiff<B256>,
+    pub storage: BTreeMap<U256, EvmValueDiff<U256>>,
+}
+
+pub(crate) fn build_state_diff(state: &EvmState) -> Vec<EvmAccountDiff> {
+    let mut accounts = state
+        .iter()
+        .filter_map(|(address, account)| {
+            let original = &account.original_info;
+            // A self-destructed account is removed once the transition commits.
+            let (balance, nonce, code_hash) = if account.is_selfdestructed() {
+                (U256::ZERO, 0, KECCAK256_EMPTY)
+            } else {
+                (
+                    account.info.balance,
+                    account.info.nonce,
+                    account.info.code_hash,
+                )
+            };
+            let storage = account
+                .storage
+                .iter()
+                .map(|(slot, value)| {
+                    let after = if account.is_selfdestructed() {
+                        U256::ZERO
+                    } else {
+                        value.present_value
+                    };
+                    (
+                        *slot,
+                        EvmValueDiff {
+                            before: value.original_value,
+                            after,
+                        },
+                    )
+                })
+                .filter(|(_, diff)| diff.before != diff.after)
+                .collect::<BTreeMap<_, _>>();
+
+            let unchanged = original.balance == balance
+                && original.nonce == nonce
+                && original.code_hash == code_hash
+                && storage.is_empty();
+            (!unchanged).then(|| EvmAccountDiff {
+                address: *address,
+                balance: EvmValueDiff {
+                    before: original.balance,
+                    after: balance,
+                },
+                nonce: EvmValueDiff {
+                    before: original.nonce,
+                    after: nonce,
+                },
+                code_hash: EvmValueDiff {
+                    before: original.code_hash,
+                    after: code_hash,
+                },
+                storage,
+            })
+        })
+        .collect::<Vec<_>>();
+    accounts.sort_by_key(|account| account.address);
+
+    accounts
+}
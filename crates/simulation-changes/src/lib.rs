@@ -1,7 +1,25 @@
-use alloy_primitives::{Address, U256};
-use contract_standards::{Position, PositionedStandardChange, StandardChange, StandardMetadata};
+use std::collections::BTreeMap;
 
-pub use contract_standards::{Erc20Metadata, Erc721CollectionMetadata};
+use alloy_primitives::{
+    Address, U256,
+    aliases::{I512, U512},
+};
+use contract_standards::{
+    Erc20BalanceKey, Position, PositionedStandardChange, StandardChange, StandardMetadata,
+    StandardStateValues,
+};
+
+mod risk;
+mod units;
+
+pub use contract_standards::{
+    Erc20Metadata, Erc721CollectionMetadata, PERMIT2_UNLIMITED_AMOUNT, ProxySlot,
+};
+pub use risk::{
+    OwnershipTransfer, RiskAccountKind, RiskCode, RiskConfig, RiskInput, RiskRule, RiskSeverity,
+    RiskWarning, approved_operators, assess_risks, risk_accounts,
+};
+pub use units::format_units;
 
 #[derive(Debug, Clone, PartialEq, Eq, Default)]
 pub struct NativeMetadata {
@@ -83,6 +101,32 @@ pub enum Change {
         raw_amount_after: U256,
         metadata: Erc20Metadata,
     },
+    /// An ERC-20 allowance set by an EIP-2612 `permit` signature.
+    Erc20Permit {
+        contract_address: Address,
+        owner: Address,
+        spender: Address,
+        raw_amount_before: U256,
+        raw_amount_after: U256,
+        nonce_before: U256,
+        nonce_after: U256,
+        metadata: Erc20Metadata,
+    },
+    /// An allowance held by the Permit2 contract `contract_address` for the
+    /// ERC-20 `token`.
+    Permit2Allowance {
+        contract_address: Address,
+        token: Address,
+        owner: Address,
+        spender: Address,
+        raw_amount_before: U256,
+        raw_amount_after: U256,
+        expiration_before: u64,
+        expiration_after: u64,
+        nonce_before: u64,
+        nonce_after: u64,
+        metadata: Erc20Metadata,
+    },
     Erc721TokenApproval {
         contract_address: Address,
         token_id: U256,
@@ -105,6 +149,20 @@ pub enum Change {
         approved_before: bool,
         approved_after: bool,
     },
+    /// An EIP-7702 delegation of `authority` was set, replaced or cleared.
+    Delegation {
+        authority: Address,
+        delegate_before: Option<Address>,
+        delegate_after: Option<Address>,
+    },
+    /// An EIP-1967 slot of `proxy` now points to another implementation,
+    /// admin or beacon.
+    ProxyUpgrade {
+        proxy: Address,
+        slot: ProxySlot,
+        address_before: Option<Address>,
+        address_after: Option<Address>,
+    },
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -117,6 +175,10 @@ impl PositionedChange {
     pub const fn new(position: Position, change: Change) -> Self {
         Self { position, change }
     }
+
+    pub const fn position(&self) -> Position {
+        self.position
+    }
 }
 
 impl From<PositionedStandardChange> for PositionedChange {
@@ -241,6 +303,48 @@ impl From<StandardChange> for Change {
                 raw_amount_after,
                 metadata: Erc20Metadata::default(),
             },
+            StandardChange::Erc20Permit {
+                contract_address,
+                owner,
+                spender,
+                raw_amount_before,
+                raw_amount_after,
+                nonce_before,
+                nonce_after,
+            } => Self::Erc20Permit {
+                contract_address,
+                owner,
+                spender,
+                raw_amount_before,
+                raw_amount_after,
+                nonce_before,
+                nonce_after,
+                metadata: Erc20Metadata::default(),
+            },
+            StandardChange::Permit2Allowance {
+                contract_address,
+                token,
+                owner,
+                spender,
+                raw_amount_before,
+                raw_amount_after,
+                expiration_before,
+                expiration_after,
+                nonce_before,
+                nonce_after,
+            } => Self::Permit2Allowance {
+                contract_address,
+                token,
+                owner,
+                spender,
+                raw_amount_before,
+                raw_amount_after,
+                expiration_before,
+                expiration_after,
+                nonce_before,
+                nonce_after,
+                metadata: Erc20Metadata::default(),
+            },
             StandardChange::Erc721TokenApproval {
                 contract_address,
                 token_id,
@@ -323,6 +427,16 @@ impl ChangeMetadata {
                 contract_address,
                 metadata,
                 ..
+            }
+            | Change::Erc20Permit {
+                contract_address,
+                metadata,
+                ..
+            }
+            | Change::Permit2Allowance {
+                token: contract_address,
+                metadata,
+                ..
             } => {
                 *metadata = self
                     .standard
@@ -364,7 +478,9 @@ impl ChangeMetadata {
             Change::Erc1155Transfer { .. }
             | Change::Erc1155Mint { .. }
             | Change::Erc1155Burn { .. }
-            | Change::Erc1155OperatorApproval { .. } => {}
+            | Change::Erc1155OperatorApproval { .. }
+            | Change::Delegation { .. }
+            | Change::ProxyUpgrade { .. } => {}
         }
     }
 }
@@ -385,3 +501,255 @@ pub fn into_enriched_changes(
         })
         .collect()
 }
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ChangeAsset {
+    Native,
+    Erc20 {
+        contract_address: Address,
+    },
+    Erc721 {
+        contract_address: Address,
+        token_id: U256,
+    },
+    Erc1155 {
+        contract_address: Address,
+        token_id: U256,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NetAssetChange {
+    pub account: Address,
+    pub asset: ChangeAsset,
+    pub raw_delta: I512,
+}
+
+/// Sums asset movements into one signed delta per account and asset.
+/// Approvals and delegations do not move assets and are ignored; zero deltas
+/// are dropped.
+///
+/// Deltas are 512-bit so that any number of full-range `uint256` amounts sum
+/// exactly, without clamping.
+pub fn net_asset_changes<'a>(changes: impl IntoIterator<Item = &'a Change>) -> Vec<NetAssetChange> {
+    let mut deltas = BTreeMap::<(Address, ChangeAsset), I512>::new();
+    let mut record = |account: Address, asset: ChangeAsset, amount: I512| {
+        let delta = deltas.entry((account, asset)).or_insert(I512::ZERO);
+        *delta += amount;
+    };
+
+    for change in changes {
+        let Some((asset, from, to, raw_amount)) = asset_movement(change) else {
+            continue;
+        };
+        let amount = I512::from_raw(U512::from(raw_amount));
+
+        if let Some(from) = from {
+            record(from, asset, -amount);
+        }
+
+        if let Some(to) = to {
+            record(to, asset, amount);
+        }
+    }
+
+    deltas
+        .into_iter()
+        .filter(|(_, raw_delta)| !raw_delta.is_zero())
+        .map(|((account, asset), raw_delta)| NetAssetChange {
+            account,
+            asset,
+            raw_delta,
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BalanceChange {
+    pub account: Address,
+    pub asset: ChangeAsset,
+    pub raw_delta: I512,
+    pub raw_balance_before: Option<U256>,
+    pub raw_balance_after: Option<U256>,
+}
+
+/// Groups the asset movements of one transaction by account and asset. ERC-20
+/// entries carry the balances read around the transaction when verifying its
+/// changes; other assets report only the net delta.
+pub fn balance_changes<'a>(
+    changes: impl IntoIterator<Item = &'a Change>,
+    before: &StandardStateValues,
+    after: &StandardStateValues,
+) -> Vec<BalanceChange> {
+    net_asset_changes(changes)
+        .into_iter()
+        .map(|net_change| {
+            let (raw_balance_before, raw_balance_after) = match net_change.asset {
+                ChangeAsset::Erc20 { contract_address } => {
+                    let key = Erc20BalanceKey {
+                        token: contract_address,
+                        account: net_change.account,
+                    };
+                    (
+                        before.erc20_balances.get(&key).copied(),
+                        after.erc20_balances.get(&key).copied(),
+                    )
+                }
+                ChangeAsset::Native | ChangeAsset::Erc721 { .. } | ChangeAsset::Erc1155 { .. } => {
+                    (None, None)
+                }
+            };
+
+            BalanceChange {
+                account: net_change.account,
+                asset: net_change.asset,
+                raw_delta: net_change.raw_delta,
+                raw_balance_before,
+                raw_balance_after,
+            }
+        })
+        .collect()
+}
+
+pub(crate) fn asset_movement(
+    change: &Change,
+) -> Option<(ChangeAsset, Option<Address>, Option<Address>, U256)> {
+    let movement = match *change {
+        Change::NativeTransfer {
+            from,
+            to,
+            raw_amount,
+            ..
+        } => (ChangeAsset::Native, Some(from), Some(to), raw_amount),
+        Change::Erc20Transfer {
+            contract_address,
+            from,
+            to,
+            raw_amount,
+            ..
+        } => (
+            ChangeAsset::Erc20 { contract_address },
+            Some(from),
+            Some(to),
+            raw_amount,
+        ),
+        Change::Erc20Mint {
+            contract_address,
+            to,
+            raw_amount,
+            ..
+        } => (
+            ChangeAsset::Erc20 { contract_address },
+            None,
+            Some(to),
+            raw_amount,
+        ),
+        Change::Erc20Burn {
+            contract_address,
+            from,
+            raw_amount,
+            ..
+        } => (
+            ChangeAsset::Erc20 { contract_address },
+            Some(from),
+            None,
+            raw_amount,
+        ),
+        Change::Erc721Transfer {
+            contract_address,
+            from,
+            to,
+            token_id,
+            ..
+        } => (
+            ChangeAsset::Erc721 {
+                contract_address,
+                token_id,
+            },
+            Some(from),
+            Some(to),
+            U256::from(1),
+        ),
+        Change::Erc721Mint {
+            contract_address,
+            to,
+            token_id,
+            ..
+        } => (
+            ChangeAsset::Erc721 {
+                contract_address,
+                token_id,
+            },
+            None,
+            Some(to),
+            U256::from(1),
+        ),
+        Change::Erc721Burn {
+            contract_address,
+            from,
+            token_id,
+            ..
+        } => (
+            ChangeAsset::Erc721 {
+                contract_address,
+                token_id,
+            },
+            Some(from),
+            None,
+            U256::from(1),
+        ),
+        Change::Erc1155Transfer {
+            contract_address,
+            from,
+            to,
+            token_id,
+            raw_amount,
+        } => (
+            ChangeAsset::Erc1155 {
+                contract_address,
+                token_id,
+            },
+            Some(from),
+            Some(to),
+            raw_amount,
+        ),
+        Change::Erc1155Mint {
+            contract_address,
+            to,
+            token_id,
+            raw_amount,
+        } => (
+            ChangeAsset::Erc1155 {
+                contract_address,
+                token_id,
+            },
+            None,
+            Some(to),
+            raw_amount,
+        ),
+        Change::Erc1155Burn {
+            contract_address,
+            from,
+            token_id,
+            raw_amount,
+        } => (
+            ChangeAsset::Erc1155 {
+                contract_address,
+                token_id,
+            },
+            Some(from),
+            None,
+            raw_amount,
+        ),
+        Change::Erc20Allowance { .. }
+        | Change::Erc20Permit { .. }
+        | Change::Permit2Allowance { .. }
+        | Change::Erc721TokenApproval { .. }
+        | Change::Erc721OperatorApproval { .. }
+        | Change::Erc1155OperatorApproval { .. }
+        | Change::Delegation { .. }
+        | Change::ProxyUpgrade { .. } => return None,
+    };
+
+    Some(movement)
+}
@@ -0,0 +1,454 @@
+//! Rule-based risk warnings for the changes of one transaction.
+
+use std::{
+    collections::{BTreeSet, HashMap},
+    sync::LazyLock,
+};
+
+use alloy_primitives::{Address, B256, U256, keccak256};
+use contract_standards::PERMIT2_UNLIMITED_AMOUNT;
+
+use crate::{BalanceChange, Change, ChangeAsset, asset_movement};
+
+static OWNERSHIP_TRANSFERRED_TOPIC0: LazyLock<B256> =
+    LazyLock::new(|| keccak256("OwnershipTransferred(address,address)"));
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum RiskCode {
+    UnlimitedAllowanceToEoa,
+    ApprovalForAllToFreshContract,
+    NativeValueToCodelessTarget,
+    OwnershipTransfer,
+    WalletDrained,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum RiskSeverity {
+    Low,
+    Medium,
+    High,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RiskWarning {
+    pub code: RiskCode,
+    pub severity: RiskSeverity,
+    /// The spender, operator, recipient, contract or wallet the warning is
+    /// about.
+    pub account: Address,
+    /// Indexes into the transaction's changes that triggered the warning.
+    pub change_indexes: Vec<usize>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RiskRule {
+    pub enabled: bool,
+    pub severity: RiskSeverity,
+}
+
+impl RiskRule {
+    pub const fn new(severity: RiskSeverity) -> Self {
+        Self {
+            enabled: true,
+            severity,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RiskConfig {
+    pub unlimited_allowance_to_eoa: RiskRule,
+    pub approval_for_all_to_fresh_contract: RiskRule,
+    pub native_value_to_codeless_target: RiskRule,
+    pub ownership_transfer: RiskRule,
+    pub wallet_drained: RiskRule,
+    /// Blocks before the simulated one within which a deployed operator
+    /// still counts as fresh for `approval_for_all_to_fresh_contract`.
+    pub fresh_contract_max_age_blocks: u64,
+    /// Number of assets the sender must empty before `wallet_drained` fires.
+    pub wallet_drained_min_assets: usize,
+}
+
+impl Default for RiskConfig {
+    fn default() -> Self {
+        Self {
+            unlimited_allowance_to_eoa: RiskRule::new(RiskSeverity::High),
+            approval_for_all_to_fresh_contract: RiskRule::new(RiskSeverity::High),
+            native_value_to_codeless_target: RiskRule::new(RiskSeverity::Medium),
+            ownership_transfer: RiskRule::new(RiskSeverity::Medium),
+            wallet_drained: RiskRule::new(RiskSeverity::High),
+            // About a day of 12 second blocks.
+            fresh_contract_max_age_blocks: 7_200,
+            wallet_drained_min_assets: 2,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RiskAccountKind {
+    /// No runtime code, or only an EIP-7702 delegation designator.
+    Eoa,
+    Contract {
+        /// The contract was deployed by the simulated transaction or within
+        /// [`RiskConfig::fresh_contract_max_age_blocks`] before its block.
+        fresh: bool,
+    },
+}
+
+/// An `OwnershipTransferred` log emitted by the transaction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OwnershipTransfer {
+    pub contract: Address,
+    pub previous_owner: Address,
+    pub new_owner: Address,
+}
+
+impl OwnershipTransfer {
+    /// Decodes an `OwnershipTransferred(address,address)` log. Renouncing and
+    /// the initial assignment from the zero address are not transfers.
+    pub fn from_log(contract: Address, topics: &[B256], data: &[u8]) -> Option<Self> {
+        if topics.len() != 3 || !data.is_empty() || topics[0] != *OWNERSHIP_TRANSFERRED_TOPIC0 {
+            return None;
+        }
+
+        let previous_owner = topic_address(&topics[1])?;
+        let new_owner = topic_address(&topics[2])?;
+        (previous_owner != Address::ZERO && new_owner != Address::ZERO).then_some(Self {
+            contract,
+            previous_owner,
+            new_owner,
+        })
+    }
+}
+
+/// What the rules know about one simulated transaction beyond its changes.
+#[derive(Debug, Clone, Copy)]
+pub struct RiskInput<'a> {
+    pub sender: Address,
+    /// The top-level call target, when the transaction carried calldata.
+    pub called_target: Option<Address>,
+    pub changes: &'a [Change],
+    pub balance_changes: &'a [BalanceChange],
+    pub ownership_transfers: &'a [OwnershipTransfer],
+    /// Kinds of the accounts returned by [`risk_accounts`], read after the
+    /// transaction.
+    pub accounts: &'a HashMap<Address, RiskAccountKind>,
+}
+
+/// Accounts whose code the rules inspect.
+pub fn risk_accounts(changes: &[Change], called_target: Option<Address>) -> Vec<Address> {
+    let mut accounts = BTreeSet::new();
+    accounts.extend(called_target);
+
+    for change in changes {
+        match *change {
+            Change::Erc20Allowance { spender, .. }
+            | Change::Erc20Permit { spender, .. }
+            | Change::Permit2Allowance { spender, .. } => {
+                accounts.insert(spender);
+            }
+            Change::Erc721OperatorApproval { operator, .. }
+            | Change::Erc1155OperatorApproval { operator, .. } => {
+                accounts.insert(operator);
+            }
+            _ => {}
+        }
+    }
+
+    accounts.into_iter().collect()
+}
+
+/// Operators newly approved for all of an owner's tokens, whose deployment
+/// age `approval_for_all_to_fresh_contract` depends on.
+pub fn approved_operators(changes: &[Change]) -> BTreeSet<Address> {
+    changes
+        .iter()
+        .filter_map(|change| match *change {
+            Change::Erc721OperatorApproval {
+                operator,
+                approved_before: false,
+                approved_after: true,
+                ..
+            }
+            | Change::Erc1155OperatorApproval {
+                operator,
+                approved_before: false,
+                approved_after: true,
+                ..
+            } => Some(operator),
+            _ => None,
+        })
+        .collect()
+}
+
+pub fn assess_risks(config: &RiskConfig, input: &RiskInput<'_>) -> Vec<RiskWarning> {
+    let mut warnings = Vec::new();
+    let account_kind = |account: &Address| input.accounts.get(account).copied();
+
+    for (index, change) in input.changes.iter().enumerate() {
+        match *change {
+            Change::Erc20Allowance {
+                spender,
+                raw_amount_after,
+                ..
+            }
+            | Change::Erc20Permit {
+                spender,
+                raw_amount_after,
+                ..
+            } if raw_amount_after == U256::MAX => {
+                push_eoa_allowance(
+                    &mut warnings,
+                    config,
+                    spender,
+                    index,
+                    account_kind(&spender),
+                );
+            }
+            Change::Permit2Allowance {
+                spender,
+                raw_amount_after,
+                ..
+            } if raw_amount_after == PERMIT2_UNLIMITED_AMOUNT => {
+                push_eoa_allowance(
+                    &mut warnings,
+                    config,
+                    spender,
+                    index,
+                    account_kind(&spender),
+                );
+            }
+            Change::Erc721OperatorApproval {
+                operator,
+                approved_before: false,
+                approved_after: true,
+                ..
+            }
+            | Change::Erc1155OperatorApproval {
+                operator,
+                approved_before: false,
+                approved_after: true,
+                ..
+            } if account_kind(&operator) == Some(RiskAccountKind::Contract { fresh: true }) => {
+                push_warning(
+                    &mut warnings,
+                    config.approval_for_all_to_fresh_contract,
+                    RiskCode::ApprovalForAllToFreshContract,
+                    operator,
+                    vec![index],
+                );
+            }
+            Change::NativeTransfer { from, to, .. }
+                if from == input.sender
+                    && input.called_target == Some(to)
+                    && account_kind(&to) == Some(RiskAccountKind::Eoa) =>
+            {
+                push_warning(
+                    &mut warnings,
+                    config.native_value_to_codeless_target,
+                    RiskCode::NativeValueToCodelessTarget,
+                    to,
+                    vec![index],
+                );
+            }
+            _ => {}
+        }
+    }
+
+    // The transfer is a log, not a change, so no change triggered it.
+    for transfer in input.ownership_transfers {
+        push_warning(
+            &mut warnings,
+            config.ownership_transfer,
+            RiskCode::OwnershipTransfer,
+            transfer.contract,
+            Vec::new(),
+        );
+    }
+
+    push_wallet_drained(&mut warnings, config, input);
+
+    warnings
+}
+
+fn push_eoa_allowance(
+    warnings: &mut Vec<RiskWarning>,
+    config: &RiskConfig,
+    spender: Address,
+    index: usize,
+    kind: Option<RiskAccountKind>,
+) {
+    if kind == Some(RiskAccountKind::Eoa) {
+        push_warning(
+            warnings,
+            config.unlimited_allowance_to_eoa,
+            RiskCode::UnlimitedAllowanceToEoa,
+            spender,
+            vec![index],
+        );
+    }
+}
+
+/// Fires when the sender ends the transaction with a zero balance of several
+/// fungible tokens it held before. Only ERC-20 balances are read around the
+/// transaction, so native funds do not count.
+fn push_wallet_drained(
+    warnings: &mut Vec<RiskWarning>,
+    config: &RiskConfig,
+    input: &RiskInput<'_>,
+) {
+    let emptied = input
+        .balance_changes
+        .iter()
+        .filter(|balance_change| {
+            balance_change.account == input.sender
+                && balance_change
+                    .raw_balance_before
+                    .is_some_and(|balance| !balance.is_zero())
+                && balance_change.raw_balance_after == Some(U256::ZERO)
+        })
+        .map(|balance_change| balance_change.asset)
+        .collect::<BTreeSet<ChangeAsset>>();
+
+    if emptied.is_empty() || emptied.len() < config.wallet_drained_min_assets {
+        return;
+    }
+
+    let change_indexes = input
+        .changes
+        .iter()
+        .enumerate()
+        .filter(|(_, change)| {
+            asset_movement(change).is_some_and(|(asset, from, _, _)| {
+                from == Some(input.sender) && emptied.contains(&asset)
+            })
+        })
+        .map(|(index, _)| index)
+        .collect();
+
+    push_warning(
+        warnings,
+        config.wallet_drained,
+        RiskCode::WalletDrained,
+        input.sender,
+        change_indexes,
+    );
+}
+
+fn push_warning(
+    warnings: &mut Vec<RiskWarning>,
+    rule: RiskRule,
+    code: RiskCode,
+    account: Address,
+    change_indexes: Vec<usize>,
+) {
+    if rule.enabled {
+        warnings.push(RiskWarning {
+            code,
+            severity: rule.severity,
+            account,
+            change_indexes,
+        });
+    }
+}
+
+fn topic_address(topic: &B256) -> Option<Address> {
+    topic.as_slice()[..12]
+        .iter()
+        .all(|byte| *byte == 0)
+        .then(|| Address::from_word(*topic))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Erc721CollectionMetadata;
+
+    const SENDER: Address = Address::repeat_byte(0x11);
+    const OPERATOR: Address = Address::repeat_byte(0x22);
+    const COLLECTION: Address = Address::repeat_byte(0x33);
+
+    fn operator_approval() -> Change {
+        Change::Erc721OperatorApproval {
+            contract_address: COLLECTION,
+            owner: SENDER,
+            operator: OPERATOR,
+            approved_before: false,
+            approved_after: true,
+            metadata: Erc721CollectionMetadata::default(),
+        }
+    }
+
+    fn assess(
+        changes: &[Change],
+        ownership_transfers: &[OwnershipTransfer],
+        accounts: &HashMap<Address, RiskAccountKind>,
+    ) -> Vec<RiskWarning> {
+        assess_risks(
+            &RiskConfig::default(),
+            &RiskInput {
+                sender: SENDER,
+                called_target: None,
+                changes,
+                balance_changes: &[],
+                ownership_transfers,
+                accounts,
+            },
+        )
+    }
+
+    #[test]
+    fn warns_about_approval_for_all_to_a_recently_deployed_operator() {
+        // The operator existed before the transaction but was deployed within
+        // the freshness window.
+        let changes = [operator_approval()];
+        let accounts = HashMap::from([(OPERATOR, RiskAccountKind::Contract { fresh: true })]);
+
+        assert_eq!(approved_operators(&changes), BTreeSet::from([OPERATOR]));
+        assert_eq!(
+            assess(&changes, &[], &accounts),
+            vec![RiskWarning {
+                code: RiskCode::ApprovalForAllToFreshContract,
+                severity: RiskSeverity::High,
+                account: OPERATOR,
+                change_indexes: vec![0],
+            }]
+        );
+    }
+
+    #[test]
+    fn ignores_approval_for_all_to_an_established_operator() {
+        let changes = [operator_approval()];
+        let accounts = HashMap::from([(OPERATOR, RiskAccountKind::Contract { fresh: false })]);
+
+        assert_eq!(assess(&changes, &[], &accounts), Vec::new());
+    }
+
+    #[test]
+    fn ownership_transfer_points_at_no_change() {
+        let previous_owner = Address::repeat_byte(0x44);
+        let new_owner = Address::repeat_byte(0x55);
+        let transfer = OwnershipTransfer::from_log(
+            COLLECTION,
+            &[
+                *OWNERSHIP_TRANSFERRED_TOPIC0,
+                previous_owner.into_word(),
+                new_owner.into_word(),
+            ],
+            &[],
+        )
+        .expect("ownership transfer log");
+        let changes = [operator_approval()];
+        let accounts = HashMap::from([(OPERATOR, RiskAccountKind::Contract { fresh: false })]);
+
+        assert_eq!(
+            assess(&changes, &[transfer], &accounts),
+            vec![RiskWarning {
+                code: RiskCode::OwnershipTransfer,
+                severity: RiskSeverity::Medium,
+                account: COLLECTION,
+                change_indexes: Vec::new(),
+            }]
+        );
+    }
+}
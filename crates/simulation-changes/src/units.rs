@@ -0,0 +1,20 @@
+use std::fmt;
+
+/// Formats a raw integer amount as an exact decimal string, without trailing
+/// fractional zeroes.
+pub fn format_units(raw: impl fmt::Display, decimals: u8) -> String {
+    let digits = raw.to_string();
+    let decimals = usize::from(decimals);
+    if decimals == 0 {
+        return digits;
+    }
+
+    let padded = format!("{digits:0>width$}", width = decimals + 1);
+    let (integer, fraction) = padded.split_at(padded.len() - decimals);
+    let fraction = fraction.trim_end_matches('0');
+    if fraction.is_empty() {
+        integer.to_string()
+    } else {
+        format!("{integer}.{fraction}")
+    }
+}
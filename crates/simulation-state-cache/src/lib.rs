@@ -0,0 +1,132 @@
+use std::{
+    fmt,
+    hash::Hash,
+    num::NonZeroUsize,
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+};
+
+use lru::LruCache;
+
+mod snapshot;
+
+pub use snapshot::{SnapshotMode, SnapshotStore};
+
+/// Entries kept per kind of read when no capacity is configured.
+pub const DEFAULT_STATE_CACHE_ENTRIES: NonZeroUsize = NonZeroUsize::new(100_000).unwrap();
+
+/// Bytes of values kept per kind of read when no budget is configured.
+pub const DEFAULT_STATE_CACHE_BYTES: NonZeroUsize = NonZeroUsize::new(256 * 1024 * 1024).unwrap();
+
+/// Bounds of one kind of cached read. Entry counts alone would let a cache of
+/// contract code grow to gigabytes, so the values are bounded by size too.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StateCacheLimits {
+    pub entries: NonZeroUsize,
+    /// Total size of the cached values, as measured by the cache's weigher.
+    pub bytes: NonZeroUsize,
+}
+
+impl Default for StateCacheLimits {
+    fn default() -> Self {
+        Self {
+            entries: DEFAULT_STATE_CACHE_ENTRIES,
+            bytes: DEFAULT_STATE_CACHE_BYTES,
+        }
+    }
+}
+
+/// A least-recently-used map of upstream state reads shared by every clone.
+///
+/// Values are only valid for the state they were read at, so keys must
+/// include the block or epoch anchor of the read.
+pub struct SharedStateCache<K, V> {
+    inner: Arc<Mutex<Inner<K, V>>>,
+}
+
+struct Inner<K, V> {
+    entries: LruCache<K, V>,
+    weigh: fn(&V) -> usize,
+    weight: usize,
+    max_weight: usize,
+}
+
+impl<K, V> SharedStateCache<K, V>
+where
+    K: Hash + Eq,
+    V: Clone,
+{
+    /// Bounds the cache by entry count and by the total size of its values as
+    /// measured by `weigh`, evicting least recently used entries past either
+    /// bound.
+    pub fn new(limits: StateCacheLimits, weigh: fn(&V) -> usize) -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(Inner {
+                entries: LruCache::new(limits.entries),
+                weigh,
+                weight: 0,
+                max_weight: limits.bytes.get(),
+            })),
+        }
+    }
+
+    pub fn get(&self, key: &K) -> Option<V> {
+        self.lock().entries.get(key).cloned()
+    }
+
+    pub fn contains(&self, key: &K) -> bool {
+        self.lock().entries.contains(key)
+    }
+
+    /// Values heavier than the whole budget are not cached.
+    pub fn insert(&self, key: K, value: V) {
+        self.lock().insert(key, value);
+    }
+
+    // Entries are inserted whole, so a panic while the lock was held cannot
+    // leave a partial value behind.
+    fn lock(&self) -> MutexGuard<'_, Inner<K, V>> {
+        self.inner.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl<K, V> Inner<K, V>
+where
+    K: Hash + Eq,
+{
+    fn insert(&mut self, key: K, value: V) {
+        let weight = (self.weigh)(&value);
+        if weight > self.max_weight {
+            if let Some(replaced) = self.entries.pop(&key) {
+                self.weight -= (self.weigh)(&replaced);
+            }
+            return;
+        }
+
+        self.weight += weight;
+        if let Some((_, evicted)) = self.entries.push(key, value) {
+            self.weight -= (self.weigh)(&evicted);
+        }
+        while self.weight > self.max_weight {
+            let Some((_, evicted)) = self.entries.pop_lru() else {
+                break;
+            };
+            self.weight -= (self.weigh)(&evicted);
+        }
+    }
+}
+
+impl<K, V> Clone for SharedStateCache<K, V> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: Arc::clone(&self.inner),
+        }
+    }
+}
+
+impl<K, V> fmt::Debug for SharedStateCache<K, V> {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("SharedStateCache")
+            .finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,158 @@
+use std::{
+    collections::HashMap,
+    fs, io,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex, PoisonError},
+};
+
+use serde::{Serialize, de::DeserializeOwned};
+
+/// Whether simulations record their state reads into a snapshot store or
+/// read their state back from it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SnapshotMode {
+    #[default]
+    Record,
+    Replay,
+}
+
+/// A directory of JSON snapshots, one file per key.
+#[derive(Debug, Clone)]
+pub struct SnapshotStore {
+    directory: PathBuf,
+    // Serializes read-merge-write updates of one key made through clones of
+    // the store. Updates of different keys run concurrently.
+    key_locks: Arc<Mutex<HashMap<String, Arc<Mutex<()>>>>>,
+}
+
+impl SnapshotStore {
+    pub fn new(directory: impl Into<PathBuf>) -> Self {
+        Self {
+            directory: directory.into(),
+            key_locks: Arc::default(),
+        }
+    }
+
+    pub fn directory(&self) -> &Path {
+        &self.directory
+    }
+
+    pub fn load<T: DeserializeOwned>(&self, key: &str) -> io::Result<Option<T>> {
+        let bytes = match fs::read(self.path(key)) {
+            Ok(bytes) => bytes,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(error) => return Err(error),
+        };
+
+        serde_json::from_slice(&bytes)
+            .map(Some)
+            .map_err(io::Error::other)
+    }
+
+    /// Loads the snapshot stored under `key`, or starts from `value` when
+    /// there is none, merges `value` into it and writes the result back.
+    pub fn merge<T>(&self, key: &str, value: T, merge: impl FnOnce(&mut T, T)) -> io::Result<()>
+    where
+        T: Serialize + DeserializeOwned,
+    {
+        let key_lock = self.key_lock(key);
+        let _guard = key_lock.lock().unwrap_or_else(PoisonError::into_inner);
+        let merged = match self.load::<T>(key)? {
+            Some(mut stored) => {
+                merge(&mut stored, value);
+                stored
+            }
+            None => value,
+        };
+
+        self.write(key, &merged)
+    }
+
+    fn key_lock(&self, key: &str) -> Arc<Mutex<()>> {
+        let mut key_locks = self
+            .key_locks
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        Arc::clone(key_locks.entry(key.to_owned()).or_default())
+    }
+
+    fn write<T: Serialize>(&self, key: &str, value: &T) -> io::Result<()> {
+        fs::create_dir_all(&self.directory)?;
+        let bytes = serde_json::to_vec(value).map_err(io::Error::other)?;
+        // Readers never see a partially written snapshot.
+        let temporary_path = self.directory.join(format!(".{key}.json.tmp"));
+        fs::write(&temporary_path, bytes)?;
+        fs::rename(temporary_path, self.path(key))
+    }
+
+    fn path(&self, key: &str) -> PathBuf {
+        self.directory.join(format!("{key}.json"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::BTreeMap, process, thread};
+
+    use super::*;
+
+    type Reads = BTreeMap<u64, u64>;
+
+    fn test_store(name: &str) -> SnapshotStore {
+        let directory =
+            std::env::temp_dir().join(format!("simulation-state-cache-{name}-{}", process::id()));
+        let _ = fs::remove_dir_all(&directory);
+        SnapshotStore::new(directory)
+    }
+
+    fn merge_reads(stored: &mut Reads, reads: Reads) {
+        stored.extend(reads);
+    }
+
+    #[test]
+    fn loads_nothing_for_an_unrecorded_key() {
+        let store = test_store("unrecorded");
+
+        assert_eq!(store.load::<Reads>("block").unwrap(), None);
+    }
+
+    #[test]
+    fn merges_recorded_reads_into_the_stored_snapshot() {
+        let store = test_store("merge");
+
+        store
+            .merge("block", Reads::from([(1, 10), (2, 20)]), merge_reads)
+            .unwrap();
+        store
+            .merge("block", Reads::from([(2, 21), (3, 30)]), merge_reads)
+            .unwrap();
+
+        assert_eq!(
+            store.load::<Reads>("block").unwrap(),
+            Some(Reads::from([(1, 10), (2, 21), (3, 30)]))
+        );
+        fs::remove_dir_all(store.directory()).unwrap();
+    }
+
+    #[test]
+    fn keeps_every_concurrent_update_of_a_key() {
+        let store = test_store("concurrent");
+
+        thread::scope(|scope| {
+            for read in 0..8 {
+                let store = store.clone();
+                scope.spawn(move || {
+                    store
+                        .merge("block", Reads::from([(read, read)]), merge_reads)
+                        .unwrap();
+                });
+            }
+        });
+
+        assert_eq!(
+            store.load::<Reads>("block").unwrap(),
+            Some((0..8).map(|read| (read, read)).collect())
+        );
+        fs::remove_dir_all(store.directory()).unwrap();
+    }
+}
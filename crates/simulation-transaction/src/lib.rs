@@ -1,7 +1,7 @@
 use std::fmt;
 
-pub use alloy_eips::eip2930::AccessListItem;
-use alloy_primitives::{Address, Bytes, U256};
+pub use alloy_eips::{eip2930::AccessListItem, eip7702::SignedAuthorization};
+use alloy_primitives::{Address, B256, Bytes, U256};
 use thiserror::Error;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -33,6 +33,8 @@ pub enum TransactionType {
     Legacy,
     AccessList,
     DynamicFee,
+    Blob,
+    SetCode,
 }
 
 impl fmt::Display for TransactionType {
@@ -41,6 +43,8 @@ impl fmt::Display for TransactionType {
             Self::Legacy => "legacy",
             Self::AccessList => "access-list",
             Self::DynamicFee => "dynamic-fee",
+            Self::Blob => "blob",
+            Self::SetCode => "set-code",
         })
     }
 }
@@ -56,6 +60,19 @@ impl TransactionType {
     }
 }
 
+/// Type-specific fields of a transaction request before the variant is
+/// chosen.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TransactionVariantFields {
+    pub access_list: Option<Vec<AccessListItem>>,
+    pub gas_price: Option<u128>,
+    pub max_fee_per_gas: Option<u128>,
+    pub max_priority_fee_per_gas: Option<u128>,
+    pub max_fee_per_blob_gas: Option<u128>,
+    pub blob_versioned_hashes: Option<Vec<B256>>,
+    pub authorization_list: Option<Vec<SignedAuthorization>>,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum TransactionVariantRequest {
     Legacy {
@@ -70,17 +87,45 @@ pub enum TransactionVariantRequest {
         max_priority_fee_per_gas: Option<u128>,
         access_list: Vec<AccessListItem>,
     },
+    Blob {
+        max_fee_per_gas: Option<u128>,
+        max_priority_fee_per_gas: Option<u128>,
+        max_fee_per_blob_gas: Option<u128>,
+        access_list: Vec<AccessListItem>,
+        blob_versioned_hashes: Vec<B256>,
+    },
+    SetCode {
+        max_fee_per_gas: Option<u128>,
+        max_priority_fee_per_gas: Option<u128>,
+        access_list: Vec<AccessListItem>,
+        authorization_list: Vec<SignedAuthorization>,
+    },
 }
 
 impl TransactionVariantRequest {
     pub fn try_new(
         transaction_type: TransactionType,
-        access_list: Option<Vec<AccessListItem>>,
-        gas_price: Option<u128>,
-        max_fee_per_gas: Option<u128>,
-        max_priority_fee_per_gas: Option<u128>,
+        fields: TransactionVariantFields,
     ) -> Result<Self, TransactionVariantError> {
+        let TransactionVariantFields {
+            access_list,
+            gas_price,
+            max_fee_per_gas,
+            max_priority_fee_per_gas,
+            max_fee_per_blob_gas,
+            blob_versioned_hashes,
+            authorization_list,
+        } = fields;
         let has_dynamic_fee = max_fee_per_gas.is_some() || max_priority_fee_per_gas.is_some();
+        let has_blob_fields = max_fee_per_blob_gas.is_some() || blob_versioned_hashes.is_some();
+
+        if has_blob_fields && transaction_type != TransactionType::Blob {
+            return Err(TransactionVariantError::BlobFieldsNotAllowed { transaction_type });
+        }
+
+        if authorization_list.is_some() && transaction_type != TransactionType::SetCode {
+            return Err(TransactionVariantError::AuthorizationListNotAllowed { transaction_type });
+        }
 
         match transaction_type {
             TransactionType::Legacy => {
@@ -115,6 +160,41 @@ impl TransactionVariantRequest {
                     access_list: access_list.unwrap_or_default(),
                 })
             }
+            TransactionType::Blob => {
+                if gas_price.is_some() {
+                    return Err(TransactionVariantError::GasPriceNotAllowed { transaction_type });
+                }
+
+                let blob_versioned_hashes = blob_versioned_hashes.unwrap_or_default();
+                if blob_versioned_hashes.is_empty() {
+                    return Err(TransactionVariantError::BlobVersionedHashesRequired);
+                }
+
+                Ok(Self::Blob {
+                    max_fee_per_gas,
+                    max_priority_fee_per_gas,
+                    max_fee_per_blob_gas,
+                    access_list: access_list.unwrap_or_default(),
+                    blob_versioned_hashes,
+                })
+            }
+            TransactionType::SetCode => {
+                if gas_price.is_some() {
+                    return Err(TransactionVariantError::GasPriceNotAllowed { transaction_type });
+                }
+
+                let authorization_list = authorization_list.unwrap_or_default();
+                if authorization_list.is_empty() {
+                    return Err(TransactionVariantError::AuthorizationListRequired);
+                }
+
+                Ok(Self::SetCode {
+                    max_fee_per_gas,
+                    max_priority_fee_per_gas,
+                    access_list: access_list.unwrap_or_default(),
+                    authorization_list,
+                })
+            }
         }
     }
 
@@ -123,6 +203,8 @@ impl TransactionVariantRequest {
             Self::Legacy { .. } => TransactionType::Legacy,
             Self::AccessList { .. } => TransactionType::AccessList,
             Self::DynamicFee { .. } => TransactionType::DynamicFee,
+            Self::Blob { .. } => TransactionType::Blob,
+            Self::SetCode { .. } => TransactionType::SetCode,
         }
     }
 }
@@ -141,6 +223,19 @@ pub enum TransactionVariant {
         max_priority_fee_per_gas: u128,
         access_list: Vec<AccessListItem>,
     },
+    Blob {
+        max_fee_per_gas: u128,
+        max_priority_fee_per_gas: u128,
+        max_fee_per_blob_gas: u128,
+        access_list: Vec<AccessListItem>,
+        blob_versioned_hashes: Vec<B256>,
+    },
+    SetCode {
+        max_fee_per_gas: u128,
+        max_priority_fee_per_gas: u128,
+        access_list: Vec<AccessListItem>,
+        authorization_list: Vec<SignedAuthorization>,
+    },
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Error)]
@@ -153,4 +248,19 @@ pub enum TransactionVariantError {
 
     #[error("{transaction_type} transactions cannot include a gas price")]
     GasPriceNotAllowed { transaction_type: TransactionType },
+
+    #[error("{transaction_type} transactions cannot include blob fields")]
+    BlobFieldsNotAllowed { transaction_type: TransactionType },
+
+    #[error("{transaction_type} transactions cannot include an authorization list")]
+    AuthorizationListNotAllowed { transaction_type: TransactionType },
+
+    #[error("blob transactions must include at least one blob versioned hash")]
+    BlobVersionedHashesRequired,
+
+    #[error("set-code transactions must include at least one authorization")]
+    AuthorizationListRequired,
+
+    #[error("{transaction_type} transactions are not supported")]
+    Unsupported { transaction_type: TransactionType },
 }
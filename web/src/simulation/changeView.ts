@@ -4,6 +4,7 @@ import {
   formatRawAmount,
 } from '../lib/formatting.ts';
 import { getEnvironment, type EnvironmentId } from './environment.ts';
+import type { ProxySlot } from './rpc.ts';
 import type { SimulationChange } from './types.ts';
 
 export type ChangeTone = 'amber' | 'blue' | 'green' | 'red' | 'violet';
@@ -53,6 +54,24 @@ export function toChangeItemViewModel(
             : 'amber',
         value: formatAllowanceDelta(change),
       };
+    case 'PERMIT':
+      return {
+        detail: `Signature nonce ${formatHexQuantity(change.nonceBefore)}`,
+        identifier: change.contractAddress,
+        label: 'Permit',
+        title: tokenName(change, 'ERC-20'),
+        tone: 'amber',
+        value: formatAllowanceDelta(change),
+      };
+    case 'PERMIT2_ALLOWANCE':
+      return {
+        detail: formatPermit2Expiration(change.expirationAfter),
+        identifier: change.contractAddress,
+        label: 'Permit2 allowance',
+        title: tokenName(change, 'ERC-20'),
+        tone: 'amber',
+        value: formatAllowanceDelta(change),
+      };
     case 'TOKEN_APPROVAL':
       return {
         detail: `Token #${formatHexQuantity(change.tokenId)}`,
@@ -73,6 +92,22 @@ export function toChangeItemViewModel(
         tone: 'green',
         value: booleanTransition(change.approvedBefore, change.approvedAfter),
       };
+    case 'DELEGATION':
+      return {
+        identifier: change.authority,
+        label: 'Delegation',
+        title: 'EIP-7702 code',
+        tone: change.delegateAfter ? 'violet' : 'amber',
+        value: delegationTransition(change.delegateBefore, change.delegateAfter),
+      };
+    case 'PROXY_UPGRADE':
+      return {
+        identifier: change.proxy,
+        label: 'Proxy upgrade',
+        title: PROXY_SLOT_TITLES[change.slot],
+        tone: change.slot === 'ADMIN' ? 'red' : 'amber',
+        value: proxyTransition(change.addressBefore, change.addressAfter),
+      };
     case 'STAKING_DEPOSIT':
       return coreAmountChange(
         'Staking deposit',
@@ -255,7 +290,10 @@ function formatTokenAmount(rawAmount: string, decimals = 0) {
 }
 
 function formatAllowanceDelta(
-  change: Extract<SimulationChange, { changeType: 'ALLOWANCE' }>,
+  change: Extract<
+    SimulationChange,
+    { changeType: 'ALLOWANCE' | 'PERMIT' | 'PERMIT2_ALLOWANCE' }
+  >,
 ) {
   const delta =
     BigInt(change.rawAmountAfter) - BigInt(change.rawAmountBefore);
@@ -269,12 +307,34 @@ function formatAllowanceDelta(
   return `${delta > 0n ? '+' : '-'}${amount}${symbol ? ` ${symbol}` : ''}`;
 }
 
+function formatPermit2Expiration(expiration: string) {
+  const seconds = Number(BigInt(expiration));
+  if (seconds === 0) return 'No expiry';
+  return `Expires ${new Date(seconds * 1000).toISOString()}`;
+}
+
 function addressTransition(before: string | null, after: string | null) {
   if (before === after) return after ? 'Unchanged' : 'None';
   if (!after) return 'Revoked';
   return before ? 'Changed' : 'Approved';
 }
 
+function delegationTransition(before: string | null, after: string | null) {
+  if (!after) return 'Cleared';
+  return before ? 'Changed' : 'Delegated';
+}
+
+const PROXY_SLOT_TITLES: Record<ProxySlot, string> = {
+  ADMIN: 'Proxy admin',
+  BEACON: 'Proxy beacon',
+  IMPLEMENTATION: 'Proxy implementation',
+};
+
+function proxyTransition(before: string | null, after: string | null) {
+  if (!after) return 'Cleared';
+  return before ? 'Changed' : 'Set';
+}
+
 function sponsorTransition(before: string | null, after: string | null) {
   if (before === after) return after ? 'Sponsor unchanged' : 'No sponsor';
   if (!after) return 'Sponsor removed';
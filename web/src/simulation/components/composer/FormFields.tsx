@@ -159,6 +159,7 @@ export function ContextModeField({
               <SelectItem value="latest">Latest</SelectItem>
               {environmentId === 'ethereum-mainnet' ? (
                 <>
+                  <SelectItem value="pending">Pending</SelectItem>
                   <SelectItem value="safe">Safe</SelectItem>
                   <SelectItem value="finalized">Finalized</SelectItem>
                 </>
@@ -2,12 +2,14 @@ import { formatAmount, formatNativeAmount } from '../../../lib/formatting.ts';
 import { getEnvironment } from '../../environment.ts';
 import {
   isAssetFlowChange,
+  isSameFlowAsset,
   normalizeAddress,
   toAssetFlowItemViewModel,
   type AssetFlowItemViewModel,
   type ChangeAddressViewModel,
   type FlowEndpoint,
 } from '../../flowView.ts';
+import type { BalanceChange } from '../../rpc.ts';
 import type { SimulationRecord } from '../../types.ts';
 import type {
   ExecutionAnchor,
@@ -43,6 +45,9 @@ export function createSimulationResultViewModel(
         record.response.execution.fee,
         environment.nativeSymbol,
       ),
+      'balanceChanges' in record.response
+        ? record.response.balanceChanges
+        : undefined,
     ),
     stateEffects,
   };
@@ -147,12 +152,58 @@ function senderNetImpacts(
   flowItems: readonly AssetFlowItemViewModel[],
   sender: string,
   fee: string,
+  balanceChanges?: readonly BalanceChange[],
 ): SenderImpactItem[] {
+  const balances = balanceChanges
+    ? senderSummaryBalances(flowItems, sender, balanceChanges)
+    : senderFlowBalances(flowItems, sender);
+
+  const impacts: SenderImpactItem[] = [];
+  for (const balance of balances.values()) {
+    if (balance.amount === 0n) continue;
+    const received = balance.amount > 0n;
+    impacts.push({
+      label: received ? 'Received' : 'Sent',
+      tone: received ? 'positive' : 'negative',
+      value: formatSenderNetValue(balance.amount, balance.item),
+    });
+  }
+  impacts.push({ label: 'Fee', tone: 'neutral', value: fee });
+  return impacts;
+}
+
+type SenderBalances = Map<
+  string,
+  { amount: bigint; item: AssetFlowItemViewModel }
+>;
+
+// Reads the sender's net deltas from the server-side balance summary. The
+// flow items only provide the asset display metadata.
+function senderSummaryBalances(
+  flowItems: readonly AssetFlowItemViewModel[],
+  sender: string,
+  balanceChanges: readonly BalanceChange[],
+): SenderBalances {
+  const normalizedSender = normalizeAddress(sender);
+  const balances: SenderBalances = new Map();
+
+  for (const change of balanceChanges) {
+    if (normalizeAddress(change.account) !== normalizedSender) continue;
+    const item = flowItems.find((candidate) =>
+      isSameFlowAsset(candidate, change),
+    );
+    if (!item) continue;
+    balances.set(item.assetKey, { amount: BigInt(change.rawDelta), item });
+  }
+  return balances;
+}
+
+function senderFlowBalances(
+  flowItems: readonly AssetFlowItemViewModel[],
+  sender: string,
+): SenderBalances {
   const normalizedSender = normalizeAddress(sender);
-  const balances = new Map<
-    string,
-    { amount: bigint; item: AssetFlowItemViewModel }
-  >();
+  const balances: SenderBalances = new Map();
 
   for (const item of flowItems) {
     let amount = 0n;
@@ -177,19 +228,7 @@ function senderNetImpacts(
       balances.set(item.assetKey, { amount, item });
     }
   }
-
-  const impacts: SenderImpactItem[] = [];
-  for (const balance of balances.values()) {
-    if (balance.amount === 0n) continue;
-    const received = balance.amount > 0n;
-    impacts.push({
-      label: received ? 'Received' : 'Sent',
-      tone: received ? 'positive' : 'negative',
-      value: formatSenderNetValue(balance.amount, balance.item),
-    });
-  }
-  impacts.push({ label: 'Fee', tone: 'neutral', value: fee });
-  return impacts;
+  return balances;
 }
 
 function formatSenderNetValue(
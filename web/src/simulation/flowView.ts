@@ -3,6 +3,7 @@ import {
   type ChangeTone,
 } from './changeView.ts';
 import { getEnvironment, type EnvironmentId } from './environment.ts';
+import type { BalanceChange } from './rpc.ts';
 import type { SimulationChange } from './types.ts';
 
 export interface ChangeAddressViewModel {
@@ -135,11 +136,19 @@ export function getChangeAddresses(
         { address: change.from, label: 'From' },
       ]);
     case 'ALLOWANCE':
+    case 'PERMIT':
       return [
         { address: change.owner, label: 'Owner' },
         { address: change.spender, label: 'Spender' },
         { address: change.contractAddress, label: 'Asset contract' },
       ];
+    case 'PERMIT2_ALLOWANCE':
+      return [
+        { address: change.owner, label: 'Owner' },
+        { address: change.spender, label: 'Spender' },
+        { address: change.contractAddress, label: 'Asset contract' },
+        { address: change.permit2Address, label: 'Permit2 contract' },
+      ];
     case 'TOKEN_APPROVAL':
       return compactAddresses([
         change.approvedAddressBefore
@@ -156,6 +165,26 @@ export function getChangeAddresses(
         { address: change.operator, label: 'Operator' },
         { address: change.contractAddress, label: 'Asset contract' },
       ];
+    case 'DELEGATION':
+      return compactAddresses([
+        { address: change.authority, label: 'Authority' },
+        change.delegateBefore
+          ? { address: change.delegateBefore, label: 'Delegate before' }
+          : null,
+        change.delegateAfter
+          ? { address: change.delegateAfter, label: 'Delegate after' }
+          : null,
+      ]);
+    case 'PROXY_UPGRADE':
+      return compactAddresses([
+        { address: change.proxy, label: 'Proxy' },
+        change.addressBefore
+          ? { address: change.addressBefore, label: 'Address before' }
+          : null,
+        change.addressAfter
+          ? { address: change.addressAfter, label: 'Address after' }
+          : null,
+      ]);
     case 'STAKING_DEPOSIT':
     case 'STAKING_WITHDRAWAL':
     case 'STAKING_BURN':
@@ -207,6 +236,26 @@ export function normalizeAddress(address: string) {
   return address.toLowerCase();
 }
 
+// Native movements are keyed by symbol, so a balance change matches any
+// native flow item.
+export function isSameFlowAsset(
+  item: AssetFlowItemViewModel,
+  change: BalanceChange,
+) {
+  switch (change.assetType) {
+    case 'NATIVE':
+      return item.assetKey.startsWith('NATIVE:');
+    case 'ERC20':
+      return item.assetKey === `ERC20:${normalizeAddress(change.contractAddress)}`;
+    case 'ERC721':
+    case 'ERC1155':
+      return (
+        item.assetKey ===
+        `${change.assetType}:${normalizeAddress(change.contractAddress)}:${change.tokenId}`
+      );
+  }
+}
+
 function addressEndpoint(
   label: string,
   address: string,
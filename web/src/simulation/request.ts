@@ -310,7 +310,9 @@ function validateRelationships(
 
   if (
     environmentId !== 'ethereum-mainnet' &&
-    (values.contextMode === 'safe' || values.contextMode === 'finalized')
+    (values.contextMode === 'pending' ||
+      values.contextMode === 'safe' ||
+      values.contextMode === 'finalized')
   ) {
     issues.push('This environment only supports Latest or a specific number.');
   }
@@ -77,6 +77,22 @@ export interface AllowanceChange extends FungibleAssetMetadata {
   spender: string;
 }
 
+export interface PermitChange extends Omit<AllowanceChange, 'changeType'> {
+  changeType: 'PERMIT';
+  nonceBefore: string;
+  nonceAfter: string;
+}
+
+export interface Permit2AllowanceChange
+  extends Omit<AllowanceChange, 'changeType'> {
+  changeType: 'PERMIT2_ALLOWANCE';
+  permit2Address: string;
+  expirationBefore: string;
+  expirationAfter: string;
+  nonceBefore: string;
+  nonceAfter: string;
+}
+
 export interface TokenApprovalChange extends AssetMetadata {
   changeType: 'TOKEN_APPROVAL';
   assetType: 'ERC721';
@@ -106,16 +122,35 @@ export interface Erc1155OperatorApprovalChange {
   approvedAfter: boolean;
 }
 
+export interface DelegationChange {
+  changeType: 'DELEGATION';
+  authority: string;
+  delegateBefore: string | null;
+  delegateAfter: string | null;
+}
+
+export type ProxySlot = 'IMPLEMENTATION' | 'ADMIN' | 'BEACON';
+
+export interface ProxyUpgradeChange {
+  changeType: 'PROXY_UPGRADE';
+  proxy: string;
+  slot: ProxySlot;
+  addressBefore: string | null;
+  addressAfter: string | null;
+}
+
 type CommonChange =
   | TransferChange
   | MintChange
   | BurnChange
   | AllowanceChange
+  | PermitChange
+  | Permit2AllowanceChange
   | TokenApprovalChange
   | Erc721OperatorApprovalChange
   | Erc1155OperatorApprovalChange;
 
-export type HexChange = CommonChange;
+export type HexChange = CommonChange | DelegationChange | ProxyUpgradeChange;
 
 export interface StakingDepositChange {
   changeType: 'STAKING_DEPOSIT';
@@ -307,9 +342,41 @@ export interface CoreExecution {
   failure: ExecutionFailure | null;
 }
 
+export type BalanceChangeAsset =
+  | { assetType: 'NATIVE' }
+  | { assetType: 'ERC20'; contractAddress: string }
+  | {
+      assetType: 'ERC721' | 'ERC1155';
+      contractAddress: string;
+      tokenId: string;
+    };
+
+export type BalanceChange = BalanceChangeAsset & {
+  account: string;
+  rawDelta: string;
+  rawBalanceBefore?: string;
+  rawBalanceAfter?: string;
+};
+
+export type RiskCode =
+  | 'UNLIMITED_ALLOWANCE_TO_EOA'
+  | 'APPROVAL_FOR_ALL_TO_FRESH_CONTRACT'
+  | 'NATIVE_VALUE_TO_CODELESS_TARGET'
+  | 'OWNERSHIP_TRANSFER'
+  | 'WALLET_DRAINED';
+
+export interface RiskWarning {
+  code: RiskCode;
+  severity: 'LOW' | 'MEDIUM' | 'HIGH';
+  account: string;
+  changeIndexes: string[];
+}
+
 export interface EthereumResponse {
   execution: EvmExecution;
   changes: HexChange[];
+  balanceChanges?: BalanceChange[];
+  warnings?: RiskWarning[];
 }
 
 export interface EspaceResponse {
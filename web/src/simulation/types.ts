@@ -11,7 +11,12 @@ export type TxTypeOption =
   | 'access-list'
   | 'dynamic-fee';
 
-export type ContextMode = 'latest' | 'safe' | 'finalized' | 'number';
+export type ContextMode =
+  | 'latest'
+  | 'pending'
+  | 'safe'
+  | 'finalized'
+  | 'number';
 
 export interface SimulationFormValues {
   from: string;